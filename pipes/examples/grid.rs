@@ -0,0 +1,126 @@
+use geo::{coord, LineString, Rect};
+use nannou::{
+  prelude::{Key, Vec2, BLACK, WHITE},
+  App,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use utils::app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork};
+use utils::geometry::{
+  curve::{sample_adaptive, QuadraticBezier},
+  grid::{Grid, GridKind, HexOrientation},
+};
+
+const CURVE_TOLERANCE: f32 = 0.5;
+const GRID_KINDS: [GridKind; 4] = [
+  GridKind::Square,
+  GridKind::Hexagonal(HexOrientation::Flat),
+  GridKind::Hexagonal(HexOrientation::Pointy),
+  GridKind::Triangular,
+];
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  depth: usize,
+  line_width: f32,
+  grid_kind: usize,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      depth: 4,
+      line_width: 10.0,
+      grid_kind: 1,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    format!(
+      "frame_{}_{}_{}",
+      self.base_model.seed, self.depth, self.grid_kind
+    )
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Up => self.depth += 1,
+      Key::Down if self.depth > 0 => self.depth -= 1,
+      Key::Right => self.line_width += 1.0,
+      Key::Left if self.line_width > 1.0 => self.line_width -= 1.0,
+      Key::G => self.grid_kind = (self.grid_kind + 1) % GRID_KINDS.len(),
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+
+    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
+
+    let [w_w, w_h] = self.base_model.texture.size();
+    let w = w_w as f32 * 0.9;
+    let h = w_h as f32 * 0.9;
+
+    let rect = Rect::new(
+      coord! {x:-(w / 2.0), y:-(h / 2.0) },
+      coord! {x:w / 2.0, y:h / 2.0 },
+    );
+    let size = w / 2f32.powi(self.depth as i32);
+    let grid = Grid::new(GRID_KINDS[self.grid_kind], rect, size);
+
+    // Edge midpoints are shared by neighbouring cells, so pipes joining them stay continuous
+    grid
+      .cells()
+      .iter()
+      .flat_map(|cell| {
+        let center = grid.center(*cell);
+        let midpoints = grid.edge_midpoints(*cell);
+        let n_sides = midpoints.len();
+        let offset = rng.gen_range(0..n_sides);
+        let pipe = |from: usize, to: usize| {
+          let curve = QuadraticBezier::new(midpoints[from], center, midpoints[to]);
+          sample_adaptive(&curve, CURVE_TOLERANCE)
+        };
+        let mut pipes = (0..n_sides / 2)
+          .map(|i| pipe((offset + 2 * i) % n_sides, (offset + 2 * i + 1) % n_sides))
+          .collect::<Vec<_>>();
+        // Triangles have a side left, which ends in the middle of the cell
+        if n_sides % 2 == 1 {
+          pipes.push(LineString::new(vec![
+            midpoints[(offset + n_sides - 1) % n_sides],
+            center,
+          ]));
+        }
+        pipes
+      })
+      .map(|line| line.into_iter().map(|coord| Vec2::from(coord.x_y())))
+      .for_each(|coords| {
+        draw
+          .polyline()
+          .weight(self.line_width * 2.)
+          .color(WHITE)
+          .points(coords.clone());
+        draw
+          .polyline()
+          .weight(self.line_width)
+          .color(BLACK)
+          .points(coords);
+      });
+  }
+}
@@ -1,12 +1,17 @@
-use geo::{coord, line_string, Coord, LineString, MultiLineString, Rect};
+use geo::{coord, Coord, Rect};
 use nannou::{
-  prelude::{Key, Vec2, BLACK, PI, WHITE},
+  prelude::{Key, Vec2, BLACK, WHITE},
   App,
 };
-use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::f32::consts::FRAC_PI_2;
+use rand::{rngs::StdRng, SeedableRng};
+use utils::algorithm::{
+  space::{compute_space, QuadSplit, SpaceOptions, SpaceTile},
+  truchet::{compute_truchet, PathShape, TileSet, TruchetOptions},
+};
 use utils::app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork};
 
+const ARC_TOLERANCE: f32 = 0.5;
+
 fn main() {
   make_static_artwork::<Model>().run();
 }
@@ -15,6 +20,8 @@ struct Model {
   base_model: BaseModel,
   depth: usize,
   line_width: f32,
+  tile_set: usize,
+  multi_scale: bool,
 }
 
 impl Artwork for Model {
@@ -23,6 +30,8 @@ impl Artwork for Model {
       base_model,
       depth: 4,
       line_width: 10.0,
+      tile_set: 0,
+      multi_scale: false,
     }
   }
   fn get_options() -> ArtworkOptions {
@@ -35,7 +44,10 @@ impl Artwork for Model {
     &mut self.base_model
   }
   fn current_frame_name(&self) -> String {
-    format!("frame_{}", self.base_model.seed)
+    format!(
+      "frame_{}_{}_{}_{}",
+      self.base_model.seed, self.depth, self.tile_set, self.multi_scale
+    )
   }
   fn key_pressed(&mut self, _app: &App, key: Key) {
     match key {
@@ -51,6 +63,8 @@ impl Artwork for Model {
           self.line_width -= 1.0;
         }
       }
+      Key::T => self.tile_set = (self.tile_set + 1) % 3,
+      Key::M => self.multi_scale = !self.multi_scale,
       _ => {}
     }
   }
@@ -68,15 +82,40 @@ impl StaticArtwork for Model {
     let w = w_w as f32 * 0.9;
     let h = w_h as f32 * 0.9;
 
-    let rect = Rect::new(
-      coord! {x:-(w / 2.0), y:-(h as f32 / 2.0) },
-      coord! {x:w as f32 / 2.0, y:h as f32 / 2.0 },
+    let root = Tile::new(
+      coord! {x:-(w / 2.0), y:-(h / 2.0) },
+      coord! {x:w / 2.0, y:h / 2.0 },
     );
-
-    let rects = (0..self.depth).fold(vec![rect], |r, _| tile(r));
-    let tiles = rects.into_iter().map(|rect| Tile::new(rect, &mut rng));
-    let lines = tiles.flat_map(|tile| tile.create_lines());
-    lines
+    let depth = self.depth;
+    // Multi-scale tiles go one level deeper, neighbours being at most twice as large as each other
+    let options = match self.multi_scale {
+      true => SpaceOptions {
+        max_leaves: usize::MAX,
+        max_depth: depth + 1,
+        split_probability: Box::new(move |d: usize| if d < depth { 1.0 } else { 0.5 }),
+        ..Default::default()
+      },
+      false => SpaceOptions {
+        max_leaves: usize::MAX,
+        max_depth: depth,
+        ..Default::default()
+      },
+    };
+    let space = compute_space(root, &options, &QuadSplit { jitter: 0.0 }, &mut rng);
+
+    let tile_set = match self.tile_set {
+      0 => TileSet::pipes(),
+      1 => TileSet::smith(),
+      _ => TileSet::complete(&[1.0 / 3.0, 2.0 / 3.0], PathShape::Curved),
+    };
+    let options = TruchetOptions {
+      tolerance: ARC_TOLERANCE,
+      ..Default::default()
+    };
+    let truchet = compute_truchet(&space, &tile_set, &options, &mut rng);
+    truchet
+      .lines()
+      .into_iter()
       .map(|line| line.into_iter().map(|coord| Vec2::from(coord.x_y())))
       .for_each(|coords| {
         draw
@@ -93,115 +132,30 @@ impl StaticArtwork for Model {
   }
 }
 
-enum Position {
-  Top,
-  Bottom,
-  Left,
-  Right,
-}
-
-impl Position {
-  fn get_position_coords(&self, rect: &Rect<f32>) -> Coord<f32> {
-    let (x_min, y_min) = rect.min().into();
-    let (x_max, y_max) = rect.max().into();
-    match self {
-      Position::Top => coord! {x:(x_min+x_max)/2.,y:y_max},
-      Position::Bottom => coord! {x:(x_min+ x_max)/2.,y:y_min},
-      Position::Left => coord! {x:x_min,y:(y_min+y_max)/2.},
-      Position::Right => coord! {x:x_max,y:(y_min+y_max)/2.},
-    }
-  }
-}
-
-enum Pipe {
-  TopBottomLeftRight,
-  LeftRightTopBottom,
-  TopLeftBottomRigh,
-  TopRightBottomLeft,
-}
-
-impl Pipe {
-  fn create_lines(&self, rect: &Rect<f32>) -> MultiLineString<f32> {
-    // let (x_min, y_min) = rect.min().into();
-    // let (x_max, y_max) = rect.max().into();
-    match self {
-      Pipe::TopLeftBottomRigh => {
-        let tl: Coord<f32> = (rect.min().x, rect.max().y).into();
-        let br: Coord<f32> = (rect.max().x, rect.min().y).into();
-        MultiLineString::new(vec![
-          create_arc_line(tl, rect.width() / 2., 1.5 * PI, 2.0 * PI),
-          create_arc_line(br, rect.width() / 2., FRAC_PI_2, PI),
-        ])
-      }
-      Pipe::TopRightBottomLeft => MultiLineString::new(vec![
-        create_arc_line(rect.max(), rect.width() / 2., PI, 1.5 * PI),
-        create_arc_line(rect.min(), rect.width() / 2., 0.0, FRAC_PI_2),
-      ]),
-      _ => MultiLineString::new(vec![
-        line_string![
-          Position::Top.get_position_coords(rect),
-          Position::Bottom.get_position_coords(rect)
-        ],
-        line_string![
-          Position::Left.get_position_coords(rect),
-          Position::Right.get_position_coords(rect)
-        ],
-      ]),
-    }
-  }
-}
-
 struct Tile {
   rect: Rect<f32>,
-  pipe: Pipe,
-  _rotation: f32,
-  _ratio: f32,
 }
 
-impl Tile {
-  fn new(rect: Rect<f32>, rng: &mut StdRng) -> Self {
-    Self {
-      rect,
-      pipe: gen_pipe(rng),
-      _rotation: 0.0,
-      _ratio: 1.0,
+impl SpaceTile for Tile {
+  fn new(min: Coord<f32>, max: Coord<f32>) -> Self {
+    Tile {
+      rect: Rect::new(min, max),
     }
   }
 
-  fn create_lines(&self) -> MultiLineString<f32> {
-    self.pipe.create_lines(&self.rect)
+  fn width(&self) -> f32 {
+    self.rect.width()
   }
-}
 
-fn gen_pipe(rng: &mut StdRng) -> Pipe {
-  match rng.gen_range(0..4) {
-    0 => Pipe::TopBottomLeftRight,
-    1 => Pipe::TopLeftBottomRigh,
-    2 => Pipe::TopRightBottomLeft,
-    _ => Pipe::LeftRightTopBottom,
+  fn height(&self) -> f32 {
+    self.rect.height()
   }
-}
 
-fn tile(rectangles: Vec<Rect<f32>>) -> Vec<Rect<f32>> {
-  rectangles
-    .iter()
-    .flat_map(|child| child.split_x().map(|x_child| x_child.split_y()))
-    .flatten()
-    .collect()
-}
+  fn min(&self) -> Coord<f32> {
+    self.rect.min()
+  }
 
-fn create_arc_line(
-  center: Coord<f32>,
-  radius: f32,
-  theta_start: f32,
-  theta_end: f32,
-) -> LineString<f32> {
-  let n_points = 10;
-  let theta_incr = (theta_end - theta_start).abs() / (n_points - 1) as f32;
-  let mut coords: Vec<Coord<f32>> = vec![];
-  for i in 0..n_points {
-    let theta = theta_start + i as f32 * theta_incr;
-    coords.push(center + (radius * theta.cos(), radius * theta.sin()).into());
+  fn max(&self) -> Coord<f32> {
+    self.rect.max()
   }
-  LineString::new(coords)
 }
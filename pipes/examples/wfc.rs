@@ -0,0 +1,194 @@
+use geo::{coord, LineString, Rect};
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use utils::algorithm::wfc::{collapse, WfcOptions, WfcTile, WfcTileSet};
+use utils::app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork};
+use utils::draw::{
+  filling::FillingOptions,
+  line::{LineOptions, PathOptions},
+};
+use utils::geometry::{
+  curve::{sample_adaptive, QuadraticBezier},
+  grid::{CellCoord, Grid, GridKind, HexOrientation},
+};
+
+const CURVE_TOLERANCE: f32 = 0.5;
+const GRID_KINDS: [GridKind; 4] = [
+  GridKind::Square,
+  GridKind::Hexagonal(HexOrientation::Flat),
+  GridKind::Hexagonal(HexOrientation::Pointy),
+  GridKind::Triangular,
+];
+// Socket of the edges crossed by a pipe
+const PIPE: &str = "1";
+const EMPTY: &str = "0";
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  depth: usize,
+  line_width: f32,
+  grid_kind: usize,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      depth: 4,
+      line_width: 10.0,
+      grid_kind: 0,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    format!(
+      "frame_{}_{}_{}",
+      self.base_model.seed, self.depth, self.grid_kind
+    )
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Up => self.depth += 1,
+      Key::Down if self.depth > 0 => self.depth -= 1,
+      Key::Right => self.line_width += 1.0,
+      Key::Left if self.line_width > 1.0 => self.line_width -= 1.0,
+      Key::G => self.grid_kind = (self.grid_kind + 1) % GRID_KINDS.len(),
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+
+    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
+
+    let [w_w, w_h] = self.base_model.texture.size();
+    let w = w_w as f32 * 0.9;
+    let h = w_h as f32 * 0.9;
+
+    let rect = Rect::new(
+      coord! {x:-(w / 2.0), y:-(h / 2.0) },
+      coord! {x:w / 2.0, y:h / 2.0 },
+    );
+    let size = w / 2f32.powi(self.depth as i32);
+    let grid = Grid::new(GRID_KINDS[self.grid_kind], rect, size);
+    let tile_set = create_tile_set(grid.n_sides());
+
+    // A crossing in the middle of the grid, pipes never leaving the grid
+    let center = grid.cell_at(rect.center());
+    let crossing = tile_set
+      .variants()
+      .iter()
+      .position(|variant| variant.sockets.iter().all(|socket| socket == PIPE));
+    let options = WfcOptions {
+      fixed: center.into_iter().zip(crossing).collect(),
+      border: Some(EMPTY.to_string()),
+      ..Default::default()
+    };
+    let cells = match collapse(&grid, &tile_set, &options, &mut rng) {
+      Some(cells) => cells,
+      None => return,
+    };
+
+    let color = Hsl::new(0.0, 0.0, 0.0);
+    let path_options = PathOptions::default();
+    grid.cells().iter().for_each(|cell| {
+      let sockets = &tile_set.variants()[cells[cell]].sockets;
+      let pipes = pipes(&grid, *cell, sockets);
+      if pipes.is_empty() {
+        utils::draw::filling::pencil(
+          grid.polygon(*cell),
+          draw,
+          45.0,
+          FillingOptions {
+            weight: 1.0,
+            density: 0.05,
+            color,
+          },
+        );
+      }
+      pipes.iter().for_each(|pipe| {
+        utils::draw::line::stroke_path(
+          pipe,
+          draw,
+          LineOptions {
+            weight: self.line_width,
+            density: 1.0,
+            color,
+          },
+          &path_options,
+        )
+      });
+    });
+  }
+}
+
+// Every way of crossing the edges of a cell, up to rotations
+fn create_tile_set(n_sides: usize) -> WfcTileSet {
+  let rotate = |mask: usize| ((mask << 1) | (mask >> (n_sides - 1))) & ((1 << n_sides) - 1);
+  let tiles = (0..1usize << n_sides)
+    .filter(|mask| {
+      (1..n_sides)
+        .scan(*mask, |rotated, _| {
+          *rotated = rotate(*rotated);
+          Some(*rotated)
+        })
+        .all(|rotated| rotated >= *mask)
+    })
+    .map(|mask| {
+      let sockets = (0..n_sides)
+        .map(|edge| match mask & (1 << edge) != 0 {
+          true => PIPE,
+          false => EMPTY,
+        })
+        .collect::<Vec<_>>();
+      // Dead ends are rare and crossings less frequent than turns
+      let weight = match mask.count_ones() {
+        1 => 0.05,
+        0 | 2 => 1.0,
+        _ => 0.3,
+      };
+      WfcTile::new(&sockets).rotations(true).weight(weight)
+    })
+    .collect::<Vec<_>>();
+  WfcTileSet::new(&tiles)
+}
+
+// Curves between two crossed edges, straight lines to the cell center otherwise
+fn pipes(grid: &Grid, cell: CellCoord, sockets: &[String]) -> Vec<LineString<f32>> {
+  let center = grid.center(cell);
+  let midpoints = grid.edge_midpoints(cell);
+  let crossed = (0..midpoints.len())
+    .filter(|edge| sockets[*edge] == PIPE)
+    .map(|edge| midpoints[edge])
+    .collect::<Vec<_>>();
+  match crossed.len() {
+    2 => vec![sample_adaptive(
+      &QuadraticBezier::new(crossed[0], center, crossed[1]),
+      CURVE_TOLERANCE,
+    )],
+    _ => crossed
+      .into_iter()
+      .map(|midpoint| LineString::new(vec![midpoint, center]))
+      .collect(),
+  }
+}
@@ -1,11 +1,19 @@
-use geo::{coord, line_string, Coord, LineString, MultiLineString, Rect, Rotate};
+mod tile;
+
+use geo::{coord, MultiLineString, Rotate};
 use nannou::{
-  prelude::{map_range, Key, Vec2, BLACK, PI, WHITE},
+  prelude::{map_range, Key, Vec2, BLACK, WHITE},
   App,
 };
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::f32::consts::FRAC_PI_2;
-use utils::app::{make_dynamic_artwork, Artwork, ArtworkOptions, BaseModel, DynamicArtwork};
+use tile::Tile;
+use utils::{
+  algorithm::{
+    space::{compute_space, QuadSplit, SpaceOptions, SpaceTile},
+    truchet::{compute_truchet, TileSet, TruchetOptions},
+  },
+  app::{make_dynamic_artwork, Artwork, ArtworkOptions, BaseModel, DynamicArtwork},
+};
 
 const FPS: u32 = 60;
 const N_SEC: u32 = 20;
@@ -15,6 +23,8 @@ const DEPTH: usize = 4;
 const N_ROTATIONS: usize = 60;
 const ROTATION_MAX: f32 = 90.0;
 
+const ARC_TOLERANCE: f32 = 0.5;
+
 fn main() {
   make_dynamic_artwork::<Model>().run();
 }
@@ -124,126 +134,23 @@ impl DynamicArtwork for Model {
 }
 
 fn create_lines(width: f32, height: f32, rng: &mut StdRng) -> Vec<MultiLineString<f32>> {
-  let rect = Rect::new(
-    coord! {x:-(width / 2.0), y:-(height as f32 / 2.0) },
-    coord! {x:width as f32 / 2.0, y:height as f32 / 2.0 },
+  let root = Tile::new(
+    coord! {x:-(width / 2.0), y:-(height / 2.0) },
+    coord! {x:width / 2.0, y:height / 2.0 },
   );
-
-  let rects = (0..DEPTH).fold(vec![rect], |r, _| tile(r));
-  let tiles = rects.into_iter().map(|rect| Tile::new(rect, rng));
-
-  tiles.map(|tile| tile.create_lines()).collect::<Vec<_>>()
-}
-
-enum Position {
-  Top,
-  Bottom,
-  Left,
-  Right,
-}
-
-impl Position {
-  fn get_position_coords(&self, rect: &Rect<f32>) -> Coord<f32> {
-    let (x_min, y_min) = rect.min().into();
-    let (x_max, y_max) = rect.max().into();
-    match self {
-      Position::Top => coord! {x:(x_min+x_max)/2.,y:y_max},
-      Position::Bottom => coord! {x:(x_min+ x_max)/2.,y:y_min},
-      Position::Left => coord! {x:x_min,y:(y_min+y_max)/2.},
-      Position::Right => coord! {x:x_max,y:(y_min+y_max)/2.},
-    }
-  }
-}
-
-enum Pipe {
-  TopBottomLeftRight,
-  LeftRightTopBottom,
-  TopLeftBottomRigh,
-  TopRightBottomLeft,
-}
-
-impl Pipe {
-  fn create_lines(&self, rect: &Rect<f32>) -> MultiLineString<f32> {
-    // let (x_min, y_min) = rect.min().into();
-    // let (x_max, y_max) = rect.max().into();
-    match self {
-      Pipe::TopLeftBottomRigh => {
-        let tl: Coord<f32> = (rect.min().x, rect.max().y).into();
-        let br: Coord<f32> = (rect.max().x, rect.min().y).into();
-        MultiLineString::new(vec![
-          create_arc_line(tl, rect.width() / 2., 1.5 * PI, 2.0 * PI),
-          create_arc_line(br, rect.width() / 2., FRAC_PI_2, PI),
-        ])
-      }
-      Pipe::TopRightBottomLeft => MultiLineString::new(vec![
-        create_arc_line(rect.max(), rect.width() / 2., PI, 1.5 * PI),
-        create_arc_line(rect.min(), rect.width() / 2., 0.0, FRAC_PI_2),
-      ]),
-      _ => MultiLineString::new(vec![
-        line_string![
-          Position::Top.get_position_coords(rect),
-          Position::Bottom.get_position_coords(rect)
-        ],
-        line_string![
-          Position::Left.get_position_coords(rect),
-          Position::Right.get_position_coords(rect)
-        ],
-      ]),
-    }
-  }
-}
-
-struct Tile {
-  rect: Rect<f32>,
-  pipe: Pipe,
-  _rotation: f32,
-  _ratio: f32,
-}
-
-impl Tile {
-  fn new(rect: Rect<f32>, rng: &mut StdRng) -> Self {
-    Self {
-      rect,
-      pipe: gen_pipe(rng),
-      _rotation: 0.0,
-      _ratio: 1.0,
-    }
-  }
-
-  fn create_lines(&self) -> MultiLineString<f32> {
-    self.pipe.create_lines(&self.rect)
-  }
-}
-
-fn gen_pipe(rng: &mut StdRng) -> Pipe {
-  match rng.gen_range(0..4) {
-    0 => Pipe::TopBottomLeftRight,
-    1 => Pipe::TopLeftBottomRigh,
-    2 => Pipe::TopRightBottomLeft,
-    _ => Pipe::LeftRightTopBottom,
-  }
-}
-
-fn tile(rectangles: Vec<Rect<f32>>) -> Vec<Rect<f32>> {
-  rectangles
-    .iter()
-    .flat_map(|child| child.split_x().map(|x_child| x_child.split_y()))
-    .flatten()
+  let options = SpaceOptions {
+    max_leaves: 4usize.pow(DEPTH as u32),
+    ..Default::default()
+  };
+  let space = compute_space(root, &options, &QuadSplit { jitter: 0.0 }, rng);
+
+  let options = TruchetOptions {
+    tolerance: ARC_TOLERANCE,
+    ..Default::default()
+  };
+  compute_truchet(&space, &TileSet::pipes(), &options, rng)
+    .tiles
+    .into_iter()
+    .map(|tile| tile.lines)
     .collect()
 }
-
-fn create_arc_line(
-  center: Coord<f32>,
-  radius: f32,
-  theta_start: f32,
-  theta_end: f32,
-) -> LineString<f32> {
-  let n_points = 10;
-  let theta_incr = (theta_end - theta_start).abs() / (n_points - 1) as f32;
-  let mut coords: Vec<Coord<f32>> = vec![];
-  for i in 0..n_points {
-    let theta = theta_start + i as f32 * theta_incr;
-    coords.push(center + (radius * theta.cos(), radius * theta.sin()).into());
-  }
-  LineString::new(coords)
-}
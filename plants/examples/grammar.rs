@@ -0,0 +1,142 @@
+use nannou::{prelude::Key, App};
+use plants::utils::app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork};
+use plants::utils::draw::BrushDrawer;
+use plants::utils::geometry::{ProjectionMatrix, WorldBox, WorldPoint, WorldTransform};
+use plants::{systems::grammar::Grammar, turtle};
+use rand::{rngs::StdRng, SeedableRng};
+use std::{f64::consts::FRAC_PI_3, path::PathBuf};
+
+// Derivations stop growing past this many modules
+const MAX_SYMBOLS: usize = 500_000;
+
+// Grammar file given as first argument, read again at each redraw so that it can be edited while
+// the artwork is open
+struct Model {
+  base_model: BaseModel,
+  steps: usize,
+  path: PathBuf,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    let path = std::env::args()
+      .nth(1)
+      .map(PathBuf::from)
+      .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("grammars/leaf.txt"));
+    Self {
+      base_model,
+      steps: 20,
+      path,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions {
+      ..ArtworkOptions::default()
+    }
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    format!("frame_{}", self.base_model.seed)
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Equals => self.steps += 1,
+      Key::Minus => self.steps = self.steps.saturating_sub(1),
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let mut rng: StdRng = StdRng::seed_from_u64(self.base_model.seed);
+    let draw = &self.base_model.draw;
+    draw.background().color(nannou::color::WHITE);
+
+    let grammar = match Grammar::from_file(&self.path) {
+      Ok(grammar) => grammar,
+      Err(error) => {
+        println!("{}: {}", self.path.display(), error);
+        return;
+      }
+    };
+
+    let [w_w, _] = self.base_model.texture.size();
+    let size = w_w as f64;
+
+    let bbox = WorldBox::new(
+      WorldPoint::new(-(size / 2.0), -(size / 2.0), 0.0),
+      WorldPoint::new(size / 2.0, size / 2.0, 0.0),
+    );
+
+    let polygons_3d = grow_l_system(grammar, self.steps, self.base_model.seed, bbox);
+    let projection = ProjectionMatrix::perspective(1.0);
+
+    let polygons_2d = polygons_3d
+      .iter()
+      .map(|polygon| {
+        polygon
+          .iter()
+          .filter_map(|point| projection.transform_point3d(*point))
+          .map(|point| point.xy())
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+
+    polygons_2d.iter().for_each(|polygon| {
+      draw
+        .polyline()
+        .stroke_weight(2.0)
+        .brush_from_points(polygon, 7.0, &mut rng)
+        .color(nannou::color::BLACK);
+    });
+  }
+}
+
+fn grow_l_system(
+  grammar: Grammar,
+  steps: usize,
+  seed: u64,
+  bbox_out: WorldBox,
+) -> Vec<Vec<WorldPoint>> {
+  let turtle_params = turtle::polygon::Params::new(grammar.angle().unwrap_or(FRAC_PI_3));
+  let commands = grammar
+    .l_system()
+    .seed(seed)
+    .max_symbols(MAX_SYMBOLS)
+    .take(steps + 1)
+    .last()
+    .unwrap();
+  let polygons = turtle::polygon::to_geom(commands, &turtle_params);
+  let bbox_in = WorldBox::from_points(polygons.iter().flatten());
+  let transform = WorldTransform::translation(
+    -(bbox_in.min.x + bbox_in.max.x) / 2.0,
+    -(bbox_in.min.y + bbox_in.max.y) / 2.0,
+    -(bbox_in.min.z + bbox_in.max.z) / 2.0,
+  )
+  .then_scale(
+    bbox_out.width() / bbox_in.width(),
+    bbox_out.height() / bbox_in.height(),
+    bbox_out.depth() / bbox_in.depth(),
+  )
+  .then_scale(0.95, 0.95, 0.95);
+
+  polygons
+    .into_iter()
+    .map(|polygon| {
+      polygon
+        .into_iter()
+        .filter_map(|point| transform.transform_point3d(point))
+        .collect()
+    })
+    .collect()
+}
+
+fn main() {
+  make_static_artwork::<Model>().run()
+}
@@ -0,0 +1,124 @@
+use geo::{Coord, LineString, Polygon};
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::f32::consts::TAU;
+use utils::{
+  algorithm::differential_growth::{DifferentialGrowth, GrowthOptions},
+  app::{make_dynamic_artwork, Artwork, ArtworkOptions, BaseModel, DynamicArtwork},
+  draw::line::{LineOptions, PathOptions},
+};
+
+const FPS: u32 = 30;
+const N_SEC: u32 = 20;
+const STEPS_PER_FRAME: usize = 2;
+const N_BOUNDARY_POINTS: usize = 128;
+
+fn main() {
+  make_dynamic_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  current_frame: u32,
+  rng: StdRng,
+  growth: Option<DifferentialGrowth>,
+  // Grows a stem between two fixed points instead of a closed outline
+  open: bool,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    let rng = StdRng::seed_from_u64(base_model.seed);
+    Self {
+      base_model,
+      current_frame: 0,
+      rng,
+      growth: None,
+      open: false,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    format!("frame_{}", self.current_frame)
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    if key == Key::O {
+      self.open = !self.open;
+      self.growth = None;
+    }
+  }
+}
+
+impl DynamicArtwork for Model {
+  fn fps(&self) -> u32 {
+    FPS
+  }
+  fn n_sec(&self) -> u32 {
+    N_SEC
+  }
+  fn current_frame(&mut self) -> &mut u32 {
+    &mut self.current_frame
+  }
+  fn draw_at_time(&mut self, t: f64) {
+    if t == 0.0 || self.growth.is_none() {
+      self.rng = StdRng::seed_from_u64(self.base_model.seed);
+      self.growth = Some(self.start_growth());
+    }
+    let growth = self.growth.as_mut().unwrap();
+    (0..STEPS_PER_FRAME).for_each(|_| growth.step(&mut self.rng));
+
+    let draw = &self.base_model.draw;
+    draw.background().color(WHITE);
+    utils::draw::line::stroke_path(
+      &growth.line_string(),
+      draw,
+      LineOptions {
+        weight: 1.5,
+        density: 0.9,
+        color: Hsl::new(0.35, 0.4, 0.25),
+      },
+      &PathOptions::default(),
+    );
+  }
+}
+
+impl Model {
+  fn start_growth(&self) -> DifferentialGrowth {
+    let [w_w, w_h] = self.base_model.texture.size();
+    let radius = w_w.min(w_h) as f32 * 0.45;
+    let boundary = Polygon::new(
+      LineString::new(
+        (0..N_BOUNDARY_POINTS)
+          .map(|i| {
+            let angle = TAU * i as f32 / N_BOUNDARY_POINTS as f32;
+            Coord::from((angle.cos(), angle.sin())) * radius
+          })
+          .collect(),
+      ),
+      vec![],
+    );
+    let options = GrowthOptions {
+      max_nodes: 8000,
+      ..Default::default()
+    };
+    let growth = match self.open {
+      true => DifferentialGrowth::new(
+        LineString::from(vec![(0.0, -radius * 0.9), (0.0, -radius * 0.8)]),
+        options,
+      ),
+      false => DifferentialGrowth::circle(Coord::zero(), radius / 10.0, 40, options),
+    };
+    growth.boundary(boundary)
+  }
+}
@@ -0,0 +1,362 @@
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+  Add,
+  Subtract,
+  Multiply,
+  Divide,
+  Remainder,
+  Power,
+  Less,
+  LessOrEqual,
+  Greater,
+  GreaterOrEqual,
+  Equal,
+  NotEqual,
+  And,
+  Or,
+}
+
+// Arithmetic expression of module parameters and constants, conditions evaluating to 1.0 when
+// true and 0.0 when false
+#[derive(Clone, Debug)]
+pub enum Expression {
+  Number(f64),
+  Variable(String),
+  Negate(Box<Expression>),
+  Not(Box<Expression>),
+  Binary(Operator, Box<Expression>, Box<Expression>),
+  Call(String, Vec<Expression>),
+}
+
+const FUNCTIONS: &[(&str, usize)] = &[
+  ("abs", 1),
+  ("sqrt", 1),
+  ("exp", 1),
+  ("ln", 1),
+  ("sin", 1),
+  ("cos", 1),
+  ("tan", 1),
+  ("floor", 1),
+  ("ceil", 1),
+  ("min", 2),
+  ("max", 2),
+];
+
+impl Expression {
+  pub fn parse(input: &str) -> Result<Expression, String> {
+    let mut parser = Parser {
+      tokens: tokenize(input)?,
+      position: 0,
+    };
+    let expression = parser.or()?;
+    match parser.peek() {
+      None => Ok(expression),
+      Some(token) => Err(format!("unexpected {} in \"{}\"", token, input.trim())),
+    }
+  }
+
+  // Values of the variables are looked up by name, None meaning the variable is unknown
+  pub fn evaluate<F: Fn(&str) -> Option<f64>>(&self, lookup: &F) -> f64 {
+    match self {
+      Expression::Number(value) => *value,
+      Expression::Variable(name) => lookup(name).unwrap_or(f64::NAN),
+      Expression::Negate(operand) => -operand.evaluate(lookup),
+      Expression::Not(operand) => truth(operand.evaluate(lookup) == 0.0),
+      Expression::Binary(operator, left, right) => {
+        let (left, right) = (left.evaluate(lookup), right.evaluate(lookup));
+        match operator {
+          Operator::Add => left + right,
+          Operator::Subtract => left - right,
+          Operator::Multiply => left * right,
+          Operator::Divide => left / right,
+          Operator::Remainder => left.rem_euclid(right),
+          Operator::Power => left.powf(right),
+          Operator::Less => truth(left < right),
+          Operator::LessOrEqual => truth(left <= right),
+          Operator::Greater => truth(left > right),
+          Operator::GreaterOrEqual => truth(left >= right),
+          Operator::Equal => truth(left == right),
+          Operator::NotEqual => truth(left != right),
+          Operator::And => truth(left != 0.0 && right != 0.0),
+          Operator::Or => truth(left != 0.0 || right != 0.0),
+        }
+      }
+      Expression::Call(name, arguments) => {
+        let arguments = arguments
+          .iter()
+          .map(|argument| argument.evaluate(lookup))
+          .collect::<Vec<_>>();
+        match name.as_str() {
+          "abs" => arguments[0].abs(),
+          "sqrt" => arguments[0].sqrt(),
+          "exp" => arguments[0].exp(),
+          "ln" => arguments[0].ln(),
+          "sin" => arguments[0].sin(),
+          "cos" => arguments[0].cos(),
+          "tan" => arguments[0].tan(),
+          "floor" => arguments[0].floor(),
+          "ceil" => arguments[0].ceil(),
+          "min" => arguments[0].min(arguments[1]),
+          "max" => arguments[0].max(arguments[1]),
+          _ => f64::NAN,
+        }
+      }
+    }
+  }
+
+  pub fn variables(&self) -> Vec<&str> {
+    match self {
+      Expression::Number(_) => vec![],
+      Expression::Variable(name) => vec![name.as_str()],
+      Expression::Negate(operand) | Expression::Not(operand) => operand.variables(),
+      Expression::Binary(_, left, right) => {
+        let mut variables = left.variables();
+        variables.extend(right.variables());
+        variables
+      }
+      Expression::Call(_, arguments) => arguments
+        .iter()
+        .flat_map(|argument| argument.variables())
+        .collect(),
+    }
+  }
+}
+
+fn truth(value: bool) -> f64 {
+  match value {
+    true => 1.0,
+    false => 0.0,
+  }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+  Number(f64),
+  Identifier(String),
+  Operator(Operator),
+  Not,
+  Open,
+  Close,
+  Comma,
+}
+
+impl Display for Token {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Token::Number(value) => write!(f, "number {}", value),
+      Token::Identifier(name) => write!(f, "\"{}\"", name),
+      Token::Operator(operator) => write!(f, "operator {:?}", operator),
+      Token::Not => write!(f, "\"!\""),
+      Token::Open => write!(f, "\"(\""),
+      Token::Close => write!(f, "\")\""),
+      Token::Comma => write!(f, "\",\""),
+    }
+  }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+  let chars = input.chars().collect::<Vec<_>>();
+  let mut tokens = vec![];
+  let mut i = 0;
+  while i < chars.len() {
+    let c = chars[i];
+    let next = chars.get(i + 1).copied();
+    if c.is_whitespace() {
+      i += 1;
+      continue;
+    }
+    if c.is_ascii_digit() || c == '.' {
+      let start = i;
+      while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+        i += 1;
+      }
+      let text = chars[start..i].iter().collect::<String>();
+      match text.parse() {
+        Ok(value) => tokens.push(Token::Number(value)),
+        Err(_) => return Err(format!("invalid number \"{}\"", text)),
+      }
+      continue;
+    }
+    if c.is_alphabetic() || c == '_' {
+      let start = i;
+      while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+        i += 1;
+      }
+      tokens.push(Token::Identifier(chars[start..i].iter().collect()));
+      continue;
+    }
+    let (token, length) = match (c, next) {
+      ('<', Some('=')) => (Token::Operator(Operator::LessOrEqual), 2),
+      ('>', Some('=')) => (Token::Operator(Operator::GreaterOrEqual), 2),
+      ('=', Some('=')) => (Token::Operator(Operator::Equal), 2),
+      ('!', Some('=')) => (Token::Operator(Operator::NotEqual), 2),
+      ('&', Some('&')) => (Token::Operator(Operator::And), 2),
+      ('|', Some('|')) => (Token::Operator(Operator::Or), 2),
+      ('<', _) => (Token::Operator(Operator::Less), 1),
+      ('>', _) => (Token::Operator(Operator::Greater), 1),
+      ('+', _) => (Token::Operator(Operator::Add), 1),
+      ('-', _) => (Token::Operator(Operator::Subtract), 1),
+      ('*', _) => (Token::Operator(Operator::Multiply), 1),
+      ('/', _) => (Token::Operator(Operator::Divide), 1),
+      ('%', _) => (Token::Operator(Operator::Remainder), 1),
+      ('^', _) => (Token::Operator(Operator::Power), 1),
+      ('!', _) => (Token::Not, 1),
+      ('(', _) => (Token::Open, 1),
+      (')', _) => (Token::Close, 1),
+      (',', _) => (Token::Comma, 1),
+      _ => return Err(format!("unexpected character '{}'", c)),
+    };
+    tokens.push(token);
+    i += length;
+  }
+  Ok(tokens)
+}
+
+// Recursive descent, from the loosest binding operators to the tightest
+struct Parser {
+  tokens: Vec<Token>,
+  position: usize,
+}
+
+impl Parser {
+  fn peek(&self) -> Option<&Token> {
+    self.tokens.get(self.position)
+  }
+
+  fn next(&mut self) -> Option<Token> {
+    let token = self.tokens.get(self.position).cloned();
+    self.position += 1;
+    token
+  }
+
+  fn expect(&mut self, expected: Token) -> Result<(), String> {
+    match self.next() {
+      Some(token) if token == expected => Ok(()),
+      Some(token) => Err(format!("expected {} but found {}", expected, token)),
+      None => Err(format!(
+        "expected {} at the end of the expression",
+        expected
+      )),
+    }
+  }
+
+  // Left associative chain of the given operators over operands parsed by operand
+  fn chain(
+    &mut self,
+    operators: &[Operator],
+    operand: fn(&mut Parser) -> Result<Expression, String>,
+  ) -> Result<Expression, String> {
+    let mut left = operand(self)?;
+    while let Some(Token::Operator(operator)) = self.peek() {
+      let operator = *operator;
+      if !operators.contains(&operator) {
+        break;
+      }
+      self.position += 1;
+      left = Expression::Binary(operator, Box::new(left), Box::new(operand(self)?));
+    }
+    Ok(left)
+  }
+
+  fn or(&mut self) -> Result<Expression, String> {
+    self.chain(&[Operator::Or], Parser::and)
+  }
+
+  fn and(&mut self) -> Result<Expression, String> {
+    self.chain(&[Operator::And], Parser::comparison)
+  }
+
+  fn comparison(&mut self) -> Result<Expression, String> {
+    self.chain(
+      &[
+        Operator::Less,
+        Operator::LessOrEqual,
+        Operator::Greater,
+        Operator::GreaterOrEqual,
+        Operator::Equal,
+        Operator::NotEqual,
+      ],
+      Parser::sum,
+    )
+  }
+
+  fn sum(&mut self) -> Result<Expression, String> {
+    self.chain(&[Operator::Add, Operator::Subtract], Parser::product)
+  }
+
+  fn product(&mut self) -> Result<Expression, String> {
+    self.chain(
+      &[Operator::Multiply, Operator::Divide, Operator::Remainder],
+      Parser::unary,
+    )
+  }
+
+  fn unary(&mut self) -> Result<Expression, String> {
+    match self.peek() {
+      Some(Token::Operator(Operator::Subtract)) => {
+        self.position += 1;
+        Ok(Expression::Negate(Box::new(self.unary()?)))
+      }
+      Some(Token::Not) => {
+        self.position += 1;
+        Ok(Expression::Not(Box::new(self.unary()?)))
+      }
+      _ => self.power(),
+    }
+  }
+
+  // Right associative, binding tighter than a negation on its left: -2^2 is -4
+  fn power(&mut self) -> Result<Expression, String> {
+    let base = self.atom()?;
+    match self.peek() {
+      Some(Token::Operator(Operator::Power)) => {
+        self.position += 1;
+        let exponent = self.unary()?;
+        Ok(Expression::Binary(
+          Operator::Power,
+          Box::new(base),
+          Box::new(exponent),
+        ))
+      }
+      _ => Ok(base),
+    }
+  }
+
+  fn atom(&mut self) -> Result<Expression, String> {
+    match self.next() {
+      Some(Token::Number(value)) => Ok(Expression::Number(value)),
+      Some(Token::Identifier(name)) if self.peek() == Some(&Token::Open) => {
+        self.position += 1;
+        let mut arguments = vec![];
+        if self.peek() != Some(&Token::Close) {
+          arguments.push(self.or()?);
+          while self.peek() == Some(&Token::Comma) {
+            self.position += 1;
+            arguments.push(self.or()?);
+          }
+        }
+        self.expect(Token::Close)?;
+        match FUNCTIONS.iter().find(|(function, _)| *function == name) {
+          Some((_, arity)) if *arity == arguments.len() => Ok(Expression::Call(name, arguments)),
+          Some((_, arity)) => Err(format!(
+            "{} takes {} arguments but {} were given",
+            name,
+            arity,
+            arguments.len()
+          )),
+          None => Err(format!("unknown function \"{}\"", name)),
+        }
+      }
+      Some(Token::Identifier(name)) => Ok(Expression::Variable(name)),
+      Some(Token::Open) => {
+        let expression = self.or()?;
+        self.expect(Token::Close)?;
+        Ok(expression)
+      }
+      Some(token) => Err(format!("unexpected {}", token)),
+      None => Err("unexpected end of the expression".to_string()),
+    }
+  }
+}
@@ -0,0 +1,565 @@
+pub mod expression;
+
+use rand::{rngs::StdRng, Rng};
+use std::{
+  collections::{HashMap, HashSet},
+  fmt::Display,
+  path::Path,
+};
+
+use self::expression::Expression;
+use super::{Context, ContextRules, LSystem};
+use crate::turtle::polygon::{Turtle, TurtleInterpretation};
+
+// Parametric L-system read from text, one statement per line:
+//   const: r = 1.456
+//   angle: 60                  in degrees
+//   turtle: G = forward        vertex, forward, left, right, push, pop, polygon, close or none
+//   axiom: {A(1).}
+//   ignore: + -                symbols skipped when matching contexts
+//   A(s) : s > 1 -> G(s)A(s/r) the condition being optional
+//   B < A(s) > C -> A(s + 1)   only when A follows B and is followed by C in its branch
+//   A(s) -> G(s)A(s) : 0.3     weighted alternative
+// Symbols are single characters, modules without a matching production being left as they are.
+// The first matching production is applied, unless it has a weight: one of the matching
+// productions with a weight is then drawn at random in proportion to it.
+pub struct Grammar {
+  axiom: Vec<Module>,
+  productions: Vec<Production>,
+  constants: HashMap<String, f64>,
+  commands: HashMap<char, Command>,
+  angle: Option<f64>,
+  ignored: HashSet<char>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+  Vertex,
+  Forward,
+  Left,
+  Right,
+  Push,
+  Pop,
+  NewPolygon,
+  ClosePolygon,
+  None,
+}
+
+#[derive(Clone, Debug)]
+pub struct Module {
+  pub symbol: char,
+  pub parameters: Vec<f64>,
+  pub command: Command,
+}
+
+// Symbol and names of its parameters
+type Pattern = (char, Vec<String>);
+
+struct Production {
+  left: Vec<Pattern>,
+  predecessor: Pattern,
+  right: Vec<Pattern>,
+  condition: Option<Expression>,
+  successor: Vec<(char, Vec<Expression>)>,
+  weight: Option<Expression>,
+}
+
+#[derive(Debug)]
+pub enum GrammarError {
+  Io(std::io::Error),
+  Syntax { line: usize, message: String },
+}
+
+pub type GrammarRule = fn(Module, &Context<Module>, &Grammar, &mut StdRng) -> Vec<Module>;
+pub type GrammarSystem = LSystem<Module, ContextRules<GrammarRule>, Grammar>;
+
+impl Grammar {
+  pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Grammar, GrammarError> {
+    match std::fs::read_to_string(path) {
+      Ok(text) => Grammar::parse(&text),
+      Err(error) => Err(GrammarError::Io(error)),
+    }
+  }
+
+  pub fn parse(text: &str) -> Result<Grammar, GrammarError> {
+    let mut grammar = Grammar {
+      axiom: vec![],
+      productions: vec![],
+      constants: HashMap::new(),
+      commands: default_commands(),
+      angle: None,
+      ignored: HashSet::new(),
+    };
+    let mut axiom = None;
+    for (index, line) in text.lines().enumerate() {
+      let line = match line.split_once('#') {
+        Some((statement, _)) => statement.trim(),
+        None => line.trim(),
+      };
+      if line.is_empty() {
+        continue;
+      }
+      let syntax_error = |message: String| GrammarError::Syntax {
+        line: index + 1,
+        message,
+      };
+      if let Some((head, successor)) = line.split_once("->") {
+        let production = grammar
+          .parse_production(head, successor)
+          .map_err(syntax_error)?;
+        grammar.productions.push(production);
+        continue;
+      }
+      let (keyword, statement) = match line.split_once(':') {
+        Some((keyword, statement)) => (keyword.trim(), statement.trim()),
+        None => {
+          return Err(syntax_error(format!(
+            "expected a statement, found \"{}\"",
+            line
+          )))
+        }
+      };
+      match keyword {
+        "axiom" if axiom.is_some() => return Err(syntax_error("axiom defined twice".to_string())),
+        "axiom" => axiom = Some(grammar.parse_axiom(statement).map_err(syntax_error)?),
+        "angle" => {
+          let angle = grammar
+            .constant_expression(statement)
+            .map_err(syntax_error)?;
+          grammar.angle = Some(angle.to_radians());
+        }
+        "const" => grammar.parse_constant(statement).map_err(syntax_error)?,
+        "turtle" => grammar.parse_command(statement).map_err(syntax_error)?,
+        "ignore" => grammar
+          .ignored
+          .extend(statement.chars().filter(|c| !c.is_whitespace())),
+        _ => return Err(syntax_error(format!("unknown statement \"{}\"", keyword))),
+      }
+    }
+    // Turtle commands may be given after the axiom
+    grammar.axiom = match axiom {
+      Some(axiom) => axiom
+        .into_iter()
+        .map(|(symbol, parameters)| grammar.module(symbol, parameters))
+        .collect(),
+      None => {
+        return Err(GrammarError::Syntax {
+          line: text.lines().count(),
+          message: "missing axiom".to_string(),
+        })
+      }
+    };
+    Ok(grammar)
+  }
+
+  pub fn axiom(&self) -> &[Module] {
+    &self.axiom
+  }
+
+  // Turning angle in radians, if given
+  pub fn angle(&self) -> Option<f64> {
+    self.angle
+  }
+
+  pub fn constant(&self, name: &str) -> Option<f64> {
+    self.constants.get(name).copied()
+  }
+
+  pub fn module(&self, symbol: char, parameters: Vec<f64>) -> Module {
+    Module {
+      symbol,
+      parameters,
+      command: self.commands.get(&symbol).copied().unwrap_or(Command::None),
+    }
+  }
+
+  pub fn l_system(self) -> GrammarSystem {
+    LSystem::new(
+      self.axiom.clone(),
+      ContextRules(grammar_rule as GrammarRule),
+      self,
+    )
+  }
+
+  pub fn rewrite(
+    &self,
+    module: &Module,
+    context: &Context<Module>,
+    rng: &mut StdRng,
+  ) -> Option<Vec<Module>> {
+    let mut matches = self.productions.iter().filter_map(|production| {
+      let bindings = self.bind(production, module, context)?;
+      match &production.condition {
+        Some(condition) if condition.evaluate(&self.lookup(&bindings)) == 0.0 => None,
+        _ => Some((production, bindings)),
+      }
+    });
+    let (production, bindings) = match matches.next()? {
+      (production, bindings) if production.weight.is_none() => (production, bindings),
+      first => {
+        let mut weighted = std::iter::once(first)
+          .chain(matches)
+          .filter_map(|(production, bindings)| {
+            let weight = production.weight.as_ref()?;
+            let weight = weight.evaluate(&self.lookup(&bindings)).max(0.0);
+            Some((weight, production, bindings))
+          })
+          .collect::<Vec<_>>();
+        let mut draw = rng.gen::<f64>() * weighted.iter().map(|(weight, ..)| weight).sum::<f64>();
+        let chosen = weighted
+          .iter()
+          .position(|(weight, ..)| {
+            draw -= weight;
+            draw < 0.0
+          })
+          .unwrap_or(weighted.len() - 1);
+        let (_, production, bindings) = weighted.swap_remove(chosen);
+        (production, bindings)
+      }
+    };
+    let lookup = self.lookup(&bindings);
+    Some(
+      production
+        .successor
+        .iter()
+        .map(|(symbol, parameters)| {
+          let parameters = parameters
+            .iter()
+            .map(|parameter| parameter.evaluate(&lookup))
+            .collect();
+          self.module(*symbol, parameters)
+        })
+        .collect(),
+    )
+  }
+
+  // Values of the parameters of the production when it applies to the module in its context
+  fn bind<'a>(
+    &self,
+    production: &'a Production,
+    module: &Module,
+    context: &Context<Module>,
+  ) -> Option<Vec<(&'a str, f64)>> {
+    let ignored = |module: &Module| self.ignored.contains(&module.symbol);
+    let mut left = context.left(ignored);
+    let mut right = context.right(ignored);
+    let neighbours = std::iter::once((&production.predecessor, Some(module)))
+      .chain(
+        production
+          .left
+          .iter()
+          .rev()
+          .map(|pattern| (pattern, left.next())),
+      )
+      .chain(
+        production
+          .right
+          .iter()
+          .map(|pattern| (pattern, right.next())),
+      );
+    let mut bindings = vec![];
+    for ((symbol, formals), neighbour) in neighbours {
+      match neighbour {
+        Some(neighbour)
+          if neighbour.symbol == *symbol && neighbour.parameters.len() == formals.len() =>
+        {
+          bindings.extend(
+            formals
+              .iter()
+              .map(String::as_str)
+              .zip(neighbour.parameters.iter().copied()),
+          )
+        }
+        _ => return None,
+      }
+    }
+    Some(bindings)
+  }
+
+  fn lookup<'a>(&'a self, bindings: &'a [(&str, f64)]) -> impl Fn(&str) -> Option<f64> + 'a {
+    move |name| {
+      bindings
+        .iter()
+        .find(|(formal, _)| *formal == name)
+        .map(|(_, value)| *value)
+        .or_else(|| self.constant(name))
+    }
+  }
+
+  fn parse_production(&self, head: &str, successor: &str) -> Result<Production, String> {
+    let (predecessor, condition) = match head.split_once(':') {
+      Some((predecessor, condition)) => (predecessor, Some(condition)),
+      None => (head, None),
+    };
+    let (left, predecessor) = match predecessor.split_once('<') {
+      Some((left, predecessor)) => (parse_patterns(left)?, predecessor),
+      None => (vec![], predecessor),
+    };
+    let (predecessor, right) = match predecessor.split_once('>') {
+      Some((predecessor, right)) => (predecessor, parse_patterns(right)?),
+      None => (predecessor, vec![]),
+    };
+    let predecessor = match parse_patterns(predecessor)?.as_slice() {
+      [pattern] => pattern.clone(),
+      _ => {
+        return Err(format!(
+          "expected a single module before ->, found \"{}\"",
+          head.trim()
+        ))
+      }
+    };
+    let formals = left
+      .iter()
+      .chain(std::iter::once(&predecessor))
+      .chain(right.iter())
+      .flat_map(|(_, formals)| formals)
+      .collect::<Vec<_>>();
+    if let Some(duplicate) = formals
+      .iter()
+      .enumerate()
+      .find(|(i, formal)| formals[..*i].contains(formal))
+    {
+      return Err(format!("parameter {} named twice", duplicate.1));
+    }
+
+    let (successor, weight) = match successor.split_once(':') {
+      Some((successor, weight)) => (successor, Some(Expression::parse(weight)?)),
+      None => (successor, None),
+    };
+    let condition = condition.map(Expression::parse).transpose()?;
+    let successor = parse_modules(successor)?;
+    let expressions = condition
+      .iter()
+      .chain(weight.iter())
+      .chain(successor.iter().flat_map(|(_, parameters)| parameters));
+    for expression in expressions {
+      if let Some(unknown) = expression
+        .variables()
+        .into_iter()
+        .find(|name| !formals.iter().any(|formal| formal == name) && self.constant(name).is_none())
+      {
+        return Err(format!("unknown variable \"{}\"", unknown));
+      }
+    }
+    Ok(Production {
+      left,
+      predecessor,
+      right,
+      condition,
+      successor,
+      weight,
+    })
+  }
+
+  fn parse_axiom(&self, statement: &str) -> Result<Vec<(char, Vec<f64>)>, String> {
+    parse_modules(statement)?
+      .into_iter()
+      .map(|(symbol, parameters)| {
+        let parameters = parameters
+          .iter()
+          .map(|parameter| self.evaluate_constant(parameter))
+          .collect::<Result<Vec<_>, _>>()?;
+        Ok((symbol, parameters))
+      })
+      .collect()
+  }
+
+  fn parse_constant(&mut self, statement: &str) -> Result<(), String> {
+    let (name, value) = match statement.split_once('=') {
+      Some((name, value)) => (name.trim(), value),
+      None => return Err(format!("expected name = value, found \"{}\"", statement)),
+    };
+    let is_name = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+      && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !is_name {
+      return Err(format!("invalid constant name \"{}\"", name));
+    }
+    if self.constants.contains_key(name) {
+      return Err(format!("constant {} defined twice", name));
+    }
+    let value = self.constant_expression(value)?;
+    self.constants.insert(name.to_string(), value);
+    Ok(())
+  }
+
+  fn parse_command(&mut self, statement: &str) -> Result<(), String> {
+    let (symbols, command) = match statement.split_once('=') {
+      Some((symbols, command)) => (symbols, command.trim()),
+      None => {
+        return Err(format!(
+          "expected symbols = command, found \"{}\"",
+          statement
+        ))
+      }
+    };
+    let command = match command {
+      "vertex" => Command::Vertex,
+      "forward" => Command::Forward,
+      "left" => Command::Left,
+      "right" => Command::Right,
+      "push" => Command::Push,
+      "pop" => Command::Pop,
+      "polygon" => Command::NewPolygon,
+      "close" => Command::ClosePolygon,
+      "none" => Command::None,
+      _ => return Err(format!("unknown turtle command \"{}\"", command)),
+    };
+    symbols
+      .chars()
+      .filter(|c| !c.is_whitespace())
+      .for_each(|symbol| {
+        self.commands.insert(symbol, command);
+      });
+    Ok(())
+  }
+
+  fn constant_expression(&self, input: &str) -> Result<f64, String> {
+    self.evaluate_constant(&Expression::parse(input)?)
+  }
+
+  fn evaluate_constant(&self, expression: &Expression) -> Result<f64, String> {
+    match expression
+      .variables()
+      .into_iter()
+      .find(|name| self.constant(name).is_none())
+    {
+      Some(unknown) => Err(format!("unknown constant \"{}\"", unknown)),
+      None => Ok(expression.evaluate(&|name| self.constant(name))),
+    }
+  }
+}
+
+pub fn grammar_rule(
+  input: Module,
+  context: &Context<Module>,
+  grammar: &Grammar,
+  rng: &mut StdRng,
+) -> Vec<Module> {
+  match grammar.rewrite(&input, context, rng) {
+    Some(successor) => successor,
+    None => vec![input],
+  }
+}
+
+fn default_commands() -> HashMap<char, Command> {
+  HashMap::from([
+    ('F', Command::Forward),
+    ('G', Command::Forward),
+    ('.', Command::Vertex),
+    ('+', Command::Left),
+    ('-', Command::Right),
+    ('[', Command::Push),
+    (']', Command::Pop),
+    ('{', Command::NewPolygon),
+    ('}', Command::ClosePolygon),
+  ])
+}
+
+// Modules of a predecessor or of its context, their parameters being names
+fn parse_patterns(input: &str) -> Result<Vec<Pattern>, String> {
+  parse_modules(input)?
+    .into_iter()
+    .map(|(symbol, parameters)| {
+      if matches!(symbol, '[' | ']') {
+        return Err(format!(
+          "branches are not supported in \"{}\"",
+          input.trim()
+        ));
+      }
+      let formals = parameters
+        .iter()
+        .map(|parameter| match parameter {
+          Expression::Variable(name) => Ok(name.clone()),
+          _ => Err(format!("parameters of {} should be names", symbol)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+      Ok((symbol, formals))
+    })
+    .collect()
+}
+
+// Sequence of symbols, each optionally followed by its parameters between parentheses
+fn parse_modules(input: &str) -> Result<Vec<(char, Vec<Expression>)>, String> {
+  let chars = input
+    .chars()
+    .filter(|c| !c.is_whitespace())
+    .collect::<Vec<_>>();
+  let mut modules = vec![];
+  let mut i = 0;
+  while i < chars.len() {
+    let symbol = chars[i];
+    if matches!(symbol, '(' | ')' | ',') {
+      return Err(format!("unexpected '{}' in \"{}\"", symbol, input.trim()));
+    }
+    i += 1;
+    let mut parameters = vec![];
+    if chars.get(i) == Some(&'(') {
+      let mut depth = 0;
+      let mut start = i + 1;
+      loop {
+        match chars.get(i) {
+          Some('(') => depth += 1,
+          Some(')') if depth == 1 => break,
+          Some(')') => depth -= 1,
+          Some(',') if depth == 1 => {
+            parameters.push(chars[start..i].iter().collect::<String>());
+            start = i + 1;
+          }
+          Some(_) => {}
+          None => return Err(format!("unclosed parameters of {}", symbol)),
+        }
+        i += 1;
+      }
+      parameters.push(chars[start..i].iter().collect::<String>());
+      i += 1;
+    }
+    let parameters = parameters
+      .iter()
+      .map(|parameter| Expression::parse(parameter))
+      .collect::<Result<Vec<_>, _>>()?;
+    modules.push((symbol, parameters));
+  }
+  Ok(modules)
+}
+
+impl Display for GrammarError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      GrammarError::Io(error) => write!(f, "{}", error),
+      GrammarError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+    }
+  }
+}
+
+impl std::error::Error for GrammarError {}
+
+impl Display for Module {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.symbol)?;
+    if !self.parameters.is_empty() {
+      let parameters = self
+        .parameters
+        .iter()
+        .map(|parameter| parameter.to_string())
+        .collect::<Vec<_>>();
+      write!(f, "({})", parameters.join(", "))?;
+    }
+    Ok(())
+  }
+}
+
+impl TurtleInterpretation for Module {
+  fn to_turtle(&self) -> Turtle {
+    match self.command {
+      Command::Vertex => Turtle::Vertex,
+      Command::Forward => Turtle::Forward(self.parameters.first().copied().unwrap_or(1.0)),
+      Command::Left => Turtle::Left,
+      Command::Right => Turtle::Right,
+      Command::Push => Turtle::Push,
+      Command::Pop => Turtle::Pop,
+      Command::NewPolygon => Turtle::NewPolygon,
+      Command::ClosePolygon => Turtle::ClosePolygon,
+      Command::None => Turtle::None,
+    }
+  }
+}
@@ -1,39 +1,171 @@
+pub mod grammar;
 pub mod leaf;
 
-pub struct LSystem<T, F, P>
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::turtle::polygon::{Turtle, TurtleInterpretation};
+
+// Rewriting of a symbol, given its neighbours in the current word, the constants of the system
+// and the random generator of the system for stochastic productions
+pub trait Rules<T, P> {
+  fn rewrite(&mut self, symbol: T, context: &Context<T>, constants: &P, rng: &mut StdRng)
+    -> Vec<T>;
+}
+
+// Context-free and deterministic rules
+impl<T, P, F> Rules<T, P> for F
 where
   F: FnMut(T, &P) -> Vec<T>,
+{
+  fn rewrite(&mut self, symbol: T, _: &Context<T>, constants: &P, _: &mut StdRng) -> Vec<T> {
+    self(symbol, constants)
+  }
+}
+
+// Rules looking at the neighbours of the symbol or drawing from the random generator
+pub struct ContextRules<F>(pub F);
+
+impl<T, P, F> Rules<T, P> for ContextRules<F>
+where
+  F: FnMut(T, &Context<T>, &P, &mut StdRng) -> Vec<T>,
+{
+  fn rewrite(
+    &mut self,
+    symbol: T,
+    context: &Context<T>,
+    constants: &P,
+    rng: &mut StdRng,
+  ) -> Vec<T> {
+    (self.0)(symbol, context, constants, rng)
+  }
+}
+
+pub struct Context<'a, T> {
+  word: &'a [T],
+  index: usize,
+}
+
+impl<'a, T: TurtleInterpretation> Context<'a, T> {
+  // Symbols before this one on the path to the root of the branching structure, closest first.
+  // Branches ending on the way are skipped, as well as the ignored symbols.
+  pub fn left<I: Fn(&T) -> bool>(&self, ignored: I) -> impl Iterator<Item = &'a T> {
+    let word = self.word;
+    let mut i = self.index;
+    std::iter::from_fn(move || loop {
+      i = i.checked_sub(1)?;
+      match word[i].to_turtle() {
+        Turtle::Push => continue,
+        Turtle::Pop => {
+          let mut depth = 1;
+          while depth > 0 {
+            i = i.checked_sub(1)?;
+            match word[i].to_turtle() {
+              Turtle::Push => depth -= 1,
+              Turtle::Pop => depth += 1,
+              _ => {}
+            }
+          }
+        }
+        _ if ignored(&word[i]) => continue,
+        _ => return Some(&word[i]),
+      }
+    })
+  }
+
+  // Symbols after this one in the same branch, closest first, skipping the branches starting
+  // on the way and the ignored symbols
+  pub fn right<I: Fn(&T) -> bool>(&self, ignored: I) -> impl Iterator<Item = &'a T> {
+    let word = self.word;
+    let mut i = self.index;
+    std::iter::from_fn(move || loop {
+      i += 1;
+      match word.get(i)?.to_turtle() {
+        Turtle::Pop => return None,
+        Turtle::Push => {
+          let mut depth = 1;
+          while depth > 0 {
+            i += 1;
+            match word.get(i)?.to_turtle() {
+              Turtle::Push => depth += 1,
+              Turtle::Pop => depth -= 1,
+              _ => {}
+            }
+          }
+        }
+        _ if ignored(&word[i]) => continue,
+        _ => return Some(&word[i]),
+      }
+    })
+  }
+}
+
+pub struct LSystem<T, F, P>
+where
+  F: Rules<T, P>,
 {
   axiom: Vec<T>,
   rules: F,
   constants: P,
+  rng: StdRng,
+  // The derivation ends before a word longer than this
+  max_symbols: usize,
+  ended: bool,
 }
 
 impl<T, F, P> LSystem<T, F, P>
 where
-  F: FnMut(T, &P) -> Vec<T>,
+  F: Rules<T, P>,
 {
   pub fn new(axiom: Vec<T>, rules: F, constants: P) -> LSystem<T, F, P> {
     LSystem {
       axiom,
       rules,
       constants,
+      rng: StdRng::seed_from_u64(0),
+      max_symbols: usize::MAX,
+      ended: false,
     }
   }
+
+  pub fn seed(mut self, seed: u64) -> Self {
+    self.rng = StdRng::seed_from_u64(seed);
+    self
+  }
+
+  pub fn max_symbols(mut self, max_symbols: usize) -> Self {
+    self.max_symbols = max_symbols;
+    self
+  }
 }
 
 impl<T, F, P> Iterator for LSystem<T, F, P>
 where
   T: Clone,
-  F: FnMut(T, &P) -> Vec<T>,
+  F: Rules<T, P>,
 {
   type Item = Vec<T>;
 
   fn next(&mut self) -> Option<Vec<T>> {
+    if self.ended {
+      return None;
+    }
     let result = self.axiom.clone();
     let mut new_axiom = Vec::new();
-    for element in self.axiom.drain(..) {
-      new_axiom.extend((self.rules)(element, &self.constants).into_iter());
+    for (index, element) in self.axiom.iter().enumerate() {
+      let context = Context {
+        word: &self.axiom,
+        index,
+      };
+      new_axiom.extend(self.rules.rewrite(
+        element.clone(),
+        &context,
+        &self.constants,
+        &mut self.rng,
+      ));
+      if new_axiom.len() > self.max_symbols {
+        self.ended = true;
+        return Some(result);
+      }
     }
     self.axiom = new_axiom;
     Some(result)
@@ -84,9 +84,18 @@ impl StaticArtwork for Model {
     let max = (w / 2.0, h / 2.0).into();
     let root = Tile::new(min, max);
 
-    let max_children = 2u32.pow(self.depth);
+    let options = utils::algorithm::space::SpaceOptions {
+      max_leaves: 2usize.pow(self.depth),
+      min_size: MIN_SIZE,
+      ..Default::default()
+    };
     let mut rng = StdRng::seed_from_u64(self.base_model.seed);
-    let mut space = utils::algorithm::space::compute_space(root, max_children, MIN_SIZE, &mut rng);
+    let space = utils::algorithm::space::compute_space(
+      root,
+      &options,
+      &utils::algorithm::space::UniformSplit,
+      &mut rng,
+    );
     let leafs = space.leafs();
     leafs.iter().for_each(|index| {
       let tile = space.get_node(*index).unwrap().content();
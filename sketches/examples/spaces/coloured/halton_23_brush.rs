@@ -3,7 +3,7 @@ use nannou::{
   prelude::{Hsl, Key, BLACK, WHITE},
   App,
 };
-use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand::{rngs::StdRng, SeedableRng};
 use sketches::tile::Tile;
 use std::path::PathBuf;
 use utils::{
@@ -96,18 +96,36 @@ impl StaticArtwork for Model {
     let max = (w / 2.0, h / 2.0).into();
     let root = Tile::new(min, max);
 
-    let max_children = 2u32.pow(self.depth);
+    let options = utils::algorithm::space::SpaceOptions {
+      max_leaves: 2usize.pow(self.depth),
+      min_size: MIN_SIZE,
+      ..Default::default()
+    };
     let mut rng = StdRng::seed_from_u64(self.base_model.seed);
-    let mut space = utils::algorithm::space::compute_space(root, max_children, MIN_SIZE, &mut rng);
+    let space = utils::algorithm::space::compute_space(
+      root,
+      &options,
+      &utils::algorithm::space::UniformSplit,
+      &mut rng,
+    );
     let leafs = space.leafs();
-    leafs.iter().for_each(|index| {
+    // Neighbouring tiles get different colours
+    let adjacency = utils::algorithm::space::compute_adjacency(&space);
+    let colours = utils::algorithm::colouring::colour(
+      &adjacency.neighbour_lists(&leafs),
+      &utils::algorithm::colouring::ColouringOptions {
+        n_colours: COLOR_PALETTE.len(),
+        ..Default::default()
+      },
+      &mut rng,
+    );
+    leafs.iter().zip(colours).for_each(|(index, colour)| {
       let tile = space.get_node(*index).unwrap().content();
       let adjusted_rect = Rect::new(
         tile.rect.min() + (10.0, 10.0).into(),
         tile.rect.max() - (10.0, 10.0).into(),
       );
-      let index = rng.gen_range(0..COLOR_PALETTE.len());
-      let color = hsl_from_palette(COLOR_PALETTE[index]);
+      let color = hsl_from_palette(COLOR_PALETTE[colour]);
       utils::draw::filling::halton_23(
         adjusted_rect.to_polygon(),
         draw,
@@ -5,9 +5,16 @@ use nannou::{
   prelude::{Hsl, Key, WHITE},
   App,
 };
-use rand::{rngs::StdRng, SeedableRng};
-use rand_distr::{Bernoulli, Distribution};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use utils::app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork};
+use utils::geometry::grid::{Grid, GridKind, HexOrientation};
+
+const GRID_KINDS: [GridKind; 4] = [
+  GridKind::Square,
+  GridKind::Hexagonal(HexOrientation::Flat),
+  GridKind::Hexagonal(HexOrientation::Pointy),
+  GridKind::Triangular,
+];
 
 fn main() {
   make_static_artwork::<Model>().run();
@@ -17,6 +24,7 @@ struct Model {
   base_model: BaseModel,
   depth: usize,
   density: f32,
+  grid_kind: usize,
 }
 
 impl Artwork for Model {
@@ -25,6 +33,7 @@ impl Artwork for Model {
       base_model,
       depth: 0,
       density: 0.75,
+      grid_kind: 0,
     }
   }
   fn get_options() -> ArtworkOptions {
@@ -40,7 +49,10 @@ impl Artwork for Model {
     &mut self.base_model
   }
   fn current_frame_name(&self) -> String {
-    format!("frame_{}_{}", self.depth, self.base_model.seed)
+    format!(
+      "frame_{}_{}_{}",
+      self.depth, self.grid_kind, self.base_model.seed
+    )
   }
   fn key_pressed(&mut self, _app: &App, key: Key) {
     match key {
@@ -52,6 +64,7 @@ impl Artwork for Model {
       }
       Key::Left => self.density -= 0.05,
       Key::Right => self.density += 0.05,
+      Key::G => self.grid_kind = (self.grid_kind + 1) % GRID_KINDS.len(),
       _ => {}
     }
   }
@@ -71,19 +84,22 @@ impl StaticArtwork for Model {
     );
 
     let line_width = (rect.width().powi(2) + rect.height().powi(2)).sqrt() * 0.9;
-    let mut tiles = vec![rect];
-    for _ in 0..self.depth {
-      tiles = tile(tiles);
-    }
+    let size = rect.width() / 2f32.powi(self.depth as i32);
+    let grid = Grid::new(GRID_KINDS[self.grid_kind], rect, size);
     let mut rng = StdRng::seed_from_u64(self.base_model.seed);
-    tiles.iter().for_each(|tile| {
-      let axis = Bernoulli::new(0.5).unwrap().sample(&mut rng);
-      let (start, end) = match axis {
-        true => (tile.min(), tile.max()),
-        false => (
-          (tile.min().x, tile.max().y).into(),
-          (tile.max().x, tile.min().y).into(),
-        ),
+    grid.cells().iter().for_each(|cell| {
+      let vertices = grid.vertices(*cell);
+      let n_sides = vertices.len();
+      // A diagonal, or a median for triangles
+      let (start, end) = match n_sides % 2 {
+        0 => {
+          let i = rng.gen_range(0..n_sides / 2);
+          (vertices[i], vertices[i + n_sides / 2])
+        }
+        _ => {
+          let i = rng.gen_range(0..n_sides);
+          (vertices[i], grid.edge_midpoints(*cell)[(i + 1) % n_sides])
+        }
       };
       let weight = 0.004 * line_width as f32;
       utils::draw::line::pencil(
@@ -99,11 +115,3 @@ impl StaticArtwork for Model {
     });
   }
 }
-
-fn tile(tiles: Vec<Rect<f32>>) -> Vec<Rect<f32>> {
-  tiles
-    .iter()
-    .flat_map(|rect| rect.split_x().map(|x_rect| x_rect.split_y()))
-    .flatten()
-    .collect()
-}
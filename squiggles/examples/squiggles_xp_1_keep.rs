@@ -90,15 +90,24 @@ impl StaticArtwork for Model {
     let max = (w / 2.0, h / 2.0).into();
     let root = Tile::new(min, max);
 
-    let max_children = 2u32.pow(self.depth);
+    let options = utils::algorithm::space::SpaceOptions {
+      max_leaves: 2usize.pow(self.depth),
+      min_size: MIN_SIZE,
+      ..Default::default()
+    };
     let mut rng = StdRng::seed_from_u64(self.base_model.seed);
-    let mut space = utils::algorithm::space::compute_space(root, max_children, MIN_SIZE, &mut rng);
+    let space = utils::algorithm::space::compute_space(
+      root,
+      &options,
+      &utils::algorithm::space::UniformSplit,
+      &mut rng,
+    );
     let leafs = space.leafs();
     leafs.iter().for_each(|_| {
       let index = rng.gen_range(0..COLOR_PALETTE.len());
       let color = hsl_from_palette(COLOR_PALETTE[index]);
 
-      create_hatches(&mut space, &mut rng)
+      create_hatches(&space, &mut rng)
         .iter()
         .for_each(|(start, end)| {
           utils::draw::line::stroke(
@@ -119,7 +128,7 @@ impl StaticArtwork for Model {
 const HATCH_WEIGHT_MEAN: f32 = 0.1;
 const HATCH_WEIGHT_STD: f32 = 0.01;
 
-fn create_hatches(space: &mut Space<Tile>, rng: &mut StdRng) -> Vec<(Coord<f32>, Coord<f32>)> {
+fn create_hatches(space: &Space<Tile>, rng: &mut StdRng) -> Vec<(Coord<f32>, Coord<f32>)> {
   space
     .leafs()
     .iter()
@@ -0,0 +1,93 @@
+use geo::Rect;
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use utils::{
+  algorithm::flow_field::{evenly_spaced_streamlines, CurlField, StreamlineOptions},
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  draw::line::{LineOptions, PathOptions, Pressure},
+  noise::{Fbm, Simplex},
+};
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  separation: f32,
+  frequency: f32,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      separation: 30.0,
+      frequency: 0.001,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    format!("frame_{}", self.base_model.seed)
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Up => self.separation += 5.0,
+      Key::Down => self.separation = (self.separation - 5.0).max(5.0),
+      Key::Right => self.frequency *= 1.5,
+      Key::Left => self.frequency /= 1.5,
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+
+    let w = w_w as f32 * 0.9;
+    let h = w_h as f32 * 0.9;
+    let boundary = Rect::new((-w / 2.0, -h / 2.0), (w / 2.0, h / 2.0)).to_polygon();
+
+    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
+    let field = CurlField::new(Fbm::new(Simplex::new(self.base_model.seed)), self.frequency);
+    let options = StreamlineOptions {
+      separation: self.separation,
+      ..StreamlineOptions::default()
+    };
+    evenly_spaced_streamlines(&field, &boundary, &options, &mut rng)
+      .iter()
+      .for_each(|line_string| {
+        utils::draw::line::stroke_path(
+          line_string,
+          draw,
+          LineOptions {
+            weight: self.separation / 3.0,
+            density: 1.0,
+            color: Hsl::new(0.0, 0.0, 0.0),
+          },
+          &PathOptions {
+            pressure: Pressure::Taper {
+              start: 0.1,
+              end: 0.1,
+            },
+            ..PathOptions::default()
+          },
+        )
+      });
+  }
+}
@@ -0,0 +1,125 @@
+use geo::{Coord, LineString, Polygon};
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use utils::{
+  algorithm::growth::{
+    aggregate, branch_paths, space_colonisation, AggregationOptions, BranchGraph,
+    ColonisationOptions,
+  },
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  draw::line::{LineOptions, PathOptions, Pressure},
+  geometry::line::Join,
+};
+
+const N_MODES: usize = 2;
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  // Aggregation or space colonisation
+  mode: usize,
+  sticking_probability: f64,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      mode: 0,
+      sticking_probability: 1.0,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    String::from("frame")
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::M => self.mode = (self.mode + 1) % N_MODES,
+      Key::Up => self.sticking_probability = (self.sticking_probability + 0.1).min(1.0),
+      Key::Down => self.sticking_probability = (self.sticking_probability - 0.1).max(0.1),
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+
+    let w = w_w as f32 * 0.9;
+    let h = w_h as f32 * 0.9;
+    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
+
+    let graph: BranchGraph = match self.mode {
+      0 => aggregate(
+        Coord::zero(),
+        &AggregationOptions {
+          n_particles: 3000,
+          sticking_probability: self.sticking_probability,
+          tip_thickness: 0.5,
+          ..Default::default()
+        },
+        &mut rng,
+      ),
+      _ => {
+        // Crown of a tree above its trunk
+        let crown = Polygon::new(
+          LineString::from(vec![
+            (-w / 2.5, -h / 8.0),
+            (w / 2.5, -h / 8.0),
+            (w / 3.0, h / 3.0),
+            (0.0, h / 2.2),
+            (-w / 3.0, h / 3.0),
+          ]),
+          vec![],
+        );
+        space_colonisation(
+          (0.0, -h / 2.0).into(),
+          &crown,
+          &ColonisationOptions {
+            tip_thickness: 0.5,
+            ..Default::default()
+          },
+          &mut rng,
+        )
+      }
+    };
+
+    branch_paths(&graph).into_iter().for_each(|path| {
+      let max_thickness = path.max_thickness();
+      let line = path.line.clone();
+      utils::draw::line::brush_path(
+        &line,
+        draw,
+        LineOptions {
+          weight: max_thickness,
+          density: 0.8,
+          color: Hsl::new(0.08, 0.4, 0.2),
+        },
+        &PathOptions {
+          pressure: Pressure::Custom(Box::new(move |t| path.thickness_at(t) / max_thickness)),
+          join: Join::Round,
+          ..PathOptions::default()
+        },
+      );
+    });
+  }
+}
@@ -0,0 +1,117 @@
+use geo::{Coord, Rect};
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use utils::{
+  algorithm::marching_squares::{bands, contours, equidistant_levels},
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  data_structures::scalar_grid::ScalarGrid,
+  draw::{
+    filling::FillingOptions,
+    line::{LineOptions, PathOptions},
+  },
+  noise::{Fbm, Noise2, Perlin},
+};
+
+const SPACING: f32 = 4.0;
+const FREQUENCY: f32 = 0.004;
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  n_levels: usize,
+  // Hatches every other band between the isolines
+  filled: bool,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      n_levels: 12,
+      filled: true,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    String::from("frame")
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Up => self.n_levels += 1,
+      Key::Down => self.n_levels = self.n_levels.saturating_sub(1).max(1),
+      Key::F => self.filled = !self.filled,
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+
+    let w = w_w as f32 * 0.9;
+    let h = w_h as f32 * 0.9;
+    let rect = Rect::new((-w / 2.0, -h / 2.0), (w / 2.0, h / 2.0));
+    let noise = Fbm::new(Perlin::new(self.base_model.seed)).octaves(4);
+    let field = ScalarGrid::from_fn(rect, SPACING, |coord: Coord<f32>| {
+      noise.noise2(coord.x * FREQUENCY, coord.y * FREQUENCY)
+    });
+    let levels = equidistant_levels(&field, self.n_levels);
+
+    if self.filled {
+      bands(&field, &levels)
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| i % 2 == 1)
+        .for_each(|(i, band)| {
+          band.into_iter().for_each(|polygon| {
+            utils::draw::filling::stroke(
+              polygon,
+              draw,
+              if i % 4 == 1 { 45.0 } else { -45.0 },
+              FillingOptions {
+                weight: 1.0,
+                density: 0.15,
+                color: Hsl::new(0.1, 0.3, 0.5),
+              },
+            );
+          });
+        });
+    }
+
+    contours(&field, &levels)
+      .into_iter()
+      .enumerate()
+      .for_each(|(i, lines)| {
+        lines.into_iter().for_each(|line| {
+          utils::draw::line::stroke_path(
+            &line,
+            draw,
+            LineOptions {
+              // Every fifth isoline is an index contour
+              weight: if i % 5 == 4 { 2.5 } else { 1.0 },
+              density: 0.9,
+              color: Hsl::new(0.08, 0.5, 0.25),
+            },
+            &PathOptions::default(),
+          );
+        });
+      });
+  }
+}
@@ -0,0 +1,181 @@
+use geo::{Coord, Rect};
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use utils::{
+  algorithm::{
+    maze::{compute_maze, MazeAlgorithm, MazeLayout},
+    space::{compute_space, QuadSplit, SpaceOptions, SpaceTile},
+  },
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  draw::line::{LineOptions, PathOptions},
+  geometry::grid::{Grid, GridKind, HexOrientation},
+};
+
+const ALGORITHMS: [MazeAlgorithm; 5] = [
+  MazeAlgorithm::RecursiveBacktracker,
+  MazeAlgorithm::Kruskal,
+  MazeAlgorithm::Prim,
+  MazeAlgorithm::Wilson,
+  MazeAlgorithm::Eller,
+];
+const GRID_KINDS: [GridKind; 4] = [
+  GridKind::Square,
+  GridKind::Hexagonal(HexOrientation::Flat),
+  GridKind::Hexagonal(HexOrientation::Pointy),
+  GridKind::Triangular,
+];
+// Proportion of the segments around each corner replaced by a curve
+const ROUNDING: f32 = 0.4;
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Tile {
+  pub rect: Rect<f32>,
+}
+
+impl SpaceTile for Tile {
+  fn new(min: Coord<f32>, max: Coord<f32>) -> Self {
+    Tile {
+      rect: Rect::new(min, max),
+    }
+  }
+
+  fn width(&self) -> f32 {
+    self.rect.width()
+  }
+
+  fn height(&self) -> f32 {
+    self.rect.height()
+  }
+
+  fn min(&self) -> Coord<f32> {
+    self.rect.min()
+  }
+
+  fn max(&self) -> Coord<f32> {
+    self.rect.max()
+  }
+}
+
+struct Model {
+  base_model: BaseModel,
+  algorithm: usize,
+  // Grid kinds, then the leaves of a space
+  layout: usize,
+  cell_size: f32,
+  corridors: bool,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      algorithm: 0,
+      layout: 0,
+      cell_size: 30.0,
+      corridors: false,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    format!("frame_{:?}", ALGORITHMS[self.algorithm])
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    let n_layouts = GRID_KINDS.len() + 1;
+    match key {
+      Key::Right => self.algorithm = (self.algorithm + 1) % ALGORITHMS.len(),
+      Key::Left => self.algorithm = (self.algorithm + ALGORITHMS.len() - 1) % ALGORITHMS.len(),
+      Key::G => self.layout = (self.layout + 1) % n_layouts,
+      Key::Up => self.cell_size += 5.0,
+      Key::Down => self.cell_size = (self.cell_size - 5.0).max(10.0),
+      Key::C => self.corridors = !self.corridors,
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+
+    let w = w_w as f32 * 0.9;
+    let h = w_h as f32 * 0.9;
+    let rect = Rect::new((-w / 2.0, -h / 2.0), (w / 2.0, h / 2.0));
+    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
+
+    let layout = match GRID_KINDS.get(self.layout) {
+      Some(kind) => MazeLayout::from_grid(&Grid::new(*kind, rect, self.cell_size)),
+      None => {
+        let options = SpaceOptions {
+          max_leaves: usize::MAX,
+          min_size: self.cell_size,
+          ..Default::default()
+        };
+        let root = Tile::new(rect.min(), rect.max());
+        let space = compute_space(root, &options, &QuadSplit { jitter: 0.2 }, &mut rng);
+        MazeLayout::from_space(&space)
+      }
+    };
+    if layout.is_empty() {
+      return;
+    }
+    let maze = compute_maze(layout, ALGORITHMS[self.algorithm], &mut rng);
+    let start = 0;
+    let end = maze.furthest(start);
+
+    if self.corridors {
+      maze.corridors(ROUNDING).into_iter().for_each(|line| {
+        utils::draw::line::stroke_path(
+          &line,
+          draw,
+          LineOptions {
+            weight: self.cell_size / 3.0,
+            density: 0.8,
+            color: Hsl::new(0.0, 0.0, 0.0),
+          },
+          &PathOptions::default(),
+        );
+      });
+    } else {
+      maze.walls().into_iter().for_each(|wall| {
+        utils::draw::line::pencil(
+          wall.start,
+          wall.end,
+          draw,
+          LineOptions {
+            weight: 2.0,
+            density: 0.5,
+            color: Hsl::new(0.0, 0.0, 0.0),
+          },
+        );
+      });
+    }
+
+    utils::draw::line::stroke_path(
+      &maze.solution(start, end, ROUNDING),
+      draw,
+      LineOptions {
+        weight: 3.0,
+        density: 0.8,
+        color: Hsl::new(0.0, 0.7, 0.5),
+      },
+      &PathOptions::default(),
+    );
+  }
+}
@@ -0,0 +1,119 @@
+use geo::{LineString, Polygon};
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use utils::{
+  algorithm::packing::{
+    pack_circles, pack_circles_front_chain, pack_shapes, PackingOptions, RadiusDistribution,
+  },
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  draw::filling::FillingOptions,
+};
+
+const N_MODES: usize = 3;
+const N_SEGMENTS: usize = 64;
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  max_radius: f32,
+  mode: usize,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      max_radius: 80.0,
+      mode: 0,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    String::from("frame")
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Up => self.max_radius += 10.0,
+      Key::Down => self.max_radius = (self.max_radius - 10.0).max(10.0),
+      Key::Right => self.mode = (self.mode + 1) % N_MODES,
+      Key::Left => self.mode = (self.mode + N_MODES - 1) % N_MODES,
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+
+    let w = w_w as f32 * 0.9;
+    let h = w_h as f32 * 0.9;
+    let container = Polygon::new(
+      LineString::from(vec![
+        (-w / 4.0, -h / 4.0),
+        (-w / 3.0, h / 3.0),
+        (-w / 7.5, 0.0),
+        (w / 3.0, h / 3.0),
+        (w / 4.0, -h / 4.0),
+        (w / 5.0, -h / 3.0),
+      ]),
+      vec![],
+    );
+    let options = PackingOptions {
+      min_radius: 4.0,
+      max_radius: self.max_radius,
+      distribution: RadiusDistribution::PowerLaw(2.0),
+      padding: 4.0,
+      ..Default::default()
+    };
+    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
+
+    let shapes = match self.mode {
+      0 => pack_circles(&container, &options, &mut rng)
+        .iter()
+        .map(|circle| circle.to_polygon(N_SEGMENTS))
+        .collect::<Vec<_>>(),
+      1 => pack_circles_front_chain(&container, &options, &mut rng)
+        .iter()
+        .map(|circle| circle.to_polygon(N_SEGMENTS))
+        .collect::<Vec<_>>(),
+      _ => {
+        let triangle = Polygon::new(
+          LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (0.5, 0.866)]),
+          vec![],
+        );
+        pack_shapes(&container, &triangle, &options, &mut rng)
+      }
+    };
+
+    shapes.into_iter().for_each(|shape| {
+      utils::draw::filling::stroke(
+        shape,
+        draw,
+        rng.gen_range(0.0..180.0),
+        FillingOptions {
+          weight: 1.0,
+          density: 0.2,
+          color: Hsl::new(0.0, 0.0, 0.0),
+        },
+      );
+    });
+  }
+}
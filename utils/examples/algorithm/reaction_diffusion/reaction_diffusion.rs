@@ -0,0 +1,109 @@
+use geo::Rect;
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use utils::{
+  algorithm::{
+    marching_squares::isolines,
+    reaction_diffusion::{ReactionDiffusion, ReactionDiffusionOptions},
+  },
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  draw::line::{LineOptions, PathOptions},
+};
+
+// Feed and kill rates of the coral, mitosis, maze and holes patterns
+const PRESETS: [(f32, f32); 4] = [
+  (0.055, 0.062),
+  (0.0367, 0.0649),
+  (0.029, 0.057),
+  (0.039, 0.058),
+];
+const RESOLUTION: usize = 200;
+const LEVELS: [f32; 3] = [0.15, 0.25, 0.35];
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  preset: usize,
+  n_steps: usize,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      preset: 0,
+      n_steps: 3000,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    String::from("frame")
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::P => self.preset = (self.preset + 1) % PRESETS.len(),
+      Key::Up => self.n_steps += 1000,
+      Key::Down => self.n_steps = self.n_steps.saturating_sub(1000),
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+
+    let size = w_w.min(w_h) as f32 * 0.9;
+    let rect = Rect::new((-size / 2.0, -size / 2.0), (size / 2.0, size / 2.0));
+    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
+
+    let (feed, kill) = PRESETS[self.preset];
+    let mut simulation = ReactionDiffusion::new(
+      RESOLUTION,
+      RESOLUTION,
+      ReactionDiffusionOptions {
+        feed,
+        kill,
+        ..Default::default()
+      },
+    );
+    // The pattern grows inside a disk
+    let center = RESOLUTION as f32 / 2.0;
+    simulation.mask(|x, y| (x as f32 - center).hypot(y as f32 - center) < center * 0.95);
+    simulation.seed_random(20, 3, &mut rng);
+    simulation.steps(self.n_steps);
+
+    let field = simulation.field(rect);
+    LEVELS.iter().enumerate().for_each(|(i, level)| {
+      isolines(&field, *level).into_iter().for_each(|line| {
+        utils::draw::line::stroke_path(
+          &line,
+          draw,
+          LineOptions {
+            weight: 1.0,
+            density: 0.9,
+            color: Hsl::new(0.6, 0.5, 0.2 + 0.2 * i as f32),
+          },
+          &PathOptions::default(),
+        );
+      });
+    });
+  }
+}
@@ -0,0 +1,134 @@
+use geo::{Coord, Rect};
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use utils::{
+  algorithm::space::{compute_adjacency, compute_space, SpaceOptions, SpaceTile, UniformSplit},
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  draw::line::LineOptions,
+};
+
+const MIN_SIZE: f32 = 10.0;
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Tile {
+  pub rect: Rect<f32>,
+}
+
+impl SpaceTile for Tile {
+  fn new(min: Coord<f32>, max: Coord<f32>) -> Self {
+    Tile {
+      rect: Rect::new(min, max),
+    }
+  }
+
+  fn width(&self) -> f32 {
+    self.rect.width()
+  }
+
+  fn height(&self) -> f32 {
+    self.rect.height()
+  }
+
+  fn min(&self) -> Coord<f32> {
+    self.rect.min()
+  }
+
+  fn max(&self) -> Coord<f32> {
+    self.rect.max()
+  }
+}
+
+struct Model {
+  base_model: BaseModel,
+  depth: u32,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      depth: 4,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    String::from("frame")
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Up => self.depth += 1,
+      Key::Down => self.depth = self.depth.saturating_sub(1),
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+
+    let w = w_w as f32 * 0.9;
+    let h = w_h as f32 * 0.9;
+    let root = Tile::new((-w / 2.0, -h / 2.0).into(), (w / 2.0, h / 2.0).into());
+
+    let options = SpaceOptions {
+      max_leaves: 2usize.pow(self.depth),
+      min_size: MIN_SIZE,
+      ..Default::default()
+    };
+    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
+    let space = compute_space(root, &options, &UniformSplit, &mut rng);
+    let adjacency = compute_adjacency(&space);
+
+    // Highlight the neighbours of a random leaf
+    let leafs = space.leafs();
+    let selected = leafs[rng.gen_range(0..leafs.len())];
+    std::iter::once(&selected)
+      .chain(adjacency.neighbours(selected))
+      .for_each(|index| {
+        let tile = space.get_node(*index).unwrap().content();
+        let lightness = if *index == selected { 0.5 } else { 0.8 };
+        utils::draw::filling::stroke(
+          tile.rect.to_polygon(),
+          draw,
+          45.0,
+          utils::draw::filling::FillingOptions {
+            weight: 1.0,
+            density: 0.2,
+            color: Hsl::new(0.6, 0.5, lightness),
+          },
+        );
+      });
+
+    // Borders between leaves are drawn once
+    adjacency.edges().iter().for_each(|edge| {
+      utils::draw::line::pencil(
+        edge.line.start,
+        edge.line.end,
+        draw,
+        LineOptions {
+          weight: 2.0,
+          density: 0.5,
+          color: Hsl::new(0.0, 0.0, 0.0),
+        },
+      );
+    });
+  }
+}
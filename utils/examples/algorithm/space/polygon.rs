@@ -0,0 +1,119 @@
+use geo::{Coord, LineString, Polygon, Rect};
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use utils::{
+  algorithm::space::{compute_delaunay_space, compute_sliced_space, compute_voronoi_space},
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  draw::filling::FillingOptions,
+};
+
+const MIN_AREA: f32 = 100.0;
+const N_MODES: usize = 3;
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  n_points: usize,
+  mode: usize,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      n_points: 32,
+      mode: 0,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    String::from("frame")
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Up => self.n_points *= 2,
+      Key::Down => self.n_points = (self.n_points / 2).max(1),
+      Key::Right => self.mode = (self.mode + 1) % N_MODES,
+      Key::Left => self.mode = (self.mode + N_MODES - 1) % N_MODES,
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+
+    let w = w_w as f32 * 0.9;
+    let h = w_h as f32 * 0.9;
+    let rect = Rect::new(Coord::from((-w / 2.0, -h / 2.0)), (w / 2.0, h / 2.0).into());
+    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
+
+    let space = match self.mode {
+      0 => {
+        let points = utils::geometry::filling::halton_23(rect.to_polygon(), 1.0)
+          .take(self.n_points)
+          .collect::<Vec<_>>();
+        compute_voronoi_space(&points, rect.to_polygon())
+      }
+      1 => {
+        let points = (0..self.n_points)
+          .map(|_| {
+            Coord::from((
+              rng.gen_range(rect.min().x..rect.max().x),
+              rng.gen_range(rect.min().y..rect.max().y),
+            ))
+          })
+          .collect::<Vec<_>>();
+        compute_delaunay_space(&points)
+      }
+      _ => {
+        let radius = w.min(h) / 2.0;
+        let hexagon = Polygon::new(
+          LineString::from(
+            (0..6)
+              .map(|i| {
+                let theta = i as f32 * std::f32::consts::TAU / 6.0;
+                (radius * theta.cos(), radius * theta.sin())
+              })
+              .collect::<Vec<_>>(),
+          ),
+          vec![],
+        );
+        compute_sliced_space(hexagon, self.n_points, MIN_AREA, &mut rng)
+      }
+    };
+
+    let leafs = space.leafs();
+    leafs.iter().for_each(|index| {
+      let polygon = space.get_node(*index).unwrap().content().clone();
+      utils::draw::filling::stroke(
+        polygon,
+        draw,
+        rng.gen_range(0.0..180.0),
+        FillingOptions {
+          weight: 1.0,
+          density: 0.1,
+          color: Hsl::new(0.0, 0.0, 0.0),
+        },
+      );
+    });
+  }
+}
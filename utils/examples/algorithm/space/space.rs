@@ -5,11 +5,16 @@ use nannou::{
 };
 use rand::{rngs::StdRng, SeedableRng};
 use utils::{
-  algorithm::space::SpaceTile,
+  algorithm::space::{
+    compute_space, DensitySplit, ExpansionOrder, LongerSideSplit, MondrianSplit, QuadSplit,
+    RatioSplit, Space, SpaceOptions, SpaceTile, SplitStrategy, UniformSplit,
+  },
   app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
 };
 
 const MIN_SIZE: f32 = 1.0;
+const N_STRATEGIES: usize = 7;
+const N_ORDERS: usize = 3;
 
 fn main() {
   make_static_artwork::<Model>().run();
@@ -46,6 +51,25 @@ impl SpaceTile for Tile {
 struct Model {
   base_model: BaseModel,
   depth: u32,
+  strategy: usize,
+  order: usize,
+}
+
+impl Model {
+  fn compute<S: SplitStrategy>(&self, root: Tile, strategy: &S) -> Space<Tile> {
+    let options = SpaceOptions {
+      max_leaves: 2usize.pow(self.depth),
+      min_size: MIN_SIZE,
+      order: match self.order {
+        1 => ExpansionOrder::DepthFirst,
+        2 => ExpansionOrder::LargestFirst,
+        _ => ExpansionOrder::BreadthFirst,
+      },
+      ..Default::default()
+    };
+    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
+    compute_space(root, &options, strategy, &mut rng)
+  }
 }
 
 impl Artwork for Model {
@@ -53,6 +77,8 @@ impl Artwork for Model {
     Self {
       base_model,
       depth: 1,
+      strategy: 0,
+      order: 0,
     }
   }
   fn get_options() -> ArtworkOptions {
@@ -71,6 +97,9 @@ impl Artwork for Model {
     match key {
       Key::Up => self.depth += 1,
       Key::Down => self.depth -= 1,
+      Key::Right => self.strategy = (self.strategy + 1) % N_STRATEGIES,
+      Key::Left => self.strategy = (self.strategy + N_STRATEGIES - 1) % N_STRATEGIES,
+      Key::O => self.order = (self.order + 1) % N_ORDERS,
       _ => {}
     }
   }
@@ -89,17 +118,33 @@ impl StaticArtwork for Model {
     let max = (w / 2.0, h / 2.0).into();
     let root = Tile::new(min, max);
 
-    let max_children = 2u32.pow(self.depth);
-    let mut rng = StdRng::seed_from_u64(self.base_model.seed);
-    let mut space = utils::algorithm::space::compute_space(root, max_children, MIN_SIZE, &mut rng);
+    let space = match self.strategy {
+      1 => self.compute(root, &RatioSplit::golden()),
+      2 => self.compute(root, &LongerSideSplit { bias: 2.0 }),
+      3 => self.compute(
+        root,
+        &MondrianSplit {
+          min_aspect_ratio: 0.3,
+        },
+      ),
+      4 => self.compute(root, &QuadSplit { jitter: 0.0 }),
+      5 => self.compute(root, &QuadSplit { jitter: 0.5 }),
+      6 => {
+        // Subdivide more around the center
+        let radius = w.hypot(h) / 2.0;
+        let importance = |coord: Coord<f32>| 1.0 - coord.x.hypot(coord.y) / radius;
+        self.compute(root, &DensitySplit::new(importance, UniformSplit))
+      }
+      _ => self.compute(root, &UniformSplit),
+    };
     let leafs = space.leafs();
     leafs.iter().for_each(|index| {
       let tile = space.get_node(*index).unwrap().content();
       let center = tile.rect.center();
       draw
         .rect()
-        .x_y(center.x as f32, center.y as f32)
-        .w_h(tile.width() as f32 - 10.0, tile.height() as f32 - 10.0)
+        .x_y(center.x, center.y)
+        .w_h(tile.width() - 10.0, tile.height() - 10.0)
         .color(BLACK);
     });
   }
@@ -0,0 +1,98 @@
+use geo::{Coord, LineString};
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use std::path::PathBuf;
+use utils::{
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  draw::line::{Grain, LineOptions, PathOptions},
+};
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  weight: f32,
+  density: f32,
+  // Options catching on the grain of the paper texture, None when it could not be read
+  paper: Option<PathOptions>,
+  use_paper: bool,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      weight: 10.0,
+      density: 0.75,
+      paper: nannou::image::open(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../assets/images/paper.jpg"),
+      )
+      .map(|image| PathOptions {
+        grain: Grain::texture(&image, 1.0),
+        ..PathOptions::default()
+      })
+      .ok(),
+      use_paper: true,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    String::from("frame")
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Up => self.weight += 5.0,
+      Key::Down => self.weight -= 5.0,
+      Key::Left => self.density -= 0.05,
+      Key::Right => self.density += 0.05,
+      Key::G => self.use_paper = !self.use_paper,
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+    let procedural = PathOptions::default();
+    let path_options = match (&self.paper, self.use_paper) {
+      (Some(paper), true) => paper,
+      _ => &procedural,
+    };
+
+    (0..5)
+      .map(|i| {
+        let h = (i as f32 / 5. - 0.5) * w_h as f32 / 2.;
+        let start: Coord<f32> = (-(w_w as f32) * 0.90 / 2., h).into();
+        let end: Coord<f32> = (w_w as f32 * 0.90 / 2., h).into();
+        (start, end)
+      })
+      .for_each(|(start, end)| {
+        utils::draw::line::charcoal_path(
+          &LineString::new(vec![start, end]),
+          draw,
+          LineOptions {
+            weight: self.weight,
+            density: self.density,
+            color: Hsl::new(0.0, 0.0, 0.0),
+          },
+          path_options,
+        )
+      });
+  }
+}
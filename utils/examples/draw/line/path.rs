@@ -0,0 +1,102 @@
+use geo::Coord;
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use utils::{
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  draw::line::{LineOptions, PathOptions, Pressure},
+  geometry::{
+    curve::{sample_adaptive, CatmullRom},
+    line::Join,
+  },
+};
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  weight: f32,
+  density: f32,
+  taper: f32,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      weight: 10.0,
+      density: 0.75,
+      taper: 0.2,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    String::from("frame")
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Up => self.weight += 5.0,
+      Key::Down => self.weight -= 5.0,
+      Key::Left => self.density -= 0.05,
+      Key::Right => self.density += 0.05,
+      Key::W => self.taper += 0.05,
+      Key::S => self.taper -= 0.05,
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+
+    let media = [
+      utils::draw::line::stroke_path,
+      utils::draw::line::brush_path,
+      utils::draw::line::pencil_path,
+      utils::draw::line::marker_path,
+    ];
+    media.iter().enumerate().for_each(|(i, medium)| {
+      let h = (i as f32 / media.len() as f32 - 0.4) * w_h as f32 * 0.9;
+      let control_points = (0..6)
+        .map(|j| {
+          let x = (j as f32 / 5. - 0.5) * w_w as f32 * 0.9;
+          let y = h + if j % 2 == 0 { 0.0 } else { w_h as f32 / 10. };
+          Coord::from((x, y))
+        })
+        .collect();
+      let line_string = sample_adaptive(&CatmullRom::centripetal(control_points), 1.0);
+      medium(
+        &line_string,
+        draw,
+        LineOptions {
+          weight: self.weight,
+          density: self.density,
+          color: Hsl::new(0.0, 0.0, 0.0),
+        },
+        &PathOptions {
+          pressure: Pressure::Taper {
+            start: self.taper,
+            end: self.taper,
+          },
+          join: Join::Round,
+          ..PathOptions::default()
+        },
+      )
+    });
+  }
+}
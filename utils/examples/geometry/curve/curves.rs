@@ -0,0 +1,116 @@
+use geo::Coord;
+use nannou::{
+  prelude::{Key, Vec2, BLACK, WHITE},
+  App,
+};
+use utils::{
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  geometry::curve::{sample_adaptive, Arc, BSpline, CatmullRom, CubicBezier},
+};
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  tolerance: f32,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      tolerance: 1.0,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    String::from("frame")
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::Up => self.tolerance *= 2.0,
+      Key::Down => self.tolerance /= 2.0,
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, _] = self.base_model.texture.size();
+    let size = w_w as f32 * 0.9 / 2.0;
+
+    let control_points: Vec<Coord<f32>> = (0..8)
+      .map(|i| {
+        let x = (i as f32 / 7.0 - 0.5) * size;
+        let y = if i % 2 == 0 { -0.2 * size } else { 0.2 * size };
+        (x, y).into()
+      })
+      .collect();
+
+    let curves = [
+      sample_adaptive(
+        &CubicBezier::new(
+          (-size, size / 2.0).into(),
+          (-size / 2.0, size).into(),
+          (-size / 2.0, 0.0).into(),
+          (0.0, size / 2.0).into(),
+        ),
+        self.tolerance,
+      ),
+      sample_adaptive(
+        &Arc::elliptical(
+          (size / 2.0, size / 2.0).into(),
+          size / 3.0,
+          size / 5.0,
+          0.5,
+          0.0,
+          1.5 * std::f32::consts::PI,
+        ),
+        self.tolerance,
+      ),
+      sample_adaptive(
+        &CatmullRom::centripetal(
+          control_points
+            .iter()
+            .map(|coord| *coord + (-size / 2.0, -size / 2.0).into())
+            .collect(),
+        ),
+        self.tolerance,
+      ),
+      sample_adaptive(
+        &BSpline::clamped(
+          control_points
+            .iter()
+            .map(|coord| *coord + (size / 2.0, -size / 2.0).into())
+            .collect(),
+        ),
+        self.tolerance,
+      ),
+    ];
+
+    curves.iter().for_each(|line_string| {
+      let points = line_string
+        .coords()
+        .map(|coord| Vec2::new(coord.x, coord.y))
+        .collect::<Vec<_>>();
+      points.iter().for_each(|point| {
+        draw.ellipse().xy(*point).w_h(10.0, 10.0).color(BLACK);
+      });
+      draw.polyline().weight(4.0).color(BLACK).points(points);
+    });
+  }
+}
@@ -0,0 +1,108 @@
+use geo::{Coord, Rect};
+use nannou::{
+  prelude::{Hsl, Key, WHITE},
+  App,
+};
+use utils::{
+  app::{make_static_artwork, Artwork, ArtworkOptions, BaseModel, StaticArtwork},
+  draw::line::{LineOptions, PathOptions},
+  geometry::sdf::{hatch_mask, offsets, outline, Circle, RoundedRectangle, Sdf, Segment},
+  noise::Perlin,
+};
+
+const N_MODES: usize = 3;
+const SPACING: f32 = 2.0;
+
+fn main() {
+  make_static_artwork::<Model>().run();
+}
+
+struct Model {
+  base_model: BaseModel,
+  // Outline, concentric offsets or masked hatches
+  mode: usize,
+  smoothness: f32,
+}
+
+impl Artwork for Model {
+  fn new(base_model: BaseModel) -> Self {
+    Self {
+      base_model,
+      mode: 1,
+      smoothness: 40.0,
+    }
+  }
+  fn get_options() -> ArtworkOptions {
+    ArtworkOptions::default()
+  }
+  fn get_base_model(&self) -> &BaseModel {
+    &self.base_model
+  }
+  fn get_base_model_mut(&mut self) -> &mut BaseModel {
+    &mut self.base_model
+  }
+  fn current_frame_name(&self) -> String {
+    String::from("frame")
+  }
+  fn key_pressed(&mut self, _app: &App, key: Key) {
+    match key {
+      Key::M => self.mode = (self.mode + 1) % N_MODES,
+      Key::Up => self.smoothness += 10.0,
+      Key::Down => self.smoothness = (self.smoothness - 10.0).max(0.0),
+      _ => {}
+    }
+  }
+}
+
+impl StaticArtwork for Model {
+  fn draw(&mut self) {
+    let draw = &self.base_model.draw;
+
+    draw.background().color(WHITE);
+    let [w_w, w_h] = self.base_model.texture.size();
+
+    let w = w_w as f32 * 0.9;
+    let h = w_h as f32 * 0.9;
+    let rect = Rect::new((-w / 2.0, -h / 2.0), (w / 2.0, h / 2.0));
+    let size = w.min(h);
+
+    // Blob made of a rounded rectangle and a bar, with a grid of holes
+    let holes = Circle::new(Coord::zero(), size / 40.0).repeat((size / 8.0, size / 8.0).into());
+    let shape = RoundedRectangle::new(
+      Rect::new((-size / 4.0, -size / 3.0), (size / 8.0, size / 6.0)),
+      size / 20.0,
+    )
+    .smooth_union(
+      Segment::new(
+        (-size / 3.0, size / 4.0).into(),
+        (size / 3.0, -size / 6.0).into(),
+        size / 8.0,
+      ),
+      self.smoothness,
+    )
+    .smooth_difference(holes, self.smoothness / 4.0)
+    .warp(Perlin::new(self.base_model.seed), 3.0 / size, size / 40.0);
+
+    let options = LineOptions {
+      weight: 1.5,
+      density: 0.9,
+      color: Hsl::new(0.6, 0.4, 0.25),
+    };
+    let lines = match self.mode {
+      0 => outline(&shape, rect, SPACING).0,
+      1 => offsets(&shape, rect, SPACING, 8.0)
+        .into_iter()
+        .flat_map(|lines| lines.0)
+        .collect(),
+      _ => {
+        hatch_mask(&shape, rect, 0.15, 30.0, 1.0)
+          .into_iter()
+          .for_each(|line| utils::draw::line::pencil(line.start, line.end, draw, options));
+        outline(&shape, rect, SPACING).0
+      }
+    };
+    lines.iter().for_each(|line| {
+      utils::draw::line::stroke_path(line, draw, options, &PathOptions::default());
+    });
+  }
+}
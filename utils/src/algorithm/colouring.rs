@@ -0,0 +1,285 @@
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+#[derive(Clone, Copy)]
+pub enum ColouringSolver {
+  // Regions coloured in a random order, often leaving equal neighbours for the exact search
+  Greedy,
+  // Regions with the most differently coloured neighbours first (Brélaz).
+  // Needs fewer colours than the greedy solver to avoid equal neighbours.
+  Dsatur,
+}
+
+// Assignments the exact search may undo before keeping the heuristic colouring
+const MAX_BACKTRACKS: usize = 10_000;
+
+pub struct ColouringOptions {
+  pub n_colours: usize,
+  // Relative target proportion of each colour. Proportions past n_colours are ignored and
+  // missing ones take the mean of the given ones, which gives equal proportions when empty.
+  pub proportions: Vec<f32>,
+  // Weight of each region in the proportions (e.g. its area), 1.0 each when empty
+  pub weights: Vec<f32>,
+  pub solver: ColouringSolver,
+}
+
+impl Default for ColouringOptions {
+  fn default() -> Self {
+    Self {
+      n_colours: 4,
+      proportions: vec![],
+      weights: vec![],
+      solver: ColouringSolver::Dsatur,
+    }
+  }
+}
+
+// Assigns a colour index to each region of the adjacency graph, given as the list of neighbours
+// of each region. Each region takes the colour furthest below its target proportion among those
+// its neighbours do not use. When the solver leaves equal neighbours, an exact DSATUR search
+// with backtracking looks for a colouring without any. Equal neighbours only remain when there is
+// no such colouring with n_colours, or when the search gives up after MAX_BACKTRACKS.
+pub fn colour(
+  neighbours: &[Vec<usize>],
+  options: &ColouringOptions,
+  rng: &mut StdRng,
+) -> Vec<usize> {
+  let n_regions = neighbours.len();
+  if options.n_colours == 0 {
+    return vec![0; n_regions];
+  }
+  let weights = (0..n_regions)
+    .map(|region| options.weights.get(region).copied().unwrap_or(1.0).max(0.0))
+    .collect::<Vec<_>>();
+  let total_weight = weights.iter().sum::<f32>();
+  let targets = proportions(&options.proportions, options.n_colours)
+    .into_iter()
+    .map(|proportion| proportion * total_weight)
+    .collect();
+  let mut colouring = Colouring {
+    neighbours,
+    weights,
+    targets,
+    colours: vec![None; n_regions],
+    used: vec![0.0; options.n_colours],
+  };
+
+  let mut order = (0..n_regions).collect::<Vec<_>>();
+  order.shuffle(rng);
+  (0..n_regions).for_each(|step| {
+    let region = match options.solver {
+      ColouringSolver::Greedy => order[step],
+      ColouringSolver::Dsatur => colouring.next_saturated(&order).unwrap(),
+    };
+    let (_, candidates) = colouring.candidates(region, rng);
+    colouring.assign(region, candidates[0]);
+  });
+
+  let has_conflicts = (0..n_regions).any(|region| {
+    neighbours[region].iter().any(|neighbour| {
+      *neighbour != region && colouring.colours.get(*neighbour) == Some(&colouring.colours[region])
+    })
+  });
+  if has_conflicts {
+    let heuristic = std::mem::replace(&mut colouring.colours, vec![None; n_regions]);
+    colouring.used.iter_mut().for_each(|used| *used = 0.0);
+    let mut budget = MAX_BACKTRACKS;
+    if !colouring.search(&order, rng, &mut budget) {
+      colouring.colours = heuristic;
+    }
+  }
+
+  colouring
+    .colours
+    .into_iter()
+    .map(|colour| colour.unwrap_or(0))
+    .collect()
+}
+
+// Proportions normalised to sum to 1, see ColouringOptions
+fn proportions(given: &[f32], n_colours: usize) -> Vec<f32> {
+  let given = given
+    .iter()
+    .take(n_colours)
+    .map(|proportion| proportion.max(0.0))
+    .collect::<Vec<_>>();
+  let mean = match given.is_empty() {
+    true => 1.0,
+    false => given.iter().sum::<f32>() / given.len() as f32,
+  };
+  let proportions = (0..n_colours)
+    .map(|colour| given.get(colour).copied().unwrap_or(mean))
+    .collect::<Vec<_>>();
+  let sum = proportions.iter().sum::<f32>();
+  match sum > 0.0 && sum.is_finite() {
+    true => proportions
+      .iter()
+      .map(|proportion| proportion / sum)
+      .collect(),
+    false => vec![1.0 / n_colours as f32; n_colours],
+  }
+}
+
+struct Colouring<'a> {
+  neighbours: &'a [Vec<usize>],
+  weights: Vec<f32>,
+  // Weight each colour should cover
+  targets: Vec<f32>,
+  colours: Vec<Option<usize>>,
+  used: Vec<f32>,
+}
+
+impl<'a> Colouring<'a> {
+  fn assign(&mut self, region: usize, colour: usize) {
+    self.colours[region] = Some(colour);
+    self.used[colour] += self.weights[region];
+  }
+
+  fn unassign(&mut self, region: usize) {
+    if let Some(colour) = self.colours[region].take() {
+      self.used[colour] -= self.weights[region];
+    }
+  }
+
+  // Colours with the fewest neighbours already using them, along with that number. The most
+  // under-represented colours come first, ties being broken randomly.
+  fn candidates(&self, region: usize, rng: &mut StdRng) -> (usize, Vec<usize>) {
+    let n_colours = self.used.len();
+    let mut conflicts = vec![0; n_colours];
+    self.neighbours[region]
+      .iter()
+      .filter(|neighbour| **neighbour != region)
+      .filter_map(|neighbour| self.colours.get(*neighbour).copied().flatten())
+      .for_each(|colour| conflicts[colour] += 1);
+    let min_conflicts = *conflicts.iter().min().unwrap();
+    let mut candidates = (0..n_colours)
+      .filter(|colour| conflicts[*colour] == min_conflicts)
+      .collect::<Vec<_>>();
+    candidates.shuffle(rng);
+    let deficit = |colour: &usize| self.targets[*colour] - self.used[*colour];
+    candidates.sort_by(|a, b| deficit(b).total_cmp(&deficit(a)));
+    (min_conflicts, candidates)
+  }
+
+  // Colours the remaining regions without equal neighbours, most saturated first, undoing the
+  // assignments leading to a region without any free colour
+  fn search(&mut self, order: &[usize], rng: &mut StdRng, budget: &mut usize) -> bool {
+    let region = match self.next_saturated(order) {
+      Some(region) => region,
+      None => return true,
+    };
+    let (min_conflicts, candidates) = self.candidates(region, rng);
+    if min_conflicts > 0 {
+      return false;
+    }
+    for colour in candidates {
+      self.assign(region, colour);
+      if self.search(order, rng, budget) {
+        return true;
+      }
+      self.unassign(region);
+      if *budget == 0 {
+        return false;
+      }
+      *budget -= 1;
+    }
+    false
+  }
+
+  // Uncoloured region with the most distinct neighbour colours, then the most uncoloured
+  // neighbours. Remaining ties are broken by the random order.
+  fn next_saturated(&self, order: &[usize]) -> Option<usize> {
+    order
+      .iter()
+      .copied()
+      .filter(|region| self.colours[*region].is_none())
+      .map(|region| {
+        let mut neighbour_colours = self.neighbours[region]
+          .iter()
+          .filter_map(|neighbour| self.colours.get(*neighbour).copied().flatten())
+          .collect::<Vec<_>>();
+        neighbour_colours.sort_unstable();
+        neighbour_colours.dedup();
+        let uncoloured = self.neighbours[region]
+          .iter()
+          .filter(|neighbour| self.colours.get(**neighbour).copied().flatten().is_none())
+          .count();
+        (region, (neighbour_colours.len(), uncoloured))
+      })
+      .fold(
+        None,
+        |best: Option<(usize, (usize, usize))>, (region, score)| match best {
+          Some((_, best_score)) if best_score >= score => best,
+          _ => Some((region, score)),
+        },
+      )
+      .map(|(region, _)| region)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::algorithm::space::{compute_polygon_adjacency, compute_voronoi_space};
+  use geo::{Coord, Rect};
+  use rand::{Rng, SeedableRng};
+
+  fn voronoi_neighbours(n_cells: usize, rng: &mut StdRng) -> Vec<Vec<usize>> {
+    let points = (0..n_cells)
+      .map(|_| Coord::from((rng.gen_range(0.0..500.0), rng.gen_range(0.0..500.0))))
+      .collect::<Vec<_>>();
+    let boundary = Rect::new(Coord::zero(), Coord::from((500.0, 500.0))).to_polygon();
+    let space = compute_voronoi_space(&points, boundary);
+    let adjacency = compute_polygon_adjacency(&space);
+    adjacency.neighbour_lists(&space.leafs())
+  }
+
+  fn n_equal_neighbours(neighbours: &[Vec<usize>], colours: &[usize]) -> usize {
+    neighbours
+      .iter()
+      .enumerate()
+      .flat_map(|(region, neighbours)| neighbours.iter().map(move |neighbour| (region, *neighbour)))
+      .filter(|(region, neighbour)| region < neighbour && colours[*region] == colours[*neighbour])
+      .count()
+  }
+
+  #[test]
+  fn maps_get_four_colours_without_equal_neighbours() {
+    let mut rng = StdRng::seed_from_u64(0);
+    (0..50).for_each(|_| {
+      let neighbours = voronoi_neighbours(60, &mut rng);
+      [ColouringSolver::Greedy, ColouringSolver::Dsatur]
+        .into_iter()
+        .for_each(|solver| {
+          let options = ColouringOptions {
+            solver,
+            ..Default::default()
+          };
+          let colours = colour(&neighbours, &options, &mut rng);
+          assert_eq!(colours.len(), neighbours.len());
+          assert!(colours.iter().all(|colour| *colour < options.n_colours));
+          assert_eq!(n_equal_neighbours(&neighbours, &colours), 0);
+        });
+    });
+  }
+
+  #[test]
+  fn too_few_colours_still_give_a_colouring() {
+    // A triangle needs three colours
+    let neighbours = vec![vec![1, 2], vec![0, 2], vec![0, 1]];
+    let options = ColouringOptions {
+      n_colours: 2,
+      ..Default::default()
+    };
+    let colours = colour(&neighbours, &options, &mut StdRng::seed_from_u64(0));
+    assert_eq!(n_equal_neighbours(&neighbours, &colours), 1);
+  }
+
+  #[test]
+  fn proportions_are_fitted_to_the_colours() {
+    assert_eq!(proportions(&[], 4), vec![0.25; 4]);
+    assert_eq!(proportions(&[3.0, 1.0, 5.0], 2), vec![0.75, 0.25]);
+    // The missing colour takes the mean of the given proportions
+    assert_eq!(proportions(&[1.0, 3.0], 3), vec![1.0 / 6.0, 0.5, 1.0 / 3.0]);
+    assert_eq!(proportions(&[0.0, -1.0], 2), vec![0.5; 2]);
+  }
+}
@@ -0,0 +1,242 @@
+use crate::data_structures::spatial_grid::SpatialGrid;
+use geo::{Coord, Intersects, LineString, Polygon};
+use rand::{rngs::StdRng, Rng};
+use std::f32::consts::TAU;
+
+pub struct GrowthOptions {
+  // Distance under which nodes push each other away
+  pub repulsion_radius: f32,
+  // Proportion of the overlap with the repulsion radius a node moves away by
+  pub repulsion: f32,
+  // Proportion of the distance to the middle of its neighbours a node moves by
+  pub attraction: f32,
+  // Edges longer than this are split in two
+  pub max_edge_length: f32,
+  // Probability for any edge to be split at each step, driving the growth
+  pub split_probability: f64,
+  // Maximum distance travelled by a node in one step
+  pub max_speed: f32,
+  // Maximum random displacement of a node in one step
+  pub jitter: f32,
+  // Edges stop splitting once the line has this many nodes
+  pub max_nodes: usize,
+  // Ends of open lines stay in place
+  pub pin_ends: bool,
+}
+
+impl Default for GrowthOptions {
+  fn default() -> Self {
+    Self {
+      repulsion_radius: 10.0,
+      repulsion: 0.5,
+      attraction: 0.2,
+      max_edge_length: 5.0,
+      split_probability: 0.01,
+      max_speed: 2.0,
+      jitter: 0.1,
+      max_nodes: 5000,
+      pin_ends: true,
+    }
+  }
+}
+
+// Line growing by splitting its edges while its nodes push each other away, giving meandering
+// organic outlines. Closed lines are given with their first coordinate repeated at the end.
+pub struct DifferentialGrowth {
+  nodes: Vec<Coord<f32>>,
+  closed: bool,
+  // Nodes leaving the boundary stay where they were
+  boundary: Option<Polygon<f32>>,
+  options: GrowthOptions,
+  n_steps: usize,
+}
+
+impl DifferentialGrowth {
+  pub fn new(line_string: LineString<f32>, options: GrowthOptions) -> Self {
+    let closed = line_string.is_closed() && line_string.0.len() > 2;
+    let mut nodes = line_string.0;
+    if closed {
+      nodes.pop();
+    }
+    Self {
+      nodes,
+      closed,
+      boundary: None,
+      options,
+      n_steps: 0,
+    }
+  }
+
+  // Closed line sampled on a circle
+  pub fn circle(center: Coord<f32>, radius: f32, n_nodes: usize, options: GrowthOptions) -> Self {
+    let mut coords = (0..n_nodes.max(3))
+      .map(|i| {
+        let angle = TAU * i as f32 / n_nodes.max(3) as f32;
+        center + Coord::from((angle.cos(), angle.sin())) * radius
+      })
+      .collect::<Vec<_>>();
+    coords.push(coords[0]);
+    Self::new(LineString::new(coords), options)
+  }
+
+  pub fn boundary(mut self, boundary: Polygon<f32>) -> Self {
+    self.boundary = Some(boundary);
+    self
+  }
+
+  pub fn nodes(&self) -> &[Coord<f32>] {
+    &self.nodes
+  }
+
+  pub fn is_closed(&self) -> bool {
+    self.closed
+  }
+
+  pub fn n_steps(&self) -> usize {
+    self.n_steps
+  }
+
+  // Current state of the line, closed again if it was given closed
+  pub fn line_string(&self) -> LineString<f32> {
+    let mut coords = self.nodes.clone();
+    if self.closed {
+      coords.extend(self.nodes.first());
+    }
+    LineString::new(coords)
+  }
+
+  pub fn grow(&mut self, n_steps: usize, rng: &mut StdRng) -> LineString<f32> {
+    (0..n_steps).for_each(|_| self.step(rng));
+    self.line_string()
+  }
+
+  // Moves all the nodes at once, then splits the edges grown too long and a few random ones
+  pub fn step(&mut self, rng: &mut StdRng) {
+    let n = self.nodes.len();
+    if n < 2 {
+      return;
+    }
+    let options = &self.options;
+    let mut grid = SpatialGrid::new(options.repulsion_radius);
+    self
+      .nodes
+      .iter()
+      .enumerate()
+      .for_each(|(i, node)| grid.insert(*node, i));
+
+    let moved = (0..n)
+      .map(|i| {
+        let node = self.nodes[i];
+        let (previous, next) = self.neighbours(i);
+        if options.pin_ends && (previous.is_none() || next.is_none()) {
+          return node;
+        }
+
+        // Direct neighbours are kept at distance by the attraction and the splits
+        let (repulsion, n_repelling) = grid
+          .query(node, options.repulsion_radius)
+          .filter(|(_, j)| *j != i && Some(*j) != previous && Some(*j) != next)
+          .fold(
+            (Coord::zero(), 0),
+            |(repulsion, n_repelling), (other, _)| {
+              let away = node - *other;
+              let distance = away.x.hypot(away.y).max(f32::EPSILON);
+              (
+                repulsion + away / distance * (options.repulsion_radius - distance),
+                n_repelling + 1,
+              )
+            },
+          );
+        let mut displacement = match n_repelling {
+          0 => Coord::zero(),
+          _ => repulsion / n_repelling as f32 * options.repulsion,
+        };
+        let attractors = [previous, next].into_iter().flatten().collect::<Vec<_>>();
+        if !attractors.is_empty() {
+          let middle = attractors
+            .iter()
+            .fold(Coord::zero(), |sum, j| sum + self.nodes[*j])
+            / attractors.len() as f32;
+          displacement = displacement + (middle - node) * options.attraction;
+        }
+        if options.jitter > 0.0 {
+          let angle = rng.gen_range(0.0..TAU);
+          displacement = displacement
+            + Coord::from((angle.cos(), angle.sin())) * rng.gen_range(0.0..options.jitter);
+        }
+
+        let length = displacement.x.hypot(displacement.y);
+        if length > options.max_speed {
+          displacement = displacement * (options.max_speed / length);
+        }
+        let position = node + displacement;
+        match &self.boundary {
+          Some(boundary) if !boundary.intersects(&position) => node,
+          _ => position,
+        }
+      })
+      .collect::<Vec<_>>();
+    self.nodes = moved;
+    self.split(rng);
+    self.n_steps += 1;
+  }
+
+  fn neighbours(&self, i: usize) -> (Option<usize>, Option<usize>) {
+    let n = self.nodes.len();
+    match self.closed {
+      true => (Some((i + n - 1) % n), Some((i + 1) % n)),
+      false => (i.checked_sub(1), Some(i + 1).filter(|next| *next < n)),
+    }
+  }
+
+  fn split(&mut self, rng: &mut StdRng) {
+    let n = self.nodes.len();
+    // Probabilities that are not finite never split
+    let probability = match self.options.split_probability {
+      probability if probability.is_finite() => probability.clamp(0.0, 1.0),
+      _ => 0.0,
+    };
+    let n_edges = if self.closed { n } else { n - 1 };
+    let mut nodes = Vec::with_capacity(n);
+    let mut n_nodes = n;
+    (0..n).for_each(|i| {
+      let node = self.nodes[i];
+      nodes.push(node);
+      if i >= n_edges || n_nodes >= self.options.max_nodes {
+        return;
+      }
+      let next = self.nodes[(i + 1) % n];
+      let edge = next - node;
+      if edge.x.hypot(edge.y) > self.options.max_edge_length || rng.gen_bool(probability) {
+        nodes.push((node + next) / 2.0);
+        n_nodes += 1;
+      }
+    });
+    self.nodes = nodes;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand::SeedableRng;
+
+  #[test]
+  fn split_probability_is_clamped() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let grow = |split_probability: f64, rng: &mut StdRng| {
+      let options = GrowthOptions {
+        max_edge_length: f32::MAX,
+        split_probability,
+        ..Default::default()
+      };
+      let mut growth = DifferentialGrowth::circle(Coord::zero(), 50.0, 20, options);
+      growth.step(rng);
+      growth.nodes().len()
+    };
+    // Every edge is split, or none
+    assert_eq!(grow(2.0, &mut rng), 40);
+    assert_eq!(grow(-1.0, &mut rng), 20);
+    assert_eq!(grow(f64::NAN, &mut rng), 20);
+  }
+}
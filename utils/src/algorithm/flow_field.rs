@@ -0,0 +1,423 @@
+use crate::{data_structures::spatial_grid::SpatialGrid, geometry::line::normals, noise::Noise2};
+use geo::{BoundingRect, Coord, EuclideanLength, Intersects, LineString, Polygon, Rect};
+use rand::{rngs::StdRng, Rng};
+use std::{collections::VecDeque, f32::consts::TAU};
+
+// Number of random seeds tried to start streamlines in areas left empty
+const N_RANDOM_SEEDS: usize = 100;
+// Seeds are taken exactly one separation away from their streamline, the test is a bit looser so
+// that rounding does not reject them
+const SEED_TOLERANCE: f32 = 0.99;
+// Samples of a streamline less than this many test distances away along it are not collisions
+const SELF_GAP: f32 = 2.0;
+
+pub trait VectorField {
+  fn vector_at(&self, coord: Coord<f32>) -> Coord<f32>;
+}
+
+// Noise values are used as angles
+pub struct NoiseField<N> {
+  noise: N,
+  frequency: f32,
+}
+
+impl<N: Noise2> NoiseField<N> {
+  pub fn new(noise: N, frequency: f32) -> Self {
+    Self { noise, frequency }
+  }
+}
+
+impl<N: Noise2> VectorField for NoiseField<N> {
+  fn vector_at(&self, coord: Coord<f32>) -> Coord<f32> {
+    let theta = TAU
+      * self
+        .noise
+        .noise2(coord.x * self.frequency, coord.y * self.frequency);
+    (theta.cos(), theta.sin()).into()
+  }
+}
+
+// Curl of the noise used as a potential.
+// The field is divergence free: lines swirl around without converging.
+pub struct CurlField<N> {
+  noise: N,
+  frequency: f32,
+}
+
+impl<N: Noise2> CurlField<N> {
+  pub fn new(noise: N, frequency: f32) -> Self {
+    Self { noise, frequency }
+  }
+}
+
+impl<N: Noise2> VectorField for CurlField<N> {
+  fn vector_at(&self, coord: Coord<f32>) -> Coord<f32> {
+    const EPSILON: f32 = 1e-3;
+    let (x, y) = (coord.x * self.frequency, coord.y * self.frequency);
+    let dx = self.noise.noise2(x + EPSILON, y) - self.noise.noise2(x - EPSILON, y);
+    let dy = self.noise.noise2(x, y + EPSILON) - self.noise.noise2(x, y - EPSILON);
+    Coord::from((dy, -dx)) / (2.0 * EPSILON)
+  }
+}
+
+// Point pulling (positive strength) or pushing (negative strength) the flow
+pub struct Attractor {
+  pub position: Coord<f32>,
+  pub strength: f32,
+}
+
+pub struct AttractorField {
+  attractors: Vec<Attractor>,
+}
+
+impl AttractorField {
+  pub fn new(attractors: Vec<Attractor>) -> Self {
+    Self { attractors }
+  }
+}
+
+impl VectorField for AttractorField {
+  fn vector_at(&self, coord: Coord<f32>) -> Coord<f32> {
+    self
+      .attractors
+      .iter()
+      .fold(Coord::zero(), |vector, attractor| {
+        let direction = attractor.position - coord;
+        let squared_distance = (direction.x * direction.x + direction.y * direction.y).max(1.0);
+        vector + direction * (attractor.strength / squared_distance)
+      })
+  }
+}
+
+// Gradient of a grayscale image stretched over rect.
+// Values are stored row by row, the first row being the top of the image.
+pub struct ImageGradientField {
+  values: Vec<f32>,
+  width: usize,
+  height: usize,
+  rect: Rect<f32>,
+  // Follow the iso-luminance lines instead of climbing the gradient
+  along_contours: bool,
+}
+
+impl ImageGradientField {
+  pub fn new(
+    values: Vec<f32>,
+    width: usize,
+    height: usize,
+    rect: Rect<f32>,
+    along_contours: bool,
+  ) -> Self {
+    Self {
+      values,
+      width,
+      height,
+      rect,
+      along_contours,
+    }
+  }
+
+  // Bilinear interpolation of the pixel values
+  fn value_at(&self, coord: Coord<f32>) -> f32 {
+    if self.width == 0 || self.height == 0 {
+      return 0.0;
+    }
+    let x = ((coord.x - self.rect.min().x) / self.rect.width() * self.width as f32 - 0.5)
+      .clamp(0.0, (self.width - 1) as f32);
+    let y = ((self.rect.max().y - coord.y) / self.rect.height() * self.height as f32 - 0.5)
+      .clamp(0.0, (self.height - 1) as f32);
+    let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+    let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+    let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+    let value = |x: usize, y: usize| self.values.get(y * self.width + x).copied().unwrap_or(0.0);
+    let top = value(x0, y0) + (value(x1, y0) - value(x0, y0)) * tx;
+    let bottom = value(x0, y1) + (value(x1, y1) - value(x0, y1)) * tx;
+    top + (bottom - top) * ty
+  }
+}
+
+impl VectorField for ImageGradientField {
+  fn vector_at(&self, coord: Coord<f32>) -> Coord<f32> {
+    let epsilon_x = self.rect.width() / self.width.max(1) as f32;
+    let epsilon_y = self.rect.height() / self.height.max(1) as f32;
+    let dx = self.value_at(coord + (epsilon_x, 0.0).into())
+      - self.value_at(coord - (epsilon_x, 0.0).into());
+    let dy = self.value_at(coord + (0.0, epsilon_y).into())
+      - self.value_at(coord - (0.0, epsilon_y).into());
+    let gradient = Coord::from((dx / (2.0 * epsilon_x), dy / (2.0 * epsilon_y)));
+    match self.along_contours {
+      true => (-gradient.y, gradient.x).into(),
+      false => gradient,
+    }
+  }
+}
+
+#[derive(Clone, Copy)]
+pub enum Integrator {
+  Euler,
+  RungeKutta4,
+}
+
+pub struct StreamlineOptions {
+  // Length of an integration step
+  pub step: f32,
+  pub max_steps: usize,
+  pub integrator: Integrator,
+  // Minimum distance between two evenly spaced streamlines
+  pub separation: f32,
+  // Proportion of the separation under which a growing streamline stops near another one
+  pub test_ratio: f32,
+  // Shorter streamlines are discarded
+  pub min_length: f32,
+}
+
+impl Default for StreamlineOptions {
+  fn default() -> Self {
+    Self {
+      step: 2.0,
+      max_steps: 1000,
+      integrator: Integrator::RungeKutta4,
+      separation: 20.0,
+      test_ratio: 0.5,
+      min_length: 10.0,
+    }
+  }
+}
+
+// Traces the streamline going through seed in both directions, until it leaves the boundary or
+// comes back close to itself
+pub fn streamline<F: VectorField>(
+  field: &F,
+  seed: Coord<f32>,
+  boundary: &Polygon<f32>,
+  options: &StreamlineOptions,
+) -> LineString<f32> {
+  trace(field, seed, boundary, options, None)
+}
+
+// Fills the boundary with streamlines separated by at least options.separation,
+// following Jobard and Lefer "Creating evenly-spaced streamlines of arbitrary density".
+// New seeds are taken on each side of the existing streamlines.
+pub fn evenly_spaced_streamlines<F: VectorField>(
+  field: &F,
+  boundary: &Polygon<f32>,
+  options: &StreamlineOptions,
+  rng: &mut StdRng,
+) -> Vec<LineString<f32>> {
+  let mut grid = SpatialGrid::new(options.separation);
+  let mut lines: Vec<LineString<f32>> = vec![];
+  let mut queue: VecDeque<usize> = VecDeque::new();
+  let bounding_rect = match boundary.bounding_rect() {
+    Some(bounding_rect) => bounding_rect,
+    None => return lines,
+  };
+  loop {
+    while let Some(index) = queue.pop_front() {
+      let seeds = lines[index]
+        .coords()
+        .zip(normals(&lines[index]))
+        .flat_map(|(coord, normal)| {
+          [-1.0, 1.0].map(|side| *coord + normal * (side * options.separation))
+        })
+        .collect::<Vec<_>>();
+      seeds.into_iter().for_each(|seed| {
+        if let Some(line) = seed_streamline(field, seed, boundary, options, &mut grid) {
+          queue.push_back(lines.len());
+          lines.push(line);
+        }
+      });
+    }
+    // Look for areas the propagation could not reach
+    let line = (0..N_RANDOM_SEEDS).find_map(|_| {
+      let seed = Coord::from((
+        rng.gen_range(bounding_rect.min().x..=bounding_rect.max().x),
+        rng.gen_range(bounding_rect.min().y..=bounding_rect.max().y),
+      ));
+      seed_streamline(field, seed, boundary, options, &mut grid)
+    });
+    match line {
+      Some(line) => {
+        queue.push_back(lines.len());
+        lines.push(line);
+      }
+      None => return lines,
+    }
+  }
+}
+
+// Traces a streamline from seed if it is far enough from the existing ones
+fn seed_streamline<F: VectorField>(
+  field: &F,
+  seed: Coord<f32>,
+  boundary: &Polygon<f32>,
+  options: &StreamlineOptions,
+  grid: &mut SpatialGrid<()>,
+) -> Option<LineString<f32>> {
+  let too_close = grid
+    .query(seed, options.separation * SEED_TOLERANCE)
+    .next()
+    .is_some();
+  if !boundary.intersects(&seed) || too_close {
+    return None;
+  }
+  let line = trace(field, seed, boundary, options, Some(grid));
+  if line.euclidean_length() < options.min_length {
+    return None;
+  }
+  line.coords().for_each(|coord| grid.insert(*coord, ()));
+  Some(line)
+}
+
+fn trace<F: VectorField>(
+  field: &F,
+  seed: Coord<f32>,
+  boundary: &Polygon<f32>,
+  options: &StreamlineOptions,
+  grid: Option<&SpatialGrid<()>>,
+) -> LineString<f32> {
+  // Samples of the line with their signed distance along it from the seed, so that closed orbits
+  // are only followed once
+  let mut own = SpatialGrid::new(options.separation);
+  own.insert(seed, 0.0);
+  let mut backward = integrate(
+    field,
+    seed,
+    -options.step,
+    boundary,
+    options,
+    grid,
+    &mut own,
+  );
+  let forward = integrate(field, seed, options.step, boundary, options, grid, &mut own);
+  backward.reverse();
+  backward.push(seed);
+  backward.extend(forward);
+  LineString::new(backward)
+}
+
+fn integrate<F: VectorField>(
+  field: &F,
+  seed: Coord<f32>,
+  step: f32,
+  boundary: &Polygon<f32>,
+  options: &StreamlineOptions,
+  grid: Option<&SpatialGrid<()>>,
+  own: &mut SpatialGrid<f32>,
+) -> Vec<Coord<f32>> {
+  let test_distance = options.separation * options.test_ratio;
+  let mut coords = vec![];
+  let mut current = seed;
+  let mut along = 0.0;
+  for _ in 0..options.max_steps {
+    let next = match options.integrator {
+      Integrator::Euler => direction_at(field, current).map(|k1| current + k1 * step),
+      Integrator::RungeKutta4 => runge_kutta_4(field, current, step),
+    };
+    let next = match next {
+      Some(next) => next,
+      None => break,
+    };
+    let collides = grid
+      .map(|grid| grid.query(next, test_distance).next().is_some())
+      .unwrap_or(false);
+    let offset = next - current;
+    let next_along = along + step.signum() * offset.x.hypot(offset.y);
+    let closes = own
+      .query(next, test_distance)
+      .any(|(_, other)| (next_along - *other).abs() > SELF_GAP * test_distance);
+    if !boundary.intersects(&next) || collides || closes {
+      break;
+    }
+    own.insert(next, next_along);
+    coords.push(next);
+    current = next;
+    along = next_along;
+  }
+  coords
+}
+
+fn runge_kutta_4<F: VectorField>(field: &F, coord: Coord<f32>, step: f32) -> Option<Coord<f32>> {
+  let k1 = direction_at(field, coord)?;
+  let k2 = direction_at(field, coord + k1 * (step / 2.0))?;
+  let k3 = direction_at(field, coord + k2 * (step / 2.0))?;
+  let k4 = direction_at(field, coord + k3 * step)?;
+  Some(coord + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * (step / 6.0))
+}
+
+// Unit direction of the field, None where the field vanishes
+fn direction_at<F: VectorField>(field: &F, coord: Coord<f32>) -> Option<Coord<f32>> {
+  let vector = field.vector_at(coord);
+  let norm = vector.x.hypot(vector.y);
+  (norm > f32::EPSILON).then(|| vector / norm)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use geo::Rect;
+  use rand::SeedableRng;
+
+  struct Uniform;
+
+  impl VectorField for Uniform {
+    fn vector_at(&self, _: Coord<f32>) -> Coord<f32> {
+      Coord::from((1.0, 0.0))
+    }
+  }
+
+  #[test]
+  fn propagation_keeps_the_separation() {
+    let boundary = Rect::new(Coord::zero(), Coord::from((200.0, 200.0))).to_polygon();
+    let options = StreamlineOptions::default();
+    let lines =
+      evenly_spaced_streamlines(&Uniform, &boundary, &options, &mut StdRng::seed_from_u64(0));
+    // Every streamline is seeded from the first one, one separation further each time
+    let first = lines[0].0[0].y;
+    lines.iter().for_each(|line| {
+      let steps = (line.0[0].y - first) / options.separation;
+      assert!((steps - steps.round()).abs() < 1e-3, "{}", steps);
+    });
+    assert_eq!(lines.len(), 10);
+  }
+
+  // Circles around the origin, counter-clockwise
+  struct Rotation;
+
+  impl VectorField for Rotation {
+    fn vector_at(&self, coord: Coord<f32>) -> Coord<f32> {
+      Coord::from((-coord.y, coord.x))
+    }
+  }
+
+  // Angle swept around the origin along the line
+  fn winding(line: &LineString<f32>) -> f32 {
+    line
+      .lines()
+      .map(|segment| {
+        let (start, end) = (segment.start, segment.end);
+        (start.x * end.y - start.y * end.x).atan2(start.x * end.x + start.y * end.y)
+      })
+      .sum::<f32>()
+      .abs()
+  }
+
+  #[test]
+  fn closed_orbits_are_followed_once() {
+    let boundary =
+      Rect::new(Coord::from((-100.0, -100.0)), Coord::from((100.0, 100.0))).to_polygon();
+    let options = StreamlineOptions::default();
+    let line = streamline(&Rotation, Coord::from((50.0, 0.0)), &boundary, &options);
+    assert!(winding(&line) > 0.9 * TAU);
+    assert!(winding(&line) <= TAU, "{}", winding(&line));
+    let lines = evenly_spaced_streamlines(
+      &Rotation,
+      &boundary,
+      &options,
+      &mut StdRng::seed_from_u64(0),
+    );
+    assert!(lines.len() > 1);
+    lines.iter().for_each(|line| {
+      assert!(winding(line) <= TAU, "{}", winding(line));
+    });
+  }
+}
@@ -0,0 +1,141 @@
+use geo::{BoundingRect, Coord, Intersects, Polygon};
+use rand::{rngs::StdRng, Rng};
+use std::f32::consts::TAU;
+
+use super::{pipe_thickness, BranchGraph, BranchNode};
+use crate::data_structures::{graph::Node, spatial_grid::SpatialGrid};
+
+// Number of random points tried per attractor when sampling the boundary
+const MAX_SAMPLING_ATTEMPTS: usize = 100;
+
+pub struct ColonisationOptions {
+  pub n_attractors: usize,
+  // Attractors further than this from every node do not pull the growth
+  pub influence_radius: f32,
+  // Attractors closer than this to a node are reached and removed
+  pub kill_radius: f32,
+  // Length of the segment added at each iteration to the growing nodes
+  pub segment_length: f32,
+  pub max_iterations: usize,
+  pub tip_thickness: f32,
+  pub pipe_exponent: f32,
+}
+
+impl Default for ColonisationOptions {
+  fn default() -> Self {
+    Self {
+      n_attractors: 1000,
+      influence_radius: 80.0,
+      kill_radius: 10.0,
+      segment_length: 5.0,
+      max_iterations: 1000,
+      tip_thickness: 1.0,
+      pipe_exponent: 2.0,
+    }
+  }
+}
+
+// Space colonisation, following Runions et al. "Modeling Trees with a Space Colonization
+// Algorithm": attractors scattered in the boundary pull the node closest to them, each node
+// growing towards the mean direction of its attractors until they are all reached.
+pub fn space_colonisation(
+  root: Coord<f32>,
+  boundary: &Polygon<f32>,
+  options: &ColonisationOptions,
+  rng: &mut StdRng,
+) -> BranchGraph {
+  let mut graph = BranchGraph::default();
+  graph.add_node(Node::new(BranchNode::new(root)));
+  let mut attractors = sample_attractors(boundary, options.n_attractors, rng);
+  let mut grid = SpatialGrid::new(options.influence_radius);
+  grid.insert(root, 0);
+  let mut positions = vec![root];
+
+  for _ in 0..options.max_iterations {
+    if attractors.is_empty() {
+      break;
+    }
+    // Sum of the directions to the attractors of each node
+    let mut directions: Vec<Option<Coord<f32>>> = vec![None; positions.len()];
+    attractors.iter().for_each(|attractor| {
+      let closest = grid
+        .query(*attractor, options.influence_radius)
+        .map(|(coord, index)| (*coord, *index))
+        .min_by(|(a, _), (b, _)| distance(*a, *attractor).total_cmp(&distance(*b, *attractor)));
+      if let Some((coord, index)) = closest {
+        let direction = (*attractor - coord) / distance(coord, *attractor).max(f32::EPSILON);
+        directions[index] = Some(directions[index].unwrap_or_else(Coord::zero) + direction);
+      }
+    });
+
+    // Out of reach of all the attractors, the last node grows towards the closest one
+    if directions.iter().all(|direction| direction.is_none()) {
+      let last = positions.len() - 1;
+      let closest = attractors
+        .iter()
+        .min_by(|a, b| distance(**a, positions[last]).total_cmp(&distance(**b, positions[last])))
+        .unwrap();
+      directions[last] = Some(*closest - positions[last]);
+    }
+
+    let mut n_added = 0;
+    directions
+      .into_iter()
+      .enumerate()
+      .filter_map(|(index, direction)| direction.map(|direction| (index, direction)))
+      .for_each(|(index, direction)| {
+        let norm = direction.x.hypot(direction.y);
+        // Attractors on opposite sides cancel each other out
+        let direction = match norm > f32::EPSILON {
+          true => direction / norm,
+          false => {
+            let angle = rng.gen_range(0.0..TAU);
+            (angle.cos(), angle.sin()).into()
+          }
+        };
+        let position = positions[index] + direction * options.segment_length;
+        // Nodes pulled back and forth could grow the same segment again
+        let duplicate = grid
+          .query(position, options.segment_length / 10.0)
+          .next()
+          .is_some();
+        if duplicate {
+          return;
+        }
+        let new_index = graph.add_node(Node::new(BranchNode::new(position)));
+        graph.add_edge(index, new_index);
+        grid.insert(position, new_index);
+        positions.push(position);
+        n_added += 1;
+      });
+    if n_added == 0 {
+      break;
+    }
+
+    attractors.retain(|attractor| grid.query(*attractor, options.kill_radius).next().is_none());
+  }
+  pipe_thickness(&mut graph, options.tip_thickness, options.pipe_exponent);
+  graph
+}
+
+fn sample_attractors(boundary: &Polygon<f32>, n: usize, rng: &mut StdRng) -> Vec<Coord<f32>> {
+  let rect = match boundary.bounding_rect() {
+    Some(rect) => rect,
+    None => return vec![],
+  };
+  (0..n)
+    .filter_map(|_| {
+      (0..MAX_SAMPLING_ATTEMPTS).find_map(|_| {
+        let coord = Coord::from((
+          rng.gen_range(rect.min().x..=rect.max().x),
+          rng.gen_range(rect.min().y..=rect.max().y),
+        ));
+        boundary.intersects(&coord).then_some(coord)
+      })
+    })
+    .collect()
+}
+
+fn distance(a: Coord<f32>, b: Coord<f32>) -> f32 {
+  (a - b).x.hypot((a - b).y)
+}
@@ -0,0 +1,142 @@
+use geo::Coord;
+use rand::{rngs::StdRng, Rng};
+use std::f32::consts::TAU;
+
+use super::{pipe_thickness, BranchGraph, BranchNode};
+use crate::data_structures::{graph::Node, spatial_grid::SpatialGrid};
+
+// Particles start on a circle this many particle radii away from the cluster
+const SPAWN_MARGIN: f32 = 5.0;
+// Particles wandering further than this factor of the spawn radius are started again
+const KILL_FACTOR: f32 = 3.0;
+// Aggregation stops after this many particles in a row never reach the cluster
+const MAX_LOST_PARTICLES: usize = 100;
+
+pub struct AggregationOptions {
+  // Number of particles stuck to the cluster
+  pub n_particles: usize,
+  pub particle_radius: f32,
+  // Probability for a particle touching the cluster to stick to it, lower values giving denser
+  // clusters
+  pub sticking_probability: f64,
+  // Drift added to each step of the random walks, relative to the step length
+  pub bias: Coord<f32>,
+  // Maximum number of steps of a random walk before giving up on the particle
+  pub max_steps: usize,
+  pub tip_thickness: f32,
+  pub pipe_exponent: f32,
+}
+
+impl Default for AggregationOptions {
+  fn default() -> Self {
+    Self {
+      n_particles: 2000,
+      particle_radius: 2.0,
+      sticking_probability: 1.0,
+      bias: Coord::zero(),
+      max_steps: 100_000,
+      tip_thickness: 1.0,
+      pipe_exponent: 2.0,
+    }
+  }
+}
+
+// Diffusion-limited aggregation: particles released around the cluster walk at random until they
+// touch it. Each stuck particle becomes a node whose parent is the particle it touched.
+pub fn aggregate(seed: Coord<f32>, options: &AggregationOptions, rng: &mut StdRng) -> BranchGraph {
+  let radius = options.particle_radius.max(f32::EPSILON);
+  let mut graph = BranchGraph::default();
+  graph.add_node(Node::new(BranchNode::new(seed)));
+  let mut grid = SpatialGrid::new(2.0 * radius);
+  grid.insert(seed, 0);
+  // Distance from the seed to the furthest particle
+  let mut cluster_radius: f32 = 0.0;
+  let mut n_lost = 0;
+  // Probabilities that are not finite never stick
+  let sticking_probability = match options.sticking_probability {
+    probability if probability.is_finite() => probability.clamp(0.0, 1.0),
+    _ => 0.0,
+  };
+
+  while graph.len() < options.n_particles.max(1) && n_lost < MAX_LOST_PARTICLES {
+    let spawn_radius = cluster_radius + SPAWN_MARGIN * radius;
+    let mut position = seed + random_direction(rng) * spawn_radius;
+    let mut stuck = false;
+    for _ in 0..options.max_steps {
+      let hit = grid
+        .query(position, 2.0 * radius)
+        .map(|(coord, index)| (*coord, *index))
+        .next();
+      if let Some((coord, index)) = hit {
+        if rng.gen_bool(sticking_probability) {
+          // Particles rest against the one they touched
+          let away = position - coord;
+          let distance = away.x.hypot(away.y);
+          let position = match distance > f32::EPSILON {
+            true => coord + away / distance * 2.0 * radius,
+            false => coord + random_direction(rng) * 2.0 * radius,
+          };
+          let new_index = graph.add_node(Node::new(BranchNode::new(position)));
+          graph.add_edge(index, new_index);
+          grid.insert(position, new_index);
+          let offset = position - seed;
+          cluster_radius = cluster_radius.max(offset.x.hypot(offset.y));
+          stuck = true;
+          break;
+        }
+      }
+
+      // Far from the cluster, particles take bigger steps as they cannot touch it on the way
+      let offset = position - seed;
+      let distance = offset.x.hypot(offset.y);
+      if distance > KILL_FACTOR * spawn_radius {
+        position = seed + random_direction(rng) * spawn_radius;
+        continue;
+      }
+      let step = (distance - cluster_radius - 2.0 * radius).max(radius);
+      let direction = random_direction(rng) + options.bias;
+      let norm = direction.x.hypot(direction.y);
+      if norm > f32::EPSILON {
+        position = position + direction / norm * step;
+      }
+    }
+    // A strong bias may keep particles from ever reaching the cluster
+    n_lost = if stuck { 0 } else { n_lost + 1 };
+  }
+  pipe_thickness(&mut graph, options.tip_thickness, options.pipe_exponent);
+  graph
+}
+
+fn random_direction(rng: &mut StdRng) -> Coord<f32> {
+  let angle = rng.gen_range(0.0..TAU);
+  (angle.cos(), angle.sin()).into()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand::SeedableRng;
+
+  #[test]
+  fn sticking_probability_is_clamped() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let options = AggregationOptions {
+      n_particles: 50,
+      sticking_probability: 1.5,
+      ..Default::default()
+    };
+    assert_eq!(aggregate(Coord::zero(), &options, &mut rng).len(), 50);
+    // Particles never stick
+    let options = AggregationOptions {
+      sticking_probability: -0.5,
+      max_steps: 100,
+      ..options
+    };
+    assert_eq!(aggregate(Coord::zero(), &options, &mut rng).len(), 1);
+    let options = AggregationOptions {
+      sticking_probability: f64::NAN,
+      ..options
+    };
+    assert_eq!(aggregate(Coord::zero(), &options, &mut rng).len(), 1);
+  }
+}
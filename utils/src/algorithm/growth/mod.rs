@@ -0,0 +1,124 @@
+mod colonisation;
+mod dla;
+
+use geo::{Coord, Line, LineString};
+
+use crate::{data_structures::graph::Graph, geometry::line::proportions};
+
+pub use colonisation::{space_colonisation, ColonisationOptions};
+pub use dla::{aggregate, AggregationOptions};
+
+#[derive(Clone, Copy, Debug)]
+pub struct BranchNode {
+  pub position: Coord<f32>,
+  // Thickness of the segment joining the node to its parent
+  pub thickness: f32,
+}
+
+impl BranchNode {
+  pub fn new(position: Coord<f32>) -> Self {
+    Self {
+      position,
+      thickness: 0.0,
+    }
+  }
+}
+
+// Branching structure rooted at its first node
+pub type BranchGraph = Graph<BranchNode>;
+
+// Sets the thicknesses following the pipe model: the cross sections of the children add up to
+// the one of their parent, thickness^exponent being summed (2.0 for da Vinci's rule, 3.0 for
+// Murray's law). Tips get tip_thickness.
+pub fn pipe_thickness(graph: &mut BranchGraph, tip_thickness: f32, exponent: f32) {
+  let exponent = exponent.max(f32::EPSILON);
+  let order = graph.dfs_post_order().collect::<Vec<_>>();
+  order.into_iter().for_each(|index| {
+    let children = graph.children(index);
+    let thickness = match children.is_empty() {
+      true => tip_thickness,
+      false => children
+        .iter()
+        .map(|child| {
+          let thickness = graph.get_node(*child).unwrap().content().thickness;
+          thickness.powf(exponent)
+        })
+        .sum::<f32>()
+        .powf(1.0 / exponent),
+    };
+    graph.get_node_mut(index).unwrap().content_mut().thickness = thickness;
+  });
+}
+
+// Segment from each node to its parent with its thickness
+pub fn branch_segments(graph: &BranchGraph) -> Vec<(Line<f32>, f32)> {
+  graph
+    .dfs_pre_order()
+    .filter_map(|index| {
+      let node = graph.get_node(index)?.content();
+      let parent = graph.get_node(graph.parent(index)?)?.content();
+      Some((Line::new(parent.position, node.position), node.thickness))
+    })
+    .collect()
+}
+
+// Chain of segments between two forks, with the thickness at each of its points
+#[derive(Clone)]
+pub struct BranchPath {
+  pub line: LineString<f32>,
+  pub thicknesses: Vec<f32>,
+}
+
+impl BranchPath {
+  pub fn max_thickness(&self) -> f32 {
+    self.thicknesses.iter().copied().fold(0.0, f32::max)
+  }
+
+  // Thickness at the proportion t of the length, e.g. to drive the pressure of a brush
+  pub fn thickness_at(&self, t: f32) -> f32 {
+    let proportions = proportions(&self.line);
+    let i = proportions
+      .iter()
+      .position(|proportion| *proportion >= t)
+      .unwrap_or(proportions.len().saturating_sub(1));
+    if i == 0 {
+      return self.thicknesses.first().copied().unwrap_or(0.0);
+    }
+    let (start, end) = (proportions[i - 1], proportions[i]);
+    let ratio = if end > start {
+      (t - start) / (end - start)
+    } else {
+      1.0
+    };
+    self.thicknesses[i - 1] + (self.thicknesses[i] - self.thicknesses[i - 1]) * ratio
+  }
+}
+
+// Splits the graph in paths going from a fork, or the root, to the next fork or tip, so that
+// branches can be drawn as continuous strokes. The thickness of a node applies to the segment
+// leading to it.
+pub fn branch_paths(graph: &BranchGraph) -> Vec<BranchPath> {
+  let mut paths = vec![];
+  let mut stack = match graph.contains(0) {
+    true => graph.children(0).iter().map(|child| (0, *child)).collect(),
+    false => vec![],
+  };
+  while let Some((start, mut index)) = stack.pop() {
+    let first = graph.get_node(start).unwrap().content();
+    let second = graph.get_node(index).unwrap().content();
+    let mut coords = vec![first.position, second.position];
+    let mut thicknesses = vec![second.thickness, second.thickness];
+    while let [child] = graph.children(index) {
+      let node = graph.get_node(*child).unwrap().content();
+      coords.push(node.position);
+      thicknesses.push(node.thickness);
+      index = *child;
+    }
+    stack.extend(graph.children(index).iter().map(|child| (index, *child)));
+    paths.push(BranchPath {
+      line: LineString::new(coords),
+      thicknesses,
+    });
+  }
+  paths
+}
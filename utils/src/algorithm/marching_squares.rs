@@ -0,0 +1,278 @@
+use geo::{Area, Contains, Coord, LineString, MultiLineString, MultiPolygon, Polygon, Rect};
+use std::collections::HashMap;
+
+use crate::data_structures::scalar_grid::ScalarGrid;
+
+// Edge between two neighbouring samples: the sample on its left or top, and whether it goes
+// down to the next row rather than right to the next column
+type EdgeKey = (usize, usize, bool);
+
+// Lines along which the field equals level, closed when they loop inside the grid
+pub fn isolines(grid: &ScalarGrid, level: f32) -> MultiLineString<f32> {
+  let segments = cell_segments(grid, level);
+  MultiLineString::new(
+    stitch(&segments)
+      .into_iter()
+      .map(|edges| {
+        LineString::new(
+          edges
+            .iter()
+            .map(|edge| crossing(grid, *edge, level))
+            .collect(),
+        )
+      })
+      .collect(),
+  )
+}
+
+// Isolines at each of the levels, e.g. for a topographic map
+pub fn contours(grid: &ScalarGrid, levels: &[f32]) -> Vec<MultiLineString<f32>> {
+  levels.iter().map(|level| isolines(grid, *level)).collect()
+}
+
+// Levels splitting the range of the field in n_levels + 1 equal intervals
+pub fn equidistant_levels(grid: &ScalarGrid, n_levels: usize) -> Vec<f32> {
+  let (min, max) = grid.min_max();
+  (1..=n_levels)
+    .map(|i| min + (max - min) * i as f32 / (n_levels + 1) as f32)
+    .collect()
+}
+
+// Area where low <= value < high, its polygons having holes where the field leaves the band.
+// The border of the band follows the outer samples of the grid where the field is cut.
+pub fn band(grid: &ScalarGrid, low: f32, high: f32) -> MultiPolygon<f32> {
+  if grid.width() == 0 || grid.height() == 0 || low >= high {
+    return MultiPolygon::new(vec![]);
+  }
+  let (min, _) = grid.min_max();
+  let low = low.max(min - 1.0);
+  // A frame of samples below every level closes all the lines
+  let padded = pad(grid, low - 1.0);
+  let min_area =
+    1e-6 * grid.rect().width() * grid.rect().height() / (grid.width() * grid.height()) as f32;
+  // Rings of both levels, telling whether they are at the high level and the field is above it
+  // on their inner side. The crossings with the frame are only moved onto the grid once the
+  // rings are sorted out, to keep rings at different levels apart.
+  let mut rings = [(low, false), (high, true)]
+    .into_iter()
+    .flat_map(|(level, is_high)| {
+      stitch(&cell_segments(&padded, level))
+        .into_iter()
+        .filter(|edges| edges.len() > 3)
+        .map(|edges| {
+          let ring = |crossing: fn(&ScalarGrid, EdgeKey, f32) -> Coord<f32>| {
+            LineString::new(
+              edges
+                .iter()
+                .map(|edge| crossing(&padded, *edge, level))
+                .collect(),
+            )
+          };
+          let polygon = Polygon::new(ring(crossing), vec![]);
+          let above_inside = is_above_inside(&padded, &polygon, edges[0], level);
+          (polygon, ring(framed_crossing), is_high, above_inside)
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect::<Vec<_>>();
+
+  // Lines at different levels never cross, so the rings nest into a tree
+  rings.sort_by(|(a, ..), (b, ..)| a.unsigned_area().total_cmp(&b.unsigned_area()));
+  let parents = (0..rings.len())
+    .map(|i| {
+      let inner = rings[i].0.exterior().0[0];
+      (i + 1..rings.len()).find(|j| rings[*j].0.contains(&inner))
+    })
+    .collect::<Vec<_>>();
+  // The area just inside a ring is above a level when the closest ring of that level around it
+  // is above on its inner side
+  let above = |mut ring: Option<usize>, is_high: bool| {
+    while let Some(i) = ring {
+      if rings[i].2 == is_high {
+        return rings[i].3;
+      }
+      ring = parents[i];
+    }
+    false
+  };
+  let polygons = (0..rings.len())
+    .filter(|i| above(Some(*i), false) && !above(Some(*i), true))
+    .map(|i| {
+      let holes = (0..rings.len())
+        .filter(|j| parents[*j] == Some(i))
+        .map(|j| rings[j].1.clone())
+        .collect();
+      Polygon::new(rings[i].1.clone(), holes)
+    })
+    // Rings moved onto the same samples of the border leave empty polygons
+    .filter(|polygon| polygon.unsigned_area() > min_area)
+    .collect();
+  MultiPolygon::new(polygons)
+}
+
+// Bands between consecutive levels, the first one below the first level and the last one above
+// the last level
+pub fn bands(grid: &ScalarGrid, levels: &[f32]) -> Vec<MultiPolygon<f32>> {
+  std::iter::once(f32::NEG_INFINITY)
+    .chain(levels.iter().copied())
+    .chain(std::iter::once(f32::INFINITY))
+    .collect::<Vec<_>>()
+    .windows(2)
+    .map(|pair| band(grid, pair[0], pair[1]))
+    .collect()
+}
+
+// Grid with an extra frame of samples with the given value around it
+fn pad(grid: &ScalarGrid, value: f32) -> ScalarGrid {
+  let (width, height) = (grid.width(), grid.height());
+  let rect = grid.rect();
+  let cell = Coord::from((rect.width() / width as f32, rect.height() / height as f32));
+  let mut padded = ScalarGrid::new(
+    vec![value; (width + 2) * (height + 2)],
+    width + 2,
+    height + 2,
+    Rect::new(rect.min() - cell, rect.max() + cell),
+  );
+  (0..height).for_each(|y| {
+    (0..width).for_each(|x| padded.set_value(x + 1, y + 1, grid.value(x, y)));
+  });
+  padded
+}
+
+// Crossings with the frame of a padded grid are moved onto the outer samples of the grid
+fn framed_crossing(padded: &ScalarGrid, edge: EdgeKey, level: f32) -> Coord<f32> {
+  let (x, y, down) = edge;
+  let (x1, y1) = if down { (x, y + 1) } else { (x + 1, y) };
+  let on_frame =
+    |x: usize, y: usize| x == 0 || y == 0 || x == padded.width() - 1 || y == padded.height() - 1;
+  match (on_frame(x, y), on_frame(x1, y1)) {
+    (true, false) => padded.coord(x1 as f32, y1 as f32),
+    (false, true) => padded.coord(x as f32, y as f32),
+    _ => crossing(padded, edge, level),
+  }
+}
+
+// Tests on which side of the ring lies the sample above the level of one of its edges
+fn is_above_inside(padded: &ScalarGrid, ring: &Polygon<f32>, edge: EdgeKey, level: f32) -> bool {
+  let (x, y, down) = edge;
+  let (x1, y1) = if down { (x, y + 1) } else { (x + 1, y) };
+  let (above, below) = match padded.value(x, y) >= level {
+    true => ((x, y), (x1, y1)),
+    false => ((x1, y1), (x, y)),
+  };
+  // Halfway between the crossing and the furthest sample stays clear of the ring
+  let crossing = crossing(padded, edge, level);
+  let sample = |(x, y): (usize, usize)| padded.coord(x as f32, y as f32);
+  let distance = |coord: Coord<f32>| (coord - crossing).x.hypot((coord - crossing).y);
+  let (probe, probe_is_above) = match distance(sample(above)) >= distance(sample(below)) {
+    true => ((sample(above) + crossing) / 2.0, true),
+    false => ((sample(below) + crossing) / 2.0, false),
+  };
+  ring.contains(&probe) == probe_is_above
+}
+
+// Pairs of edges crossed by the isoline in each cell of four samples
+fn cell_segments(grid: &ScalarGrid, level: f32) -> Vec<(EdgeKey, EdgeKey)> {
+  let (width, height) = (grid.width(), grid.height());
+  let mut segments = vec![];
+  (0..height.saturating_sub(1)).for_each(|y| {
+    (0..width.saturating_sub(1)).for_each(|x| {
+      let above = |x: usize, y: usize| grid.value(x, y) >= level;
+      let connected = |x: usize, y: usize| {
+        let center = (grid.value(x, y)
+          + grid.value(x + 1, y)
+          + grid.value(x + 1, y + 1)
+          + grid.value(x, y + 1))
+          / 4.0;
+        center >= level
+      };
+      let case = (above(x, y) as u8) << 3
+        | (above(x + 1, y) as u8) << 2
+        | (above(x + 1, y + 1) as u8) << 1
+        | above(x, y + 1) as u8;
+      let top = (x, y, false);
+      let right = (x + 1, y, true);
+      let bottom = (x, y + 1, false);
+      let left = (x, y, true);
+      // Corners are bits top left, top right, bottom right and bottom left
+      let cell: &[(EdgeKey, EdgeKey)] = match case {
+        1 | 14 => &[(left, bottom)],
+        2 | 13 => &[(bottom, right)],
+        3 | 12 => &[(left, right)],
+        4 | 11 => &[(top, right)],
+        6 | 9 => &[(top, bottom)],
+        7 | 8 => &[(left, top)],
+        // Saddles join the samples above the level when the center of the cell is above too
+        5 | 10 if connected(x, y) => match case {
+          5 => &[(left, top), (bottom, right)],
+          _ => &[(left, bottom), (top, right)],
+        },
+        5 => &[(left, bottom), (top, right)],
+        10 => &[(left, top), (bottom, right)],
+        _ => &[],
+      };
+      segments.extend_from_slice(cell);
+    })
+  });
+  segments
+}
+
+// Joins the segments sharing an edge into chains of edges, open chains starting and ending at
+// the border of the grid and closed ones repeating their first edge
+fn stitch(segments: &[(EdgeKey, EdgeKey)]) -> Vec<Vec<EdgeKey>> {
+  let mut segments_at: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+  segments
+    .iter()
+    .enumerate()
+    .for_each(|(i, (first, second))| {
+      segments_at.entry(*first).or_default().push(i);
+      segments_at.entry(*second).or_default().push(i);
+    });
+  let mut used = vec![false; segments.len()];
+  let mut chains = vec![];
+
+  let follow = |start: EdgeKey, used: &mut Vec<bool>| {
+    let mut chain = vec![start];
+    let mut current = start;
+    while let Some(i) = segments_at[&current].iter().copied().find(|i| !used[*i]) {
+      used[i] = true;
+      let (first, second) = segments[i];
+      current = if first == current { second } else { first };
+      chain.push(current);
+    }
+    chain
+  };
+
+  // Open chains first, from the edges used by a single segment
+  let mut ends = segments_at
+    .iter()
+    .filter(|(_, at)| at.len() == 1)
+    .map(|(edge, _)| *edge)
+    .collect::<Vec<_>>();
+  ends.sort();
+  ends.into_iter().for_each(|edge| {
+    if !used[segments_at[&edge][0]] {
+      chains.push(follow(edge, &mut used));
+    }
+  });
+  (0..segments.len()).for_each(|i| {
+    if !used[i] {
+      chains.push(follow(segments[i].0, &mut used));
+    }
+  });
+  chains
+}
+
+// Point of the edge where the field equals level, interpolating linearly between its samples
+fn crossing(grid: &ScalarGrid, (x, y, down): EdgeKey, level: f32) -> Coord<f32> {
+  let (x1, y1) = if down { (x, y + 1) } else { (x + 1, y) };
+  let (start, end) = (grid.value(x, y), grid.value(x1, y1));
+  let t = match (end - start).abs() > f32::EPSILON {
+    true => ((level - start) / (end - start)).clamp(0.0, 1.0),
+    false => 0.5,
+  };
+  grid.coord(
+    x as f32 + (x1 - x) as f32 * t,
+    y as f32 + (y1 - y) as f32 * t,
+  )
+}
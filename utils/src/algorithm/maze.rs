@@ -0,0 +1,666 @@
+use geo::{Coord, Line, LineString, MultiLineString, Rect};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::space::{compute_adjacency, Space, SpaceTile};
+use crate::geometry::{
+  curve::{sample_adaptive, QuadraticBezier},
+  grid::Grid,
+};
+
+// Maximum distance between the rounded corners and their sampling
+const CURVE_TOLERANCE: f32 = 0.5;
+// Probability for Eller's algorithm to join two cells of a row, or to add a passage to the next row
+const ELLER_JOIN_PROBABILITY: f64 = 0.5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MazeAlgorithm {
+  // Random depth first walk, giving long winding corridors
+  RecursiveBacktracker,
+  // Random passages between cells not connected yet, giving many short dead ends
+  Kruskal,
+  // Grows the maze from a cell by random passages at its border
+  Prim,
+  // Loop-erased random walks, drawing every maze with the same probability
+  Wilson,
+  // Row by row, keeping only the current row in memory
+  Eller,
+}
+
+// Two neighbouring cells, the walls between them and the point to cross them
+pub struct MazeLink {
+  pub first: usize,
+  pub second: usize,
+  pub walls: Vec<Line<f32>>,
+  pub door: Coord<f32>,
+}
+
+// Cells of a maze and how they touch each other
+pub struct MazeLayout {
+  centers: Vec<Coord<f32>>,
+  // Row of each cell, used by Eller's algorithm
+  rows: Vec<usize>,
+  links: Vec<MazeLink>,
+  // Walls on the outside of the maze
+  border: Vec<Line<f32>>,
+}
+
+impl MazeLayout {
+  // Cells in the order of grid.cells()
+  pub fn from_grid(grid: &Grid) -> Self {
+    let cells = grid.cells();
+    let positions = cells
+      .iter()
+      .enumerate()
+      .map(|(position, cell)| (*cell, position))
+      .collect::<HashMap<_, _>>();
+    let min_row = cells.iter().map(|cell| cell.r).min().unwrap_or(0);
+    let mut links = vec![];
+    let mut border = vec![];
+    cells.iter().enumerate().for_each(|(position, cell)| {
+      let vertices = grid.vertices(*cell);
+      (0..vertices.len()).for_each(|edge| {
+        let wall = Line::new(vertices[edge], vertices[(edge + 1) % vertices.len()]);
+        match grid
+          .neighbour(*cell, edge)
+          .map(|neighbour| positions[&neighbour])
+        {
+          Some(neighbour) if neighbour > position => links.push(MazeLink {
+            first: position,
+            second: neighbour,
+            walls: vec![wall],
+            door: (wall.start + wall.end) / 2.0,
+          }),
+          Some(_) => {}
+          None => border.push(wall),
+        }
+      });
+    });
+    Self {
+      centers: cells.iter().map(|cell| grid.center(*cell)).collect(),
+      rows: cells
+        .iter()
+        .map(|cell| (cell.r - min_row) as usize)
+        .collect(),
+      links,
+      border,
+    }
+  }
+
+  // Cells in the order of space.leafs()
+  pub fn from_space<T: SpaceTile>(space: &Space<T>) -> Self {
+    let leaves = space.leafs();
+    let rects = leaves
+      .iter()
+      .map(|index| {
+        let tile = space.get_node(*index).unwrap().content();
+        Rect::new(tile.min(), tile.max())
+      })
+      .collect::<Vec<_>>();
+    let positions = leaves
+      .iter()
+      .enumerate()
+      .map(|(position, index)| (*index, position))
+      .collect::<HashMap<_, _>>();
+
+    let adjacency = compute_adjacency(space);
+    let mut walls: HashMap<(usize, usize), Vec<Line<f32>>> = HashMap::new();
+    let mut pairs = vec![];
+    adjacency.edges().iter().for_each(|edge| {
+      let pair = (positions[&edge.first], positions[&edge.second]);
+      let pair = (pair.0.min(pair.1), pair.0.max(pair.1));
+      if !walls.contains_key(&pair) {
+        pairs.push(pair);
+      }
+      walls.entry(pair).or_default().push(edge.line);
+    });
+    let links = pairs
+      .into_iter()
+      .map(|(first, second)| {
+        let walls = walls.remove(&(first, second)).unwrap_or_default();
+        // The door is in the middle of the longest shared wall
+        let door = walls
+          .iter()
+          .max_by(|a, b| length(a).total_cmp(&length(b)))
+          .map(|wall| (wall.start + wall.end) / 2.0)
+          .unwrap_or_else(|| (rects[first].center() + rects[second].center()) / 2.0);
+        MazeLink {
+          first,
+          second,
+          walls,
+          door,
+        }
+      })
+      .collect();
+
+    let mut rows = rects.iter().map(|rect| rect.min().y).collect::<Vec<_>>();
+    rows.sort_by(|a, b| a.total_cmp(b));
+    rows.dedup();
+    let border = rects
+      .iter()
+      .copied()
+      .reduce(|a, b| {
+        Rect::new(
+          (a.min().x.min(b.min().x), a.min().y.min(b.min().y)),
+          (a.max().x.max(b.max().x), a.max().y.max(b.max().y)),
+        )
+      })
+      .map(|rect| rect.to_lines().to_vec())
+      .unwrap_or_default();
+    Self {
+      centers: rects.iter().map(|rect| rect.center()).collect(),
+      rows: rects
+        .iter()
+        .map(|rect| rows.partition_point(|y| *y < rect.min().y))
+        .collect(),
+      links,
+      border,
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.centers.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.centers.is_empty()
+  }
+
+  pub fn center(&self, cell: usize) -> Coord<f32> {
+    self.centers[cell]
+  }
+
+  pub fn links(&self) -> &[MazeLink] {
+    &self.links
+  }
+
+  // Links of each cell, with the cell on the other side
+  fn cell_links(&self) -> Vec<Vec<(usize, usize)>> {
+    let mut cell_links = vec![vec![]; self.len()];
+    self.links.iter().enumerate().for_each(|(i, link)| {
+      cell_links[link.first].push((i, link.second));
+      cell_links[link.second].push((i, link.first));
+    });
+    cell_links
+  }
+}
+
+// Perfect maze: exactly one path joins any two connected cells
+pub struct Maze {
+  layout: MazeLayout,
+  open: Vec<bool>,
+}
+
+impl Maze {
+  pub fn layout(&self) -> &MazeLayout {
+    &self.layout
+  }
+
+  pub fn is_open(&self, link: usize) -> bool {
+    self.open[link]
+  }
+
+  // Walls between cells not joined by a passage, and the outer walls
+  pub fn walls(&self) -> Vec<Line<f32>> {
+    self
+      .layout
+      .links
+      .iter()
+      .zip(self.open.iter())
+      .filter(|(_, open)| !**open)
+      .flat_map(|(link, _)| link.walls.iter().copied())
+      .chain(self.layout.border.iter().copied())
+      .collect()
+  }
+
+  // Cells from start to end, empty when they are not connected
+  pub fn solve(&self, start: usize, end: usize) -> Vec<usize> {
+    let (previous, _) = self.walk(start);
+    if end >= self.layout.len() || previous[end].is_none() {
+      return vec![];
+    }
+    let mut cells = vec![end];
+    let mut cell = end;
+    while let Some(before) = previous[cell].filter(|before| *before != cell) {
+      cells.push(before);
+      cell = before;
+    }
+    cells.reverse();
+    cells
+  }
+
+  // Cell the furthest away from start along the passages, e.g. to place the exit
+  pub fn furthest(&self, start: usize) -> usize {
+    self.walk(start).1.last().copied().unwrap_or(start)
+  }
+
+  // Path from the center of start to the center of end through the doors, its corners being
+  // rounded over a proportion of the segments given by rounding (0.0 to 0.5)
+  pub fn solution(&self, start: usize, end: usize, rounding: f32) -> LineString<f32> {
+    let cells = self.solve(start, end);
+    let link_between = self.link_between();
+    let points = cells
+      .windows(2)
+      .flat_map(|pair| {
+        let link = link_between[&(pair[0].min(pair[1]), pair[0].max(pair[1]))];
+        [self.layout.centers[pair[0]], self.layout.links[link].door]
+      })
+      .chain(cells.last().map(|cell| self.layout.centers[*cell]))
+      .collect();
+    round_corners(points, rounding)
+  }
+
+  // Centre lines of the passages, joined into as few lines as possible, e.g. to plot the maze with
+  // a wide pen. Corners are rounded like the solution.
+  pub fn corridors(&self, rounding: f32) -> MultiLineString<f32> {
+    let cell_links = self.layout.cell_links();
+    let mut visited = vec![false; self.layout.len()];
+    let mut lines = vec![];
+    (0..self.layout.len()).for_each(|root| {
+      if visited[root] {
+        return;
+      }
+      visited[root] = true;
+      // Lines being drawn, each with the cell where it currently ends
+      let mut stack = vec![(vec![self.layout.centers[root]], root)];
+      while let Some((mut points, cell)) = stack.pop() {
+        let next = cell_links[cell]
+          .iter()
+          .filter(|(link, other)| self.open[*link] && !visited[*other])
+          .copied()
+          .collect::<Vec<_>>();
+        if next.is_empty() {
+          if points.len() > 1 {
+            lines.push(round_corners(points, rounding));
+          }
+          continue;
+        }
+        next.iter().for_each(|(_, other)| visited[*other] = true);
+        // The first branch continues the line, the other ones start from the cell
+        next
+          .iter()
+          .enumerate()
+          .rev()
+          .for_each(|(i, (link, other))| {
+            let mut branch = match i {
+              0 => std::mem::take(&mut points),
+              _ => vec![self.layout.centers[cell]],
+            };
+            branch.push(self.layout.links[*link].door);
+            branch.push(self.layout.centers[*other]);
+            stack.push((branch, *other));
+          });
+      }
+    });
+    MultiLineString::new(lines)
+  }
+
+  fn link_between(&self) -> HashMap<(usize, usize), usize> {
+    self
+      .layout
+      .links
+      .iter()
+      .enumerate()
+      .filter(|(i, _)| self.open[*i])
+      .map(|(i, link)| ((link.first, link.second), i))
+      .collect()
+  }
+
+  // Previous cell on the path from start of each cell, start being its own previous cell, and
+  // the cells reachable from start in breadth first order
+  fn walk(&self, start: usize) -> (Vec<Option<usize>>, Vec<usize>) {
+    let mut previous = vec![None; self.layout.len()];
+    let mut order = vec![];
+    if start >= self.layout.len() {
+      return (previous, order);
+    }
+    let cell_links = self.layout.cell_links();
+    previous[start] = Some(start);
+    let mut queue = VecDeque::from([start]);
+    while let Some(cell) = queue.pop_front() {
+      order.push(cell);
+      cell_links[cell]
+        .iter()
+        .filter(|(link, _)| self.open[*link])
+        .for_each(|(_, other)| {
+          if previous[*other].is_none() {
+            previous[*other] = Some(cell);
+            queue.push_back(*other);
+          }
+        });
+    }
+    (previous, order)
+  }
+}
+
+pub fn compute_maze(layout: MazeLayout, algorithm: MazeAlgorithm, rng: &mut StdRng) -> Maze {
+  let passages = match algorithm {
+    MazeAlgorithm::RecursiveBacktracker => recursive_backtracker(&layout, rng),
+    MazeAlgorithm::Kruskal => kruskal(&layout, rng),
+    MazeAlgorithm::Prim => prim(&layout, rng),
+    MazeAlgorithm::Wilson => wilson(&layout, rng),
+    MazeAlgorithm::Eller => eller(&layout, rng),
+  };
+  let mut open = vec![false; layout.links.len()];
+  passages.into_iter().for_each(|link| open[link] = true);
+  Maze { layout, open }
+}
+
+fn recursive_backtracker(layout: &MazeLayout, rng: &mut StdRng) -> Vec<usize> {
+  let cell_links = layout.cell_links();
+  let mut visited = vec![false; layout.len()];
+  let mut passages = vec![];
+  (0..layout.len()).for_each(|start| {
+    if visited[start] {
+      return;
+    }
+    visited[start] = true;
+    let mut stack = vec![start];
+    while let Some(cell) = stack.last().copied() {
+      let unvisited = cell_links[cell]
+        .iter()
+        .filter(|(_, other)| !visited[*other])
+        .collect::<Vec<_>>();
+      match unvisited.choose(rng) {
+        Some((link, other)) => {
+          visited[*other] = true;
+          passages.push(*link);
+          stack.push(*other);
+        }
+        None => {
+          stack.pop();
+        }
+      }
+    }
+  });
+  passages
+}
+
+fn kruskal(layout: &MazeLayout, rng: &mut StdRng) -> Vec<usize> {
+  let mut links = (0..layout.links.len()).collect::<Vec<_>>();
+  links.shuffle(rng);
+  let mut sets = DisjointSets::new(layout.len());
+  links
+    .into_iter()
+    .filter(|link| sets.union(layout.links[*link].first, layout.links[*link].second))
+    .collect()
+}
+
+fn prim(layout: &MazeLayout, rng: &mut StdRng) -> Vec<usize> {
+  let cell_links = layout.cell_links();
+  let mut in_maze = vec![false; layout.len()];
+  let mut passages = vec![];
+  (0..layout.len()).for_each(|start| {
+    if in_maze[start] {
+      return;
+    }
+    in_maze[start] = true;
+    let mut frontier = cell_links[start].clone();
+    while !frontier.is_empty() {
+      let (link, other) = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+      if in_maze[other] {
+        continue;
+      }
+      in_maze[other] = true;
+      passages.push(link);
+      frontier.extend(cell_links[other].iter().filter(|(_, next)| !in_maze[*next]));
+    }
+  });
+  passages
+}
+
+fn wilson(layout: &MazeLayout, rng: &mut StdRng) -> Vec<usize> {
+  let cell_links = layout.cell_links();
+  let mut in_maze = vec![false; layout.len()];
+  let mut passages = vec![];
+  // Link taken when last leaving each cell during the current walk
+  let mut exits: Vec<Option<(usize, usize)>> = vec![None; layout.len()];
+  let mut cells = (0..layout.len()).collect::<Vec<_>>();
+  cells.shuffle(rng);
+  cells.iter().for_each(|start| {
+    if in_maze[*start] {
+      return;
+    }
+    // Each group of connected cells gets its own root
+    if !reaches_maze(&cell_links, &in_maze, *start) {
+      in_maze[*start] = true;
+      return;
+    }
+    let mut cell = *start;
+    while !in_maze[cell] {
+      let (link, other) = *cell_links[cell].choose(rng).unwrap();
+      exits[cell] = Some((link, other));
+      cell = other;
+    }
+    // Following the last exits erases the loops of the walk
+    let mut cell = *start;
+    while !in_maze[cell] {
+      in_maze[cell] = true;
+      let (link, other) = exits[cell].unwrap();
+      passages.push(link);
+      cell = other;
+    }
+  });
+  passages
+}
+
+fn reaches_maze(cell_links: &[Vec<(usize, usize)>], in_maze: &[bool], start: usize) -> bool {
+  let mut seen = vec![false; in_maze.len()];
+  seen[start] = true;
+  let mut queue = VecDeque::from([start]);
+  while let Some(cell) = queue.pop_front() {
+    if in_maze[cell] {
+      return true;
+    }
+    cell_links[cell].iter().for_each(|(_, other)| {
+      if !seen[*other] {
+        seen[*other] = true;
+        queue.push_back(*other);
+      }
+    });
+  }
+  false
+}
+
+fn eller(layout: &MazeLayout, rng: &mut StdRng) -> Vec<usize> {
+  let n_rows = layout.rows.iter().max().map(|row| row + 1).unwrap_or(0);
+  let mut sets = DisjointSets::new(layout.len());
+  let mut passages = vec![];
+  let row_of = |link: &MazeLink| {
+    let (first, second) = (layout.rows[link.first], layout.rows[link.second]);
+    (first.min(second), first.max(second))
+  };
+  (0..n_rows).for_each(|row| {
+    let last = row + 1 == n_rows;
+    // Joins cells of the row, all of them on the last row
+    let mut in_row = (0..layout.links.len())
+      .filter(|link| row_of(&layout.links[*link]) == (row, row))
+      .collect::<Vec<_>>();
+    in_row.shuffle(rng);
+    in_row.into_iter().for_each(|link| {
+      let MazeLink { first, second, .. } = layout.links[link];
+      if (last || rng.gen_bool(ELLER_JOIN_PROBABILITY)) && sets.union(first, second) {
+        passages.push(link);
+      }
+    });
+    // Each set of the row goes on to the following rows at least once
+    passages.extend(continue_down(
+      layout,
+      &mut sets,
+      row,
+      ELLER_JOIN_PROBABILITY,
+      rng,
+    ));
+  });
+
+  // Cells rows do not connect, e.g. in irregular layouts, are joined at random
+  let mut links = (0..layout.links.len()).collect::<Vec<_>>();
+  links.shuffle(rng);
+  links.into_iter().for_each(|link| {
+    if sets.union(layout.links[link].first, layout.links[link].second) {
+      passages.push(link);
+    }
+  });
+  passages
+}
+
+// Passages from the cells of the row to the following rows, at least one for each set of the row
+// and the others with the given probability
+fn continue_down(
+  layout: &MazeLayout,
+  sets: &mut DisjointSets,
+  row: usize,
+  probability: f64,
+  rng: &mut StdRng,
+) -> Vec<usize> {
+  let mut down = (0..layout.links.len())
+    .filter(|link| {
+      let MazeLink { first, second, .. } = layout.links[*link];
+      let (first, second) = (layout.rows[first], layout.rows[second]);
+      first.min(second) == row && first.max(second) > row
+    })
+    .collect::<Vec<_>>();
+  down.shuffle(rng);
+  // Sets are told apart by their roots before any passage moves them to the next row
+  let roots = down
+    .iter()
+    .map(|link| {
+      let MazeLink { first, second, .. } = layout.links[*link];
+      match layout.rows[first] == row {
+        true => sets.find(first),
+        false => sets.find(second),
+      }
+    })
+    .collect::<Vec<_>>();
+  let mut continued = HashSet::new();
+  let mut passages = vec![];
+  down.into_iter().zip(roots).for_each(|(link, root)| {
+    let MazeLink { first, second, .. } = layout.links[link];
+    let first_down = !continued.contains(&root);
+    if (first_down || rng.gen_bool(probability)) && sets.union(first, second) {
+      continued.insert(root);
+      passages.push(link);
+    }
+  });
+  passages
+}
+
+struct DisjointSets {
+  parents: Vec<usize>,
+}
+
+impl DisjointSets {
+  fn new(n: usize) -> Self {
+    Self {
+      parents: (0..n).collect(),
+    }
+  }
+
+  fn find(&mut self, item: usize) -> usize {
+    let mut root = item;
+    while self.parents[root] != root {
+      root = self.parents[root];
+    }
+    let mut current = item;
+    while self.parents[current] != root {
+      let next = self.parents[current];
+      self.parents[current] = root;
+      current = next;
+    }
+    root
+  }
+
+  // Returns false when both items were already in the same set
+  fn union(&mut self, a: usize, b: usize) -> bool {
+    let (a, b) = (self.find(a), self.find(b));
+    if a == b {
+      return false;
+    }
+    self.parents[a] = b;
+    true
+  }
+}
+
+// Replaces each corner by a quadratic curve starting and ending at the given proportion of the
+// segments around it
+fn round_corners(points: Vec<Coord<f32>>, rounding: f32) -> LineString<f32> {
+  let rounding = rounding.clamp(0.0, 0.5);
+  if points.len() < 3 || rounding <= 0.0 {
+    return LineString::new(points);
+  }
+  let mut coords = vec![points[0]];
+  points.windows(3).for_each(|window| {
+    let (before, corner, after) = (window[0], window[1], window[2]);
+    let start = corner + (before - corner) * rounding;
+    let end = corner + (after - corner) * rounding;
+    let curve = sample_adaptive(&QuadraticBezier::new(start, corner, end), CURVE_TOLERANCE);
+    coords.extend(curve.0);
+  });
+  coords.push(points[points.len() - 1]);
+  LineString::new(coords)
+}
+
+fn length(line: &Line<f32>) -> f32 {
+  line.dx().hypot(line.dy())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::geometry::grid::GridKind;
+  use rand::SeedableRng;
+
+  fn square_layout(n: usize) -> MazeLayout {
+    let side = 10.0 * n as f32;
+    let rect = Rect::new(Coord::zero(), Coord::from((side, side)));
+    MazeLayout::from_grid(&Grid::new(GridKind::Square, rect, 10.0))
+  }
+
+  #[test]
+  fn row_with_one_set_continues_once() {
+    let layout = square_layout(8);
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut sets = DisjointSets::new(layout.len());
+    layout
+      .links
+      .iter()
+      .filter(|link| layout.rows[link.first] == 0 && layout.rows[link.second] == 0)
+      .for_each(|link| {
+        sets.union(link.first, link.second);
+      });
+    assert_eq!(continue_down(&layout, &mut sets, 0, 0.0, &mut rng).len(), 1);
+  }
+
+  #[test]
+  fn row_of_single_cells_continues_from_each() {
+    let layout = square_layout(8);
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut sets = DisjointSets::new(layout.len());
+    assert_eq!(continue_down(&layout, &mut sets, 0, 0.0, &mut rng).len(), 8);
+  }
+
+  #[test]
+  fn eller_gives_a_spanning_tree() {
+    let layout = square_layout(8);
+    let n_cells = layout.len();
+    let n_vertical = layout
+      .links
+      .iter()
+      .filter(|link| layout.rows[link.first] != layout.rows[link.second])
+      .count();
+    let maze = compute_maze(layout, MazeAlgorithm::Eller, &mut StdRng::seed_from_u64(0));
+    let open = (0..maze.layout().links.len())
+      .filter(|link| maze.is_open(*link))
+      .collect::<Vec<_>>();
+    assert_eq!(open.len(), n_cells - 1);
+    let open_vertical = open
+      .iter()
+      .filter(|link| {
+        let link = &maze.layout().links[**link];
+        maze.layout().rows[link.first] != maze.layout().rows[link.second]
+      })
+      .count();
+    assert!(open_vertical < n_vertical);
+    assert_eq!(maze.solve(0, n_cells - 1).last(), Some(&(n_cells - 1)));
+  }
+}
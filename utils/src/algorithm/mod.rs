@@ -1,2 +1,12 @@
+pub mod colouring;
+pub mod differential_growth;
+pub mod flow_field;
+pub mod growth;
+pub mod marching_squares;
+pub mod maze;
+pub mod packing;
+pub mod reaction_diffusion;
 pub mod space;
 pub mod squiggle;
+pub mod truchet;
+pub mod wfc;
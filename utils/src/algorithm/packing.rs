@@ -0,0 +1,340 @@
+use crate::data_structures::spatial_grid::SpatialGrid;
+use geo::{
+  BoundingRect, Centroid, Coord, EuclideanDistance, Intersects, LineString, Point, Polygon, Rotate,
+  Scale, Translate,
+};
+use rand::{rngs::StdRng, Rng};
+use rand_distr::{Distribution, Normal};
+use std::f32::consts::TAU;
+
+#[derive(Clone, Copy)]
+pub struct Circle {
+  pub center: Coord<f32>,
+  pub radius: f32,
+}
+
+impl Circle {
+  pub fn new(center: Coord<f32>, radius: f32) -> Self {
+    Self { center, radius }
+  }
+
+  pub fn to_polygon(&self, n_segments: usize) -> Polygon<f32> {
+    let n_segments = n_segments.max(3);
+    let coords = (0..n_segments)
+      .map(|i| {
+        let theta = i as f32 / n_segments as f32 * TAU;
+        self.center + Coord::from((theta.cos(), theta.sin())) * self.radius
+      })
+      .collect::<Vec<_>>();
+    Polygon::new(LineString::new(coords), vec![])
+  }
+
+  fn intersects(&self, other: &Circle) -> bool {
+    let radii = self.radius + other.radius - 1e-3;
+    radii > 0.0 && radii * radii > squared_distance(self.center, other.center)
+  }
+}
+
+#[derive(Clone, Copy)]
+pub enum RadiusDistribution {
+  Uniform,
+  // Density proportional to radius^-exponent: many small circles and a few big ones
+  PowerLaw(f32),
+  // Normal distribution clamped between the minimum and maximum radius
+  Normal { mean: f32, std_dev: f32 },
+}
+
+impl RadiusDistribution {
+  fn sample(&self, min: f32, max: f32, rng: &mut StdRng) -> f32 {
+    if min >= max {
+      return min;
+    }
+    match *self {
+      RadiusDistribution::Uniform => rng.gen_range(min..=max),
+      RadiusDistribution::PowerLaw(exponent) => {
+        // Inverse transform sampling
+        let u: f32 = rng.gen();
+        match (exponent - 1.0).abs() < f32::EPSILON {
+          true => min * (max / min).powf(u),
+          false => {
+            let k = 1.0 - exponent;
+            (min.powf(k) + u * (max.powf(k) - min.powf(k))).powf(1.0 / k)
+          }
+        }
+      }
+      RadiusDistribution::Normal { mean, std_dev } => Normal::new(mean, std_dev.abs())
+        .map(|normal| normal.sample(rng))
+        .unwrap_or(mean)
+        .clamp(min, max),
+    }
+  }
+}
+
+pub struct PackingOptions {
+  pub min_radius: f32,
+  pub max_radius: f32,
+  pub distribution: RadiusDistribution,
+  // Minimum gap between two shapes, and between a shape and the container
+  pub padding: f32,
+  // Number of consecutive failed placements after which the packing stops
+  pub max_attempts: usize,
+  pub max_shapes: usize,
+}
+
+impl Default for PackingOptions {
+  fn default() -> Self {
+    Self {
+      min_radius: 2.0,
+      max_radius: 50.0,
+      distribution: RadiusDistribution::Uniform,
+      padding: 2.0,
+      max_attempts: 1000,
+      max_shapes: 10_000,
+    }
+  }
+}
+
+// Random placement: each circle starts at a random position inside the container
+// and grows up to its sampled radius, or until it touches a neighbour or the container.
+pub fn pack_circles(
+  container: &Polygon<f32>,
+  options: &PackingOptions,
+  rng: &mut StdRng,
+) -> Vec<Circle> {
+  let mut circles: Vec<Circle> = vec![];
+  let bounding_rect = match container.bounding_rect() {
+    Some(bounding_rect) => bounding_rect,
+    None => return circles,
+  };
+  let mut grid = SpatialGrid::new(2.0 * options.max_radius + options.padding);
+  let mut attempts = 0;
+
+  while attempts < options.max_attempts && circles.len() < options.max_shapes {
+    attempts += 1;
+    let center = Coord::from((
+      rng.gen_range(bounding_rect.min().x..=bounding_rect.max().x),
+      rng.gen_range(bounding_rect.min().y..=bounding_rect.max().y),
+    ));
+    if !container.intersects(&center) {
+      continue;
+    }
+    let target = options
+      .distribution
+      .sample(options.min_radius, options.max_radius, rng);
+    let mut radius = target.min(distance_to_boundary(container, center) - options.padding);
+    grid
+      .query(center, radius + options.max_radius + options.padding)
+      .for_each(|(_, index): &(Coord<f32>, usize)| {
+        let other: &Circle = &circles[*index];
+        let gap = other.center.euclidean_distance(&center) - other.radius - options.padding;
+        radius = radius.min(gap);
+      });
+    if radius < options.min_radius {
+      continue;
+    }
+    grid.insert(center, circles.len());
+    circles.push(Circle::new(center, radius));
+    attempts = 0;
+  }
+  circles
+}
+
+// Front-chain packing (Wang et al. "Visualization of large hierarchical data by circle packing"):
+// circles are placed tangent to a pair of circles of the chain surrounding the packing,
+// growing outwards from the container centroid. Gives denser packings than random placement.
+pub fn pack_circles_front_chain(
+  container: &Polygon<f32>,
+  options: &PackingOptions,
+  rng: &mut StdRng,
+) -> Vec<Circle> {
+  let (center, bounding_radius) = match (container.centroid(), container.bounding_rect()) {
+    (Some(center), Some(bounding_rect)) => {
+      let radius = [
+        bounding_rect.min(),
+        bounding_rect.max(),
+        (bounding_rect.min().x, bounding_rect.max().y).into(),
+        (bounding_rect.max().x, bounding_rect.min().y).into(),
+      ]
+      .iter()
+      .map(|corner| corner.euclidean_distance(&center.0))
+      .fold(0.0f32, f32::max);
+      (center.0, radius)
+    }
+    _ => return vec![],
+  };
+  let half_padding = options.padding / 2.0;
+  let radius = |rng: &mut StdRng| {
+    options
+      .distribution
+      .sample(options.min_radius, options.max_radius, rng)
+      + half_padding
+  };
+
+  // Padded circles packed around the origin, stored with their front chain links
+  let mut circles = vec![Circle::new(Coord::zero(), radius(rng))];
+  let second = radius(rng);
+  circles[0].center.x = -second;
+  circles.push(Circle::new((circles[0].radius, 0.0).into(), second));
+  let third = place(&circles[1], &circles[0], radius(rng));
+  circles.push(third);
+  let mut next = vec![1, 2, 0];
+  let mut previous = vec![2, 0, 1];
+  let (mut a, mut b) = (0, 1);
+
+  // Radius of a circle which could not be placed yet
+  let mut pending = None;
+  let mut attempts = 0;
+  'pack: while circles.len() < options.max_shapes && attempts < options.max_attempts {
+    // The packing is over once the whole chain lies out of the container
+    let chain_distance = chain(&next, a)
+      .map(|index| circles[index].center.euclidean_distance(&Coord::zero()) - circles[index].radius)
+      .fold(f32::INFINITY, f32::min);
+    if chain_distance > bounding_radius {
+      break;
+    }
+    attempts += 1;
+    let circle = place(
+      &circles[a],
+      &circles[b],
+      pending.take().unwrap_or_else(|| radius(rng)),
+    );
+
+    // Look for chain circles intersecting the new one, starting next to a and b
+    let (mut j, mut k) = (next[b], previous[a]);
+    let (mut sj, mut sk) = (circles[b].radius, circles[a].radius);
+    loop {
+      if sj <= sk {
+        if circles[j].intersects(&circle) {
+          b = j;
+          next[a] = b;
+          previous[b] = a;
+          pending = Some(circle.radius);
+          continue 'pack;
+        }
+        sj += circles[j].radius;
+        j = next[j];
+      } else {
+        if circles[k].intersects(&circle) {
+          a = k;
+          next[a] = b;
+          previous[b] = a;
+          pending = Some(circle.radius);
+          continue 'pack;
+        }
+        sk += circles[k].radius;
+        k = previous[k];
+      }
+      if j == next[k] {
+        break;
+      }
+    }
+
+    // Insert the circle between a and b
+    let c = circles.len();
+    circles.push(circle);
+    next.push(b);
+    previous.push(a);
+    next[a] = c;
+    previous[b] = c;
+    attempts = 0;
+
+    // Next circles are placed next to the pair closest to the origin
+    let score = |index: usize| {
+      let (first, second) = (&circles[index], &circles[next[index]]);
+      let weighted = (first.center * second.radius + second.center * first.radius)
+        / (first.radius + second.radius);
+      weighted.x * weighted.x + weighted.y * weighted.y
+    };
+    a = chain(&next, c)
+      .min_by(|i, j| score(*i).total_cmp(&score(*j)))
+      .unwrap_or(c);
+    b = next[a];
+  }
+
+  circles
+    .into_iter()
+    .map(|circle| Circle::new(circle.center + center, circle.radius - half_padding))
+    .filter(|circle| {
+      container.intersects(&circle.center)
+        && distance_to_boundary(container, circle.center) >= circle.radius + options.padding
+    })
+    .collect()
+}
+
+// Packs copies of shape, randomly rotated and scaled to fit the packed circles.
+// Shapes are fitted in their bounding circle around their centroid, which leaves gaps for
+// elongated shapes.
+pub fn pack_shapes(
+  container: &Polygon<f32>,
+  shape: &Polygon<f32>,
+  options: &PackingOptions,
+  rng: &mut StdRng,
+) -> Vec<Polygon<f32>> {
+  let centroid = match shape.centroid() {
+    Some(centroid) => centroid,
+    None => return vec![],
+  };
+  let shape_radius = shape
+    .exterior()
+    .points()
+    .map(|point| point.euclidean_distance(&centroid))
+    .fold(0.0f32, f32::max);
+  if shape_radius <= 0.0 {
+    return vec![];
+  }
+  let shape = shape.translate(-centroid.x(), -centroid.y());
+  pack_circles(container, options, rng)
+    .into_iter()
+    .map(|circle| {
+      let scale = circle.radius / shape_radius;
+      shape
+        .rotate_around_point(rng.gen_range(0.0..360.0), Point::from((0.0, 0.0)))
+        .scale_around_point(scale, scale, Point::from((0.0, 0.0)))
+        .translate(circle.center.x, circle.center.y)
+    })
+    .collect()
+}
+
+// Circle of the given radius tangent to a and b, on the left of a -> b
+fn place(a: &Circle, b: &Circle, radius: f32) -> Circle {
+  let (dx, dy) = (a.center.x - b.center.x, a.center.y - b.center.y);
+  let squared = dx * dx + dy * dy;
+  if squared <= 0.0 {
+    return Circle::new(b.center + Coord::from((radius, 0.0)), radius);
+  }
+  let to_b = (b.radius + radius).powi(2);
+  let to_a = (a.radius + radius).powi(2);
+  let center = match to_b > to_a {
+    true => {
+      let x = (squared + to_a - to_b) / (2.0 * squared);
+      let y = (to_a / squared - x * x).max(0.0).sqrt();
+      Coord::from((a.center.x - x * dx - y * dy, a.center.y - x * dy + y * dx))
+    }
+    false => {
+      let x = (squared + to_b - to_a) / (2.0 * squared);
+      let y = (to_b / squared - x * x).max(0.0).sqrt();
+      Coord::from((b.center.x + x * dx - y * dy, b.center.y + x * dy + y * dx))
+    }
+  };
+  Circle::new(center, radius)
+}
+
+// Indices of the front chain, starting at start
+fn chain(next: &[usize], start: usize) -> impl Iterator<Item = usize> + '_ {
+  std::iter::successors(Some(start), move |index| Some(next[*index]))
+    .enumerate()
+    .take_while(move |(i, index)| *i == 0 || *index != start)
+    .map(|(_, index)| index)
+}
+
+fn distance_to_boundary(polygon: &Polygon<f32>, coord: Coord<f32>) -> f32 {
+  let point = Point::from(coord);
+  std::iter::once(polygon.exterior())
+    .chain(polygon.interiors())
+    .map(|ring| point.euclidean_distance(ring))
+    .fold(f32::INFINITY, f32::min)
+}
+
+fn squared_distance(a: Coord<f32>, b: Coord<f32>) -> f32 {
+  (a.x - b.x).powi(2) + (a.y - b.y).powi(2)
+}
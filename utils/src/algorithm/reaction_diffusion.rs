@@ -0,0 +1,184 @@
+use geo::Rect;
+use rand::{rngs::StdRng, Rng};
+
+use crate::data_structures::scalar_grid::ScalarGrid;
+
+// Weights of the Laplacian kernel for the direct and the diagonal neighbours
+const DIRECT_WEIGHT: f32 = 0.2;
+const DIAGONAL_WEIGHT: f32 = 0.05;
+
+pub struct ReactionDiffusionOptions {
+  pub diffusion_a: f32,
+  pub diffusion_b: f32,
+  // Rate at which a is replenished
+  pub feed: f32,
+  // Rate at which b is removed
+  pub kill: f32,
+  pub time_step: f32,
+}
+
+// Coral-like growth, other classic (feed, kill) pairs are (0.0367, 0.0649) for mitosis,
+// (0.029, 0.057) for mazes and (0.039, 0.058) for holes
+impl Default for ReactionDiffusionOptions {
+  fn default() -> Self {
+    Self {
+      diffusion_a: 1.0,
+      diffusion_b: 0.5,
+      feed: 0.055,
+      kill: 0.062,
+      time_step: 1.0,
+    }
+  }
+}
+
+// Gray-Scott model: a is turned into b by the reaction a + 2b -> 3b while both diffuse,
+// a being fed and b killed at constant rates.
+// Cells are stored row by row, borders reflecting the concentrations.
+pub struct ReactionDiffusion {
+  width: usize,
+  height: usize,
+  a: Vec<f32>,
+  b: Vec<f32>,
+  feed: Vec<f32>,
+  kill: Vec<f32>,
+  // Masked out cells keep a = 1.0 and b = 0.0
+  mask: Vec<bool>,
+  options: ReactionDiffusionOptions,
+}
+
+impl ReactionDiffusion {
+  pub fn new(width: usize, height: usize, options: ReactionDiffusionOptions) -> Self {
+    let n = width * height;
+    Self {
+      width,
+      height,
+      a: vec![1.0; n],
+      b: vec![0.0; n],
+      feed: vec![options.feed; n],
+      kill: vec![options.kill; n],
+      mask: vec![true; n],
+      options,
+    }
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn a(&self) -> &[f32] {
+    &self.a
+  }
+
+  pub fn b(&self) -> &[f32] {
+    &self.b
+  }
+
+  // Restricts the reaction to the cells for which inside returns true
+  pub fn mask<F: Fn(usize, usize) -> bool>(&mut self, inside: F) {
+    self.cells().for_each(|(x, y)| {
+      let i = y * self.width + x;
+      self.mask[i] = inside(x, y);
+      if !self.mask[i] {
+        self.a[i] = 1.0;
+        self.b[i] = 0.0;
+      }
+    });
+  }
+
+  // Overrides the feed and kill rates of each cell, e.g. to blend patterns across the grid
+  pub fn rates<F: Fn(usize, usize) -> (f32, f32)>(&mut self, rates: F) {
+    self.cells().for_each(|(x, y)| {
+      let (feed, kill) = rates(x, y);
+      self.feed[y * self.width + x] = feed;
+      self.kill[y * self.width + x] = kill;
+    });
+  }
+
+  // Adds b in a disk of cells
+  pub fn seed(&mut self, x: usize, y: usize, radius: usize) {
+    let radius = radius as i64;
+    (-radius..=radius).for_each(|dy| {
+      (-radius..=radius).for_each(|dx| {
+        let (cx, cy) = (x as i64 + dx, y as i64 + dy);
+        let inside_grid = cx >= 0 && cy >= 0 && cx < self.width as i64 && cy < self.height as i64;
+        if inside_grid && dx * dx + dy * dy <= radius * radius {
+          let i = cy as usize * self.width + cx as usize;
+          if self.mask[i] {
+            self.b[i] = 1.0;
+          }
+        }
+      })
+    });
+  }
+
+  pub fn seed_random(&mut self, n_seeds: usize, radius: usize, rng: &mut StdRng) {
+    if self.width == 0 || self.height == 0 {
+      return;
+    }
+    (0..n_seeds).for_each(|_| {
+      let (x, y) = (rng.gen_range(0..self.width), rng.gen_range(0..self.height));
+      self.seed(x, y, radius);
+    });
+  }
+
+  pub fn step(&mut self) {
+    let (width, height) = (self.width, self.height);
+    let options = &self.options;
+    let mut a = self.a.clone();
+    let mut b = self.b.clone();
+    (0..height).for_each(|y| {
+      let rows = [y.saturating_sub(1), y, (y + 1).min(height - 1)];
+      (0..width).for_each(|x| {
+        let i = y * width + x;
+        if !self.mask[i] {
+          return;
+        }
+        let columns = [x.saturating_sub(1), x, (x + 1).min(width - 1)];
+        let (mut laplacian_a, mut laplacian_b) = (0.0, 0.0);
+        rows.iter().enumerate().for_each(|(row, ny)| {
+          columns.iter().enumerate().for_each(|(column, nx)| {
+            let weight = match (row, column) {
+              (1, 1) => -1.0,
+              (1, _) | (_, 1) => DIRECT_WEIGHT,
+              _ => DIAGONAL_WEIGHT,
+            };
+            let j = ny * width + nx;
+            laplacian_a += weight * self.a[j];
+            laplacian_b += weight * self.b[j];
+          })
+        });
+        let (cell_a, cell_b) = (self.a[i], self.b[i]);
+        let reaction = cell_a * cell_b * cell_b;
+        a[i] = (cell_a
+          + (options.diffusion_a * laplacian_a - reaction + self.feed[i] * (1.0 - cell_a))
+            * options.time_step)
+          .clamp(0.0, 1.0);
+        b[i] = (cell_b
+          + (options.diffusion_b * laplacian_b + reaction
+            - (self.kill[i] + self.feed[i]) * cell_b)
+            * options.time_step)
+          .clamp(0.0, 1.0);
+      })
+    });
+    self.a = a;
+    self.b = b;
+  }
+
+  pub fn steps(&mut self, n_steps: usize) {
+    (0..n_steps).for_each(|_| self.step());
+  }
+
+  // Concentration of b stretched over rect, ready for contour extraction
+  pub fn field(&self, rect: Rect<f32>) -> ScalarGrid {
+    ScalarGrid::new(self.b.clone(), self.width, self.height, rect)
+  }
+
+  fn cells(&self) -> impl Iterator<Item = (usize, usize)> {
+    let width = self.width;
+    (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+  }
+}
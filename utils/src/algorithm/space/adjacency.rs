@@ -0,0 +1,169 @@
+use geo::{BoundingRect, Coord, Line, LinesIter, Polygon, Rect};
+use std::collections::HashMap;
+
+use super::{Space, SpaceTile};
+
+// Maximum distance between two edges considered as shared
+const ADJACENCY_TOLERANCE: f32 = 1e-2;
+
+// Segment of border shared by two leaves, first < second
+pub struct SharedEdge {
+  pub first: usize,
+  pub second: usize,
+  pub line: Line<f32>,
+}
+
+// Which leaves of a space touch each other, and along which segments
+pub struct Adjacency {
+  neighbours: HashMap<usize, Vec<usize>>,
+  edges: Vec<SharedEdge>,
+}
+
+impl Adjacency {
+  // Leaves touching the leaf along a segment, touching only by a corner does not count
+  pub fn neighbours(&self, index: usize) -> &[usize] {
+    self
+      .neighbours
+      .get(&index)
+      .map(|neighbours| neighbours.as_slice())
+      .unwrap_or_default()
+  }
+
+  pub fn are_neighbours(&self, first: usize, second: usize) -> bool {
+    self.neighbours(first).contains(&second)
+  }
+
+  // Every shared segment, once. Useful to draw the borders between leaves a single time.
+  pub fn edges(&self) -> &[SharedEdge] {
+    &self.edges
+  }
+
+  pub fn shared_edges(&self, first: usize, second: usize) -> impl Iterator<Item = &Line<f32>> {
+    let (first, second) = (first.min(second), first.max(second));
+    self
+      .edges
+      .iter()
+      .filter(move |edge| edge.first == first && edge.second == second)
+      .map(|edge| &edge.line)
+  }
+
+  // Neighbours of each leaf given as positions in leaves, e.g. to colour the leaves
+  pub fn neighbour_lists(&self, leaves: &[usize]) -> Vec<Vec<usize>> {
+    let positions = leaves
+      .iter()
+      .enumerate()
+      .map(|(position, index)| (*index, position))
+      .collect::<HashMap<_, _>>();
+    leaves
+      .iter()
+      .map(|index| {
+        self
+          .neighbours(*index)
+          .iter()
+          .filter_map(|neighbour| positions.get(neighbour).copied())
+          .collect()
+      })
+      .collect()
+  }
+
+  // Total length of the border shared by the two leaves
+  pub fn shared_length(&self, first: usize, second: usize) -> f32 {
+    self
+      .shared_edges(first, second)
+      .map(|line| line.dx().hypot(line.dy()))
+      .sum()
+  }
+}
+
+pub fn compute_adjacency<T: SpaceTile>(space: &Space<T>) -> Adjacency {
+  let polygons = space
+    .leafs()
+    .into_iter()
+    .map(|index| {
+      let tile = space.get_node(index).unwrap().content();
+      (index, Rect::new(tile.min(), tile.max()).to_polygon())
+    })
+    .collect::<Vec<_>>();
+  adjacency(&polygons)
+}
+
+// Adjacency of polygon spaces, such as Voronoi cells or sliced polygons
+pub fn compute_polygon_adjacency(space: &Space<Polygon<f32>>) -> Adjacency {
+  let polygons = space
+    .leafs()
+    .into_iter()
+    .map(|index| (index, space.get_node(index).unwrap().content().clone()))
+    .collect::<Vec<_>>();
+  adjacency(&polygons)
+}
+
+fn adjacency(polygons: &[(usize, Polygon<f32>)]) -> Adjacency {
+  let mut bounded = polygons
+    .iter()
+    .filter_map(|(index, polygon)| polygon.bounding_rect().map(|rect| (*index, polygon, rect)))
+    .collect::<Vec<_>>();
+  bounded.sort_by(|(_, _, a), (_, _, b)| a.min().x.total_cmp(&b.min().x));
+
+  let mut neighbours: HashMap<usize, Vec<usize>> = HashMap::new();
+  let mut edges = vec![];
+  // Sweep along x: only polygons whose bounding rectangles overlap can touch
+  bounded
+    .iter()
+    .enumerate()
+    .for_each(|(i, (index, polygon, rect))| {
+      bounded[i + 1..]
+        .iter()
+        .take_while(|(_, _, other_rect)| other_rect.min().x <= rect.max().x + ADJACENCY_TOLERANCE)
+        .filter(|(_, _, other_rect)| {
+          other_rect.min().y <= rect.max().y + ADJACENCY_TOLERANCE
+            && rect.min().y <= other_rect.max().y + ADJACENCY_TOLERANCE
+        })
+        .for_each(|(other_index, other, _)| {
+          let shared = polygon
+            .lines_iter()
+            .flat_map(|line| {
+              other
+                .lines_iter()
+                .filter_map(move |other_line| overlap(line, other_line))
+            })
+            .collect::<Vec<_>>();
+          if shared.is_empty() {
+            return;
+          }
+          neighbours.entry(*index).or_default().push(*other_index);
+          neighbours.entry(*other_index).or_default().push(*index);
+          let (first, second) = (*index.min(other_index), *index.max(other_index));
+          edges.extend(shared.into_iter().map(|line| SharedEdge {
+            first,
+            second,
+            line,
+          }));
+        });
+    });
+  Adjacency { neighbours, edges }
+}
+
+// Common part of two collinear segments
+fn overlap(line: Line<f32>, other: Line<f32>) -> Option<Line<f32>> {
+  let length = line.dx().hypot(line.dy());
+  if length <= ADJACENCY_TOLERANCE {
+    return None;
+  }
+  let direction = Coord::from((line.dx(), line.dy())) / length;
+  let along = |coord: Coord<f32>| {
+    let delta = coord - line.start;
+    (
+      delta.x * direction.x + delta.y * direction.y,
+      (delta.x * direction.y - delta.y * direction.x).abs(),
+    )
+  };
+  let (t_start, d_start) = along(other.start);
+  let (t_end, d_end) = along(other.end);
+  if d_start > ADJACENCY_TOLERANCE || d_end > ADJACENCY_TOLERANCE {
+    return None;
+  }
+  let start = t_start.min(t_end).max(0.0);
+  let end = t_start.max(t_end).min(length);
+  (end - start > ADJACENCY_TOLERANCE)
+    .then(|| Line::new(line.start + direction * start, line.start + direction * end))
+}
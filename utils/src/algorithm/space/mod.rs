@@ -0,0 +1,414 @@
+mod adjacency;
+mod polygon;
+mod split;
+
+use geo::Coord;
+use rand::rngs::StdRng;
+use rand_distr::{Bernoulli, Distribution};
+use std::{
+  cmp::Ordering,
+  collections::{BinaryHeap, VecDeque},
+};
+
+use crate::data_structures::graph::{Graph, Node};
+
+pub use adjacency::{compute_adjacency, compute_polygon_adjacency, Adjacency, SharedEdge};
+pub use polygon::{compute_delaunay_space, compute_sliced_space, compute_voronoi_space};
+pub use split::{
+  DensitySplit, LongerSideSplit, MondrianSplit, QuadSplit, RatioSplit, SplitStrategy, UniformSplit,
+};
+
+pub trait SpaceTile {
+  fn new(min: Coord<f32>, max: Coord<f32>) -> Self;
+  fn width(&self) -> f32;
+  fn height(&self) -> f32;
+  fn min(&self) -> Coord<f32>;
+  fn max(&self) -> Coord<f32>;
+}
+
+pub type Space<T> = Graph<T>;
+
+// Number of times a split is drawn again when its children break the options constraints
+const MAX_SPLIT_ATTEMPTS: usize = 10;
+
+#[derive(Clone, Copy)]
+pub enum ExpansionOrder {
+  BreadthFirst,
+  DepthFirst,
+  // Always split the tile with the largest area first
+  LargestFirst,
+}
+
+pub struct SpaceOptions {
+  // Number of leaves after which the subdivision stops
+  pub max_leaves: usize,
+  pub max_depth: usize,
+  // Tiles with a side smaller than min_size are not split
+  pub min_size: f32,
+  // Splits giving children smaller than min_area or with a ratio between their shortest and
+  // longest sides under min_aspect_ratio are rejected
+  pub min_area: f32,
+  pub min_aspect_ratio: f32,
+  // Probability of splitting a tile given its depth, the root having a depth of 0
+  pub split_probability: Box<dyn Fn(usize) -> f32>,
+  pub order: ExpansionOrder,
+}
+
+impl Default for SpaceOptions {
+  fn default() -> Self {
+    Self {
+      max_leaves: 64,
+      max_depth: usize::MAX,
+      min_size: 0.0,
+      min_area: 0.0,
+      min_aspect_ratio: 0.0,
+      split_probability: Box::new(|_| 1.0),
+      order: ExpansionOrder::BreadthFirst,
+    }
+  }
+}
+
+// Recursively splits the root until the space has options.max_leaves leaves,
+// or no tile can be split anymore. Leaves always tile the root exactly.
+pub fn compute_space<T: SpaceTile, S: SplitStrategy>(
+  root: T,
+  options: &SpaceOptions,
+  strategy: &S,
+  rng: &mut StdRng,
+) -> Space<T> {
+  let mut space = Space::default();
+  let root_index = space.add_node(Node::new(root));
+
+  let mut frontier = Frontier::new(options.order);
+  frontier.push(&space, root_index, 0);
+  let mut n_leaves = 1;
+
+  while n_leaves < options.max_leaves {
+    let (index, depth) = match frontier.pop() {
+      Some(next) => next,
+      None => break,
+    };
+    let tile = space
+      .get_node(index)
+      .expect("Node with index  does not exists")
+      .content();
+
+    if depth >= options.max_depth
+      || tile.width() <= options.min_size
+      || tile.height() <= options.min_size
+    {
+      continue;
+    }
+    // Probabilities that are not finite, e.g. 0.0 / 0.0, never split
+    let probability = match (options.split_probability)(depth) {
+      probability if probability.is_finite() => probability.clamp(0.0, 1.0),
+      _ => 0.0,
+    };
+    if !Bernoulli::new(probability as f64).unwrap().sample(rng) {
+      continue;
+    }
+
+    let children = (0..MAX_SPLIT_ATTEMPTS)
+      .map(|_| strategy.split(tile, rng))
+      .find(|children| is_valid_split(children, options))
+      .unwrap_or_default();
+    // A split adding more leaves than allowed is rejected
+    if children.len() < 2 || n_leaves + children.len() - 1 > options.max_leaves {
+      continue;
+    }
+
+    n_leaves += children.len() - 1;
+    children.into_iter().for_each(|child| {
+      let child_index = space.add_node(Node::new(child));
+      space.add_edge(index, child_index);
+      frontier.push(&space, child_index, depth + 1);
+    });
+  }
+  space
+}
+
+// Tiles which may still be split, with their depth
+enum Frontier {
+  Queue(VecDeque<(usize, usize)>),
+  Stack(Vec<(usize, usize)>),
+  Heap(BinaryHeap<FrontierTile>),
+}
+
+struct FrontierTile {
+  area: f32,
+  index: usize,
+  depth: usize,
+}
+
+impl Frontier {
+  fn new(order: ExpansionOrder) -> Self {
+    match order {
+      ExpansionOrder::BreadthFirst => Frontier::Queue(VecDeque::new()),
+      ExpansionOrder::DepthFirst => Frontier::Stack(vec![]),
+      ExpansionOrder::LargestFirst => Frontier::Heap(BinaryHeap::new()),
+    }
+  }
+
+  fn push<T: SpaceTile>(&mut self, space: &Space<T>, index: usize, depth: usize) {
+    match self {
+      Frontier::Queue(queue) => queue.push_back((index, depth)),
+      Frontier::Stack(stack) => stack.push((index, depth)),
+      Frontier::Heap(heap) => {
+        let tile = space.get_node(index).unwrap().content();
+        heap.push(FrontierTile {
+          area: tile.width() * tile.height(),
+          index,
+          depth,
+        })
+      }
+    }
+  }
+
+  fn pop(&mut self) -> Option<(usize, usize)> {
+    match self {
+      Frontier::Queue(queue) => queue.pop_front(),
+      Frontier::Stack(stack) => stack.pop(),
+      Frontier::Heap(heap) => heap.pop().map(|tile| (tile.index, tile.depth)),
+    }
+  }
+}
+
+// Largest area first, the oldest tile first between equal areas
+impl Ord for FrontierTile {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self
+      .area
+      .total_cmp(&other.area)
+      .then_with(|| other.index.cmp(&self.index))
+  }
+}
+
+impl PartialOrd for FrontierTile {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl PartialEq for FrontierTile {
+  fn eq(&self, other: &Self) -> bool {
+    self.cmp(other) == Ordering::Equal
+  }
+}
+
+impl Eq for FrontierTile {}
+
+fn is_valid_split<T: SpaceTile>(children: &[T], options: &SpaceOptions) -> bool {
+  children.iter().all(|child| {
+    let (short, long) = (
+      child.width().min(child.height()),
+      child.width().max(child.height()),
+    );
+    short * long >= options.min_area && (long <= 0.0 || short / long >= options.min_aspect_ratio)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use rand::SeedableRng;
+
+  struct Tile {
+    min: Coord<f32>,
+    max: Coord<f32>,
+  }
+
+  impl SpaceTile for Tile {
+    fn new(min: Coord<f32>, max: Coord<f32>) -> Self {
+      Self { min, max }
+    }
+    fn width(&self) -> f32 {
+      self.max.x - self.min.x
+    }
+    fn height(&self) -> f32 {
+      self.max.y - self.min.y
+    }
+    fn min(&self) -> Coord<f32> {
+      self.min
+    }
+    fn max(&self) -> Coord<f32> {
+      self.max
+    }
+  }
+
+  const ORDERS: [ExpansionOrder; 3] = [
+    ExpansionOrder::BreadthFirst,
+    ExpansionOrder::DepthFirst,
+    ExpansionOrder::LargestFirst,
+  ];
+
+  fn root() -> Tile {
+    Tile::new(Coord::zero(), Coord::from((300.0, 200.0)))
+  }
+
+  fn leaves(space: &Space<Tile>) -> Vec<&Tile> {
+    space
+      .leafs()
+      .into_iter()
+      .map(|index| space.get_node(index).unwrap().content())
+      .collect()
+  }
+
+  fn area(tile: &Tile) -> f32 {
+    tile.width() * tile.height()
+  }
+
+  fn assert_tiles_root<S: SplitStrategy>(strategy: &S) {
+    let root = root();
+    let options = SpaceOptions {
+      max_leaves: 40,
+      ..Default::default()
+    };
+    let space = compute_space(
+      Tile::new(root.min, root.max),
+      &options,
+      strategy,
+      &mut StdRng::seed_from_u64(0),
+    );
+    let leaves = leaves(&space);
+    assert!(leaves.len() > 1);
+    let total = leaves.iter().map(|leaf| area(leaf)).sum::<f32>();
+    assert!((total - area(&root)).abs() < 1e-3 * area(&root));
+    leaves.iter().enumerate().for_each(|(i, a)| {
+      assert!(a.min.x >= root.min.x && a.min.y >= root.min.y);
+      assert!(a.max.x <= root.max.x && a.max.y <= root.max.y);
+      leaves[i + 1..].iter().for_each(|b| {
+        let overlap_x = a.max.x.min(b.max.x) - a.min.x.max(b.min.x);
+        let overlap_y = a.max.y.min(b.max.y) - a.min.y.max(b.min.y);
+        assert!(overlap_x <= 1e-3 || overlap_y <= 1e-3);
+      });
+    });
+  }
+
+  #[test]
+  fn max_leaves_is_respected() {
+    ORDERS.into_iter().for_each(|order| {
+      let options = SpaceOptions {
+        max_leaves: 37,
+        order,
+        ..Default::default()
+      };
+      let mut rng = StdRng::seed_from_u64(0);
+      let space = compute_space(root(), &options, &UniformSplit, &mut rng);
+      assert_eq!(leaves(&space).len(), 37);
+      // Quad splits add three leaves at a time
+      let space = compute_space(root(), &options, &QuadSplit { jitter: 0.5 }, &mut rng);
+      assert_eq!(leaves(&space).len(), 37);
+      let options = SpaceOptions {
+        max_leaves: 38,
+        ..options
+      };
+      let space = compute_space(root(), &options, &QuadSplit { jitter: 0.5 }, &mut rng);
+      assert_eq!(leaves(&space).len(), 37);
+    });
+  }
+
+  #[test]
+  fn max_depth_is_respected() {
+    ORDERS.into_iter().for_each(|order| {
+      let options = SpaceOptions {
+        max_leaves: usize::MAX,
+        max_depth: 4,
+        order,
+        ..Default::default()
+      };
+      let space = compute_space(
+        root(),
+        &options,
+        &UniformSplit,
+        &mut StdRng::seed_from_u64(0),
+      );
+      assert_eq!(leaves(&space).len(), 16);
+      space.leafs().into_iter().for_each(|index| {
+        assert_eq!(space.depth(index), Some(4));
+      });
+    });
+  }
+
+  #[test]
+  fn min_area_is_respected() {
+    ORDERS.into_iter().for_each(|order| {
+      let options = SpaceOptions {
+        max_leaves: 200,
+        min_area: 500.0,
+        order,
+        ..Default::default()
+      };
+      let space = compute_space(
+        root(),
+        &options,
+        &UniformSplit,
+        &mut StdRng::seed_from_u64(0),
+      );
+      let leaves = leaves(&space);
+      assert!(leaves.len() > 1);
+      leaves
+        .iter()
+        .for_each(|leaf| assert!(area(leaf) >= options.min_area));
+    });
+  }
+
+  #[test]
+  fn non_finite_split_probabilities_do_not_split() {
+    [f32::NAN, f32::INFINITY]
+      .into_iter()
+      .for_each(|probability| {
+        let options = SpaceOptions {
+          split_probability: Box::new(move |_| probability),
+          ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+        let space = compute_space(root(), &options, &UniformSplit, &mut rng);
+        assert_eq!(leaves(&space).len(), 1);
+      });
+  }
+
+  #[test]
+  fn non_finite_importances_do_not_split() {
+    let strategy = DensitySplit::new(|_: Coord<f32>| f32::NAN, UniformSplit);
+    let mut rng = StdRng::seed_from_u64(0);
+    let space = compute_space(root(), &SpaceOptions::default(), &strategy, &mut rng);
+    assert_eq!(leaves(&space).len(), 1);
+  }
+
+  #[test]
+  fn largest_tiles_are_split_first() {
+    let options = SpaceOptions {
+      max_leaves: 16,
+      order: ExpansionOrder::LargestFirst,
+      ..Default::default()
+    };
+    let space = compute_space(
+      root(),
+      &options,
+      &QuadSplit { jitter: 0.0 },
+      &mut StdRng::seed_from_u64(0),
+    );
+    // Five quad splits of equal tiles: the root and its four children
+    let leaves = leaves(&space);
+    assert_eq!(leaves.len(), 16);
+    leaves
+      .iter()
+      .for_each(|leaf| assert!((area(leaf) - area(&root()) / 16.0).abs() < 1e-2));
+  }
+
+  #[test]
+  fn leaves_tile_the_root() {
+    assert_tiles_root(&UniformSplit);
+    assert_tiles_root(&RatioSplit::golden());
+    assert_tiles_root(&LongerSideSplit { bias: 2.0 });
+    assert_tiles_root(&MondrianSplit {
+      min_aspect_ratio: 0.3,
+    });
+    assert_tiles_root(&QuadSplit { jitter: 0.5 });
+    // Always splits the root, less and less to the right
+    assert_tiles_root(&DensitySplit::new(
+      |coord: Coord<f32>| 1.0 - (coord.x - 150.0).max(0.0) / 300.0,
+      UniformSplit,
+    ));
+  }
+}
@@ -0,0 +1,301 @@
+use geo::{Area, BoundingRect, ConvexHull, Coord, Intersects, LineString, MultiPoint, Polygon};
+use rand::{rngs::StdRng, Rng};
+use std::{
+  collections::{BTreeSet, HashMap, HashSet, VecDeque},
+  f32::consts::PI,
+};
+
+use super::Space;
+use crate::data_structures::graph::Node;
+
+// Size of the triangle enclosing every point, relatively to the points bounding box
+const SUPER_TRIANGLE_SCALE: f64 = 1000.0;
+
+// Voronoi cells of points, clipped to the boundary.
+// The root of the space is the boundary and its children the cells, in the points order.
+// Points outside the boundary are skipped, as are duplicated points, so that the cells of the
+// other points cover the whole boundary. Holes of the boundary are ignored.
+pub fn compute_voronoi_space(points: &[Coord<f32>], boundary: Polygon<f32>) -> Space<Polygon<f32>> {
+  let exterior = open_ring(boundary.exterior());
+  let points = points
+    .iter()
+    .filter(|point| boundary.intersects(*point))
+    .copied()
+    .collect::<Vec<_>>();
+  let neighbours = delaunay_neighbours(&points);
+  let mut space = Space::default();
+  let root_index = space.add_node(Node::new(boundary));
+
+  let mut visited = HashSet::new();
+  points
+    .iter()
+    .zip(neighbours)
+    .filter(|(point, _)| visited.insert((point.x.to_bits(), point.y.to_bits())))
+    .filter_map(|(point, neighbours)| {
+      // Keep the part of the boundary closer to the point than to each of its neighbours
+      let cell = neighbours.iter().fold(exterior.clone(), |cell, neighbour| {
+        let other = points[*neighbour];
+        let normal = other - *point;
+        let offset = (dot(other, other) - dot(*point, *point)) / 2.0;
+        clip(&cell, normal, offset)
+      });
+      (cell.len() >= 3).then(|| Polygon::new(LineString::new(cell), vec![]))
+    })
+    .for_each(|cell| {
+      let index = space.add_node(Node::new(cell));
+      space.add_edge(root_index, index);
+    });
+  space
+}
+
+// Delaunay triangulation of points.
+// The root of the space is the convex hull of the points and its children the triangles.
+pub fn compute_delaunay_space(points: &[Coord<f32>]) -> Space<Polygon<f32>> {
+  let mut space = Space::default();
+  let hull = MultiPoint::from(points.to_vec()).convex_hull();
+  let root_index = space.add_node(Node::new(hull));
+
+  triangulate(points)
+    .into_iter()
+    .filter(|triangle| triangle.iter().all(|vertex| *vertex < points.len()))
+    .for_each(|triangle| {
+      let coords = triangle.iter().map(|vertex| points[*vertex]).collect();
+      let index = space.add_node(Node::new(Polygon::new(LineString::new(coords), vec![])));
+      space.add_edge(root_index, index);
+    });
+  space
+}
+
+// Recursively slices the root by random lines going through the polygons, in a breadth first order.
+// Slicing a convex polygon gives convex polygons, concave roots may give degenerate pieces.
+// Only the exterior of the root is sliced, its holes are dropped.
+pub fn compute_sliced_space(
+  root: Polygon<f32>,
+  max_leaves: usize,
+  min_area: f32,
+  rng: &mut StdRng,
+) -> Space<Polygon<f32>> {
+  let mut space = Space::default();
+  let root_index = space.add_node(Node::new(root));
+
+  let mut queue = VecDeque::new();
+  queue.push_back(root_index);
+  let mut n_leaves = 1;
+
+  while let Some(index) = queue.pop_front() {
+    if n_leaves >= max_leaves {
+      break;
+    }
+    let polygon = space
+      .get_node(index)
+      .expect("Node with index  does not exists")
+      .content();
+
+    // Only slice polygons which are not too small
+    if polygon.unsigned_area() > min_area {
+      let children = slice(polygon, rng);
+
+      n_leaves += children.len().saturating_sub(1);
+      children.into_iter().for_each(|child| {
+        let child_index = space.add_node(Node::new(child));
+        space.add_edge(index, child_index);
+        queue.push_back(child_index);
+      });
+    }
+  }
+  space
+}
+
+// Slices the polygon in two by a line of random direction crossing it
+fn slice(polygon: &Polygon<f32>, rng: &mut StdRng) -> Vec<Polygon<f32>> {
+  let ring = open_ring(polygon.exterior());
+  let theta = rng.gen_range(0.0..PI);
+  let normal = Coord::from((theta.cos(), theta.sin()));
+  let (min, max) = ring.iter().map(|coord| dot(*coord, normal)).fold(
+    (f32::INFINITY, f32::NEG_INFINITY),
+    |(min, max), projection| (min.min(projection), max.max(projection)),
+  );
+  if min >= max {
+    return vec![];
+  }
+  let offset = rng.gen_range(min..max);
+  [
+    clip(&ring, normal, offset),
+    clip(&ring, normal * -1.0, -offset),
+  ]
+  .into_iter()
+  .filter(|part| part.len() >= 3)
+  .map(|part| Polygon::new(LineString::new(part), vec![]))
+  .collect()
+}
+
+// Sutherland-Hodgman clipping of the ring by the half-plane coord.normal <= offset
+fn clip(ring: &[Coord<f32>], normal: Coord<f32>, offset: f32) -> Vec<Coord<f32>> {
+  let distance = |coord: Coord<f32>| dot(coord, normal) - offset;
+  let mut clipped = Vec::with_capacity(ring.len() + 1);
+  ring.iter().enumerate().for_each(|(i, current)| {
+    let previous = ring[(i + ring.len() - 1) % ring.len()];
+    let (d_previous, d_current) = (distance(previous), distance(*current));
+    if (d_previous <= 0.0) != (d_current <= 0.0) {
+      let t = d_previous / (d_previous - d_current);
+      clipped.push(previous + (*current - previous) * t);
+    }
+    if d_current <= 0.0 {
+      clipped.push(*current);
+    }
+  });
+  clipped
+}
+
+fn open_ring(ring: &LineString<f32>) -> Vec<Coord<f32>> {
+  let mut coords = ring.0.clone();
+  if coords.len() > 1 && coords.first() == coords.last() {
+    coords.pop();
+  }
+  coords
+}
+
+fn dot(a: Coord<f32>, b: Coord<f32>) -> f32 {
+  a.x * b.x + a.y * b.y
+}
+
+// Indices of the points sharing a Delaunay edge with each point
+fn delaunay_neighbours(points: &[Coord<f32>]) -> Vec<Vec<usize>> {
+  let mut neighbours = vec![BTreeSet::new(); points.len()];
+  // Triangles touching the enclosing triangle are kept, they hold the edges of the convex hull
+  triangulate(points).into_iter().for_each(|triangle| {
+    (0..3).for_each(|i| {
+      let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+      if a < points.len() && b < points.len() {
+        neighbours[a].insert(b);
+        neighbours[b].insert(a);
+      }
+    })
+  });
+  neighbours
+    .into_iter()
+    .map(|neighbours| neighbours.into_iter().collect())
+    .collect()
+}
+
+struct Triangle {
+  vertices: [usize; 3],
+  center: (f64, f64),
+  squared_radius: f64,
+}
+
+impl Triangle {
+  fn new(vertices: [usize; 3], coords: &[(f64, f64)]) -> Self {
+    let [(ax, ay), (bx, by), (cx, cy)] = vertices.map(|vertex| coords[vertex]);
+    let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+    let (a2, b2, c2) = (ax * ax + ay * ay, bx * bx + by * by, cx * cx + cy * cy);
+    let center = (
+      (a2 * (by - cy) + b2 * (cy - ay) + c2 * (ay - by)) / d,
+      (a2 * (cx - bx) + b2 * (ax - cx) + c2 * (bx - ax)) / d,
+    );
+    let squared_radius = (ax - center.0).powi(2) + (ay - center.1).powi(2);
+    Self {
+      vertices,
+      center,
+      // Collinear vertices: the circumcircle is the whole plane
+      squared_radius: match squared_radius.is_finite() {
+        true => squared_radius,
+        false => f64::INFINITY,
+      },
+    }
+  }
+
+  fn circumcircle_contains(&self, (x, y): (f64, f64)) -> bool {
+    match self.squared_radius.is_finite() {
+      true => (x - self.center.0).powi(2) + (y - self.center.1).powi(2) < self.squared_radius,
+      false => true,
+    }
+  }
+}
+
+// Bowyer-Watson triangulation.
+// Vertices greater or equal to points.len() belong to the triangle enclosing every point.
+fn triangulate(points: &[Coord<f32>]) -> Vec<[usize; 3]> {
+  let bounding_rect = match MultiPoint::from(points.to_vec()).bounding_rect() {
+    Some(bounding_rect) => bounding_rect,
+    None => return vec![],
+  };
+  let center = bounding_rect.center();
+  let (cx, cy) = (center.x as f64, center.y as f64);
+  let size =
+    bounding_rect.width().max(bounding_rect.height()).max(1.0) as f64 * SUPER_TRIANGLE_SCALE;
+
+  let mut coords: Vec<(f64, f64)> = points
+    .iter()
+    .map(|point| (point.x as f64, point.y as f64))
+    .collect();
+  let n_points = coords.len();
+  coords.extend([
+    (cx - size, cy - size),
+    (cx, cy + size),
+    (cx + size, cy - size),
+  ]);
+  let mut triangles = vec![Triangle::new(
+    [n_points, n_points + 1, n_points + 2],
+    &coords,
+  )];
+
+  let mut inserted = HashSet::new();
+  (0..n_points).for_each(|i| {
+    // Skip duplicated points
+    if !inserted.insert((points[i].x.to_bits(), points[i].y.to_bits())) {
+      return;
+    }
+    let (bad, good): (Vec<_>, Vec<_>) = triangles
+      .drain(..)
+      .partition(|triangle| triangle.circumcircle_contains(coords[i]));
+    triangles = good;
+
+    // The cavity left by the bad triangles is bounded by their unshared edges
+    let edges = bad
+      .iter()
+      .flat_map(|triangle| (0..3).map(|j| (triangle.vertices[j], triangle.vertices[(j + 1) % 3])))
+      .collect::<Vec<_>>();
+    let mut counts: HashMap<(usize, usize), usize> = HashMap::new();
+    edges.iter().for_each(|(a, b)| {
+      *counts.entry((*a.min(b), *a.max(b))).or_insert(0) += 1;
+    });
+    edges
+      .into_iter()
+      .filter(|(a, b)| counts[&(*a.min(b), *a.max(b))] == 1)
+      .for_each(|(a, b)| triangles.push(Triangle::new([a, b, i], &coords)));
+  });
+
+  triangles
+    .into_iter()
+    .map(|triangle| triangle.vertices)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use geo::Rect;
+
+  #[test]
+  fn points_outside_the_boundary_give_no_cell() {
+    let boundary = Rect::new(Coord::zero(), Coord::from((100.0, 100.0))).to_polygon();
+    let points = [
+      (20.0, 30.0),
+      (70.0, 60.0),
+      (40.0, 80.0),
+      (150.0, 50.0),
+      (-10.0, -10.0),
+    ]
+    .map(Coord::from);
+    let space = compute_voronoi_space(&points, boundary.clone());
+    let cells = space
+      .leafs()
+      .into_iter()
+      .map(|index| space.get_node(index).unwrap().content().clone())
+      .collect::<Vec<_>>();
+    assert_eq!(cells.len(), 3);
+    let area = cells.iter().map(|cell| cell.unsigned_area()).sum::<f32>();
+    assert!((area - boundary.unsigned_area()).abs() < 1e-2);
+  }
+}
@@ -0,0 +1,203 @@
+use super::SpaceTile;
+use geo::Coord;
+use rand::{rngs::StdRng, Rng};
+use rand_distr::{Bernoulli, Distribution};
+
+// Inverse of the golden ratio
+const GOLDEN_RATIO: f32 = 0.618_034;
+
+pub trait SplitStrategy {
+  // Children of the tile, an empty vector meaning the tile must not be split
+  fn split<T: SpaceTile>(&self, tile: &T, rng: &mut StdRng) -> Vec<T>;
+}
+
+// Splits along a random axis at a uniformly random position
+pub struct UniformSplit;
+
+impl SplitStrategy for UniformSplit {
+  fn split<T: SpaceTile>(&self, tile: &T, rng: &mut StdRng) -> Vec<T> {
+    let axis = Bernoulli::new(0.5).unwrap().sample(rng);
+
+    match axis {
+      true => {
+        let y = rng.gen_range(0.0..tile.height());
+        split_y(tile, tile.min().y + y)
+      }
+      false => {
+        let x = rng.gen_range(0.0..tile.width());
+        split_x(tile, tile.min().x + x)
+      }
+    }
+  }
+}
+
+// Splits along a random axis so that one child takes ratio of the tile size
+pub struct RatioSplit {
+  pub ratio: f32,
+}
+
+impl RatioSplit {
+  pub fn new(ratio: f32) -> Self {
+    Self {
+      ratio: ratio.clamp(0.0, 1.0),
+    }
+  }
+
+  pub fn golden() -> Self {
+    Self::new(GOLDEN_RATIO)
+  }
+}
+
+impl SplitStrategy for RatioSplit {
+  fn split<T: SpaceTile>(&self, tile: &T, rng: &mut StdRng) -> Vec<T> {
+    let axis = Bernoulli::new(0.5).unwrap().sample(rng);
+    // Randomly choose which child gets the biggest part
+    let ratio = match Bernoulli::new(0.5).unwrap().sample(rng) {
+      true => self.ratio,
+      false => 1.0 - self.ratio,
+    };
+    match axis {
+      true => split_y(tile, tile.min().y + ratio * tile.height()),
+      false => split_x(tile, tile.min().x + ratio * tile.width()),
+    }
+  }
+}
+
+// Splits across the longer side more often, bias = 0.0 being a fair coin flip
+// and higher values making the choice of the longer side more systematic
+pub struct LongerSideSplit {
+  pub bias: f32,
+}
+
+impl SplitStrategy for LongerSideSplit {
+  fn split<T: SpaceTile>(&self, tile: &T, rng: &mut StdRng) -> Vec<T> {
+    let width = tile.width().powf(self.bias);
+    let height = tile.height().powf(self.bias);
+    let probability = (height / (width + height)).clamp(0.0, 1.0);
+    let axis = match probability.is_finite() {
+      true => Bernoulli::new(probability as f64).unwrap().sample(rng),
+      false => Bernoulli::new(0.5).unwrap().sample(rng),
+    };
+    match axis {
+      true => {
+        let y = rng.gen_range(0.0..tile.height());
+        split_y(tile, tile.min().y + y)
+      }
+      false => {
+        let x = rng.gen_range(0.0..tile.width());
+        split_x(tile, tile.min().x + x)
+      }
+    }
+  }
+}
+
+// Mondrian-like splits: both children keep a ratio between their shortest and longest side
+// above min_aspect_ratio. Tiles which cannot be split that way are kept.
+pub struct MondrianSplit {
+  pub min_aspect_ratio: f32,
+}
+
+impl MondrianSplit {
+  // Range of positions along a side of length size, given the other side length,
+  // for which both children respect the minimum aspect ratio
+  fn allowed_range(&self, size: f32, other: f32) -> Option<(f32, f32)> {
+    let ratio = self.min_aspect_ratio.clamp(0.0, 1.0);
+    // A child side s must verify ratio * other <= s (s is the short side)
+    // and s <= other / ratio (s is the long side)
+    let min = ratio * other;
+    let max = match ratio > 0.0 {
+      true => other / ratio,
+      false => f32::INFINITY,
+    };
+    let start = min.max(size - max);
+    let end = max.min(size - min);
+    (start < end).then_some((start, end))
+  }
+}
+
+impl SplitStrategy for MondrianSplit {
+  fn split<T: SpaceTile>(&self, tile: &T, rng: &mut StdRng) -> Vec<T> {
+    let x_range = self.allowed_range(tile.width(), tile.height());
+    let y_range = self.allowed_range(tile.height(), tile.width());
+    let axis = match (x_range, y_range) {
+      (Some(_), Some(_)) => Bernoulli::new(0.5).unwrap().sample(rng),
+      (None, Some(_)) => true,
+      (Some(_), None) => false,
+      (None, None) => return vec![],
+    };
+    match (axis, x_range, y_range) {
+      (true, _, Some((start, end))) => split_y(tile, tile.min().y + rng.gen_range(start..end)),
+      (false, Some((start, end)), _) => split_x(tile, tile.min().x + rng.gen_range(start..end)),
+      _ => vec![],
+    }
+  }
+}
+
+// Splits into four children around the center, moved by up to jitter times the tile half size
+pub struct QuadSplit {
+  pub jitter: f32,
+}
+
+impl SplitStrategy for QuadSplit {
+  fn split<T: SpaceTile>(&self, tile: &T, rng: &mut StdRng) -> Vec<T> {
+    let jitter = self.jitter.clamp(0.0, 1.0);
+    let offset = |size: f32, rng: &mut StdRng| match jitter > 0.0 {
+      true => rng.gen_range(-jitter..jitter) * size / 2.0,
+      false => 0.0,
+    };
+    let x = tile.min().x + tile.width() / 2.0 + offset(tile.width(), rng);
+    let y = tile.min().y + tile.height() / 2.0 + offset(tile.height(), rng);
+    let (min, max) = (tile.min(), tile.max());
+    vec![
+      T::new(min, (x, y).into()),
+      T::new((x, min.y).into(), (max.x, y).into()),
+      T::new((min.x, y).into(), (x, max.y).into()),
+      T::new((x, y).into(), max),
+    ]
+  }
+}
+
+// Splits with the inner strategy with a probability given by the importance at the tile center,
+// so that the space gets more subdivided where the importance is high
+pub struct DensitySplit<F, S> {
+  importance: F,
+  strategy: S,
+}
+
+impl<F: Fn(Coord<f32>) -> f32, S: SplitStrategy> DensitySplit<F, S> {
+  pub fn new(importance: F, strategy: S) -> Self {
+    Self {
+      importance,
+      strategy,
+    }
+  }
+}
+
+impl<F: Fn(Coord<f32>) -> f32, S: SplitStrategy> SplitStrategy for DensitySplit<F, S> {
+  fn split<T: SpaceTile>(&self, tile: &T, rng: &mut StdRng) -> Vec<T> {
+    let center = (tile.min() + tile.max()) / 2.0;
+    // Importances that are not finite, e.g. 0.0 / 0.0, never split
+    let probability = match (self.importance)(center) {
+      importance if importance.is_finite() => importance.clamp(0.0, 1.0),
+      _ => 0.0,
+    };
+    match Bernoulli::new(probability as f64).unwrap().sample(rng) {
+      true => self.strategy.split(tile, rng),
+      false => vec![],
+    }
+  }
+}
+
+fn split_x<T: SpaceTile>(tile: &T, x: f32) -> Vec<T> {
+  vec![
+    T::new(tile.min(), (x, tile.max().y).into()),
+    T::new((x, tile.min().y).into(), tile.max()),
+  ]
+}
+
+fn split_y<T: SpaceTile>(tile: &T, y: f32) -> Vec<T> {
+  vec![
+    T::new(tile.min(), (tile.max().x, y).into()),
+    T::new((tile.min().x, y).into(), tile.max()),
+  ]
+}
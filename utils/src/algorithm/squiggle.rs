@@ -1,85 +1,185 @@
-use super::space::Space;
-use crate::geometry;
-use geo::{Coord, Rect};
+use crate::geometry::{self, broken_line::BrokenLineBuilder};
+use geo::{Coord, LineString, Rect};
 use rand::{rngs::StdRng, Rng};
-use rand_distr::{Distribution, Normal, Standard};
-
-// const PADDING: f32 = 50.0;
-const HATCH_DENSITY_MEAN: f32 = 40.0;
-const HATCH_DENSITY_STD: f32 = 10.0;
-// const MAX_BREAKPOINTS_MEAN: f32 = 50.0;
-// const MAX_BREAKPOINTS_VARIANCE: f32 = 10.0;
-// const BREAK_PROPORTION_MEAN: f64 = 0.8;
-// const BREAK_PROPORTION_VARIANCE: f64 = 0.05;
-
-enum HatchRotation {
-  NegFracPi8,
-  NegFracPi4,
-  NegFrac3Pi8,
-  Frac3Pi8,
-  FracPi4,
-  FracPi8,
+use rand_distr::{Distribution, Normal};
+
+#[derive(Clone, Copy)]
+pub enum ScalarDistribution {
+  Constant(f32),
+  Uniform { min: f32, max: f32 },
+  Normal { mean: f32, std_dev: f32 },
 }
 
-pub fn squiggle(space: &mut Space<Rect<f32>>, rng: &mut StdRng) -> Vec<(Coord<f32>, Coord<f32>)> {
-  //   let max_breakpoints_law = Normal::new(MAX_BREAKPOINTS_MEAN, MAX_BREAKPOINTS_VARIANCE).unwrap();
-  //   let break_proportion_law = Normal::new(BREAK_PROPORTION_MEAN, BREAK_PROPORTION_VARIANCE).unwrap();
-  create_hatches(space, rng)
-  // .iter()
-  // .map(|hatches| {
-  //   let max_breakpoint = max_breakpoints_law.sample(&mut rand::thread_rng()) as usize;
-  //   let break_proportion = break_proportion_law.sample(&mut rand::thread_rng());
-  //   hatches.iter().map(move |hatch| {
-  //     BrokenLineBuilder::new(&hatch)
-  //       .max_breakpoints(max_breakpoint)
-  //       .break_proportion(break_proportion)
-  //       .build()
-  //       .segments
-  //   })
-  // })
-  // .flatten()
-  // .flatten()
-  // .collect()
+impl ScalarDistribution {
+  pub fn sample(&self, rng: &mut StdRng) -> f32 {
+    match *self {
+      ScalarDistribution::Constant(value) => value,
+      ScalarDistribution::Uniform { min, max } if min < max => rng.gen_range(min..max),
+      ScalarDistribution::Uniform { min, .. } => min,
+      ScalarDistribution::Normal { mean, std_dev } => Normal::new(mean, std_dev.abs())
+        .map(|normal| normal.sample(rng))
+        .unwrap_or(mean),
+    }
+  }
 }
 
-fn create_hatches(space: &mut Space<Rect<f32>>, rng: &mut StdRng) -> Vec<(Coord<f32>, Coord<f32>)> {
-  space
-    .leafs()
-    .iter()
-    .map(|index| space.get_node(*index).unwrap().content())
-    .flat_map(|rectangle| {
-      // IDEA: increment & rotation depending on rectangle size
-      let hatch_density = Normal::new(HATCH_DENSITY_MEAN, HATCH_DENSITY_STD)
-        .unwrap()
-        .sample(rng);
-      let hatch_degrees = rand::random::<HatchRotation>().value();
-      geometry::hatch::hatch(rectangle.to_polygon(), hatch_density, hatch_degrees)
-    })
-    .collect()
+// Hatch angles, in degrees
+pub enum AngleDistribution {
+  Discrete(Vec<f32>),
+  Continuous { min: f32, max: f32 },
 }
 
-impl HatchRotation {
-  fn value(&self) -> f32 {
+impl AngleDistribution {
+  pub fn sample(&self, rng: &mut StdRng) -> f32 {
     match self {
-      Self::NegFracPi8 => -std::f32::consts::FRAC_PI_8,
-      Self::NegFracPi4 => -std::f32::consts::FRAC_PI_4,
-      Self::NegFrac3Pi8 => -(std::f32::consts::FRAC_PI_8 + std::f32::consts::FRAC_PI_4),
-      Self::Frac3Pi8 => std::f32::consts::FRAC_PI_8 + std::f32::consts::FRAC_PI_4,
-      Self::FracPi4 => std::f32::consts::FRAC_PI_4,
-      Self::FracPi8 => std::f32::consts::FRAC_PI_8,
+      AngleDistribution::Discrete(angles) if !angles.is_empty() => {
+        angles[rng.gen_range(0..angles.len())]
+      }
+      AngleDistribution::Discrete(_) => 0.0,
+      AngleDistribution::Continuous { min, max } if min < max => rng.gen_range(*min..*max),
+      AngleDistribution::Continuous { min, .. } => *min,
     }
   }
 }
 
-impl Distribution<HatchRotation> for Standard {
-  fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> HatchRotation {
-    match rng.gen_range(0..=5) {
-      0 => HatchRotation::NegFracPi8,
-      1 => HatchRotation::NegFracPi4,
-      2 => HatchRotation::NegFrac3Pi8,
-      3 => HatchRotation::Frac3Pi8,
-      4 => HatchRotation::FracPi4,
-      _ => HatchRotation::FracPi8,
+// Hatch angle of a tile, given the angle drawn from the distribution
+pub type AngleFn = Box<dyn Fn(&Rect<f32>, f32) -> f32>;
+
+// How the hatch angle of a tile is chosen
+pub enum AngleRule {
+  // Angle drawn from the angle distribution
+  Random,
+  // Hatches along the longest side of the tile, turned by an angle drawn from the distribution
+  Aspect,
+  // e.g. to hatch small tiles differently from the big ones
+  Custom(AngleFn),
+}
+
+pub struct SquiggleOptions {
+  density: ScalarDistribution,
+  angle: AngleDistribution,
+  angle_rule: AngleRule,
+  max_breakpoints: ScalarDistribution,
+  break_proportion: ScalarDistribution,
+  contour: bool,
+  padding: f32,
+}
+
+impl Default for SquiggleOptions {
+  fn default() -> Self {
+    Self {
+      density: ScalarDistribution::Normal {
+        mean: 0.1,
+        std_dev: 0.05,
+      },
+      angle: AngleDistribution::Discrete(vec![-67.5, -45.0, -22.5, 22.5, 45.0, 67.5]),
+      angle_rule: AngleRule::Random,
+      max_breakpoints: ScalarDistribution::Normal {
+        mean: 50.0,
+        std_dev: 10.0,
+      },
+      break_proportion: ScalarDistribution::Normal {
+        mean: 0.8,
+        std_dev: 0.05,
+      },
+      contour: true,
+      padding: 10.0,
+    }
+  }
+}
+
+impl SquiggleOptions {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  // Hatch density of a tile, see geometry::hatch::hatch
+  pub fn density(mut self, density: ScalarDistribution) -> Self {
+    self.density = density;
+    self
+  }
+
+  pub fn angle(mut self, angle: AngleDistribution) -> Self {
+    self.angle = angle;
+    self
+  }
+
+  pub fn angle_rule(mut self, angle_rule: AngleRule) -> Self {
+    self.angle_rule = angle_rule;
+    self
+  }
+
+  // Breaking parameters of the hatches, drawn once per tile. See BrokenLineBuilder.
+  pub fn max_breakpoints(mut self, max_breakpoints: ScalarDistribution) -> Self {
+    self.max_breakpoints = max_breakpoints;
+    self
+  }
+
+  pub fn break_proportion(mut self, break_proportion: ScalarDistribution) -> Self {
+    self.break_proportion = break_proportion;
+    self
+  }
+
+  // Draw the outline of the tiles
+  pub fn contour(mut self, contour: bool) -> Self {
+    self.contour = contour;
+    self
+  }
+
+  // Space left between the tiles borders and their hatches
+  pub fn padding(mut self, padding: f32) -> Self {
+    self.padding = padding;
+    self
+  }
+
+  fn tile_angle(&self, rect: &Rect<f32>, rng: &mut StdRng) -> f32 {
+    let angle = self.angle.sample(rng);
+    match &self.angle_rule {
+      AngleRule::Random => angle,
+      AngleRule::Aspect if rect.width() >= rect.height() => angle,
+      AngleRule::Aspect => 90.0 + angle,
+      AngleRule::Custom(rule) => rule(rect, angle),
     }
   }
 }
+
+// Fills each tile with broken hatches
+pub fn squiggle(
+  tiles: &[Rect<f32>],
+  options: &SquiggleOptions,
+  rng: &mut StdRng,
+) -> Vec<LineString<f32>> {
+  let mut lines = vec![];
+  tiles.iter().for_each(|tile| {
+    let offset = Coord::from((options.padding, options.padding));
+    let (min, max) = (tile.min() + offset, tile.max() - offset);
+    if min.x >= max.x || min.y >= max.y {
+      return;
+    }
+    let rect = Rect::new(min, max);
+
+    if options.contour {
+      lines.extend(
+        rect
+          .to_lines()
+          .iter()
+          .map(|line| LineString::new(vec![line.start, line.end])),
+      );
+    }
+
+    let density = options.density.sample(rng);
+    let degrees = options.tile_angle(&rect, rng);
+    let hatches = geometry::hatch::hatch(rect.to_polygon(), density, degrees).collect::<Vec<_>>();
+    // Hatches of a same tile share their breaking parameters
+    let max_breakpoints = options.max_breakpoints.sample(rng).max(0.0) as usize;
+    let break_proportion = options.break_proportion.sample(rng);
+    hatches.into_iter().for_each(|(start, end)| {
+      let broken_line = BrokenLineBuilder::new(start, end)
+        .max_breakpoints(max_breakpoints)
+        .break_proportion(break_proportion)
+        .build(rng);
+      lines.extend(broken_line.segments);
+    });
+  });
+  lines
+}
@@ -0,0 +1,798 @@
+use geo::{Coord, EuclideanDistance, Line, LineString, MultiLineString, Rect};
+use rand::{rngs::StdRng, Rng};
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::SQRT_2;
+
+use super::space::{compute_adjacency, Space, SpaceTile};
+use crate::geometry::curve::{sample_adaptive, CubicBezier};
+
+// Maximum distance between two connectors considered as the same point
+const CONNECTOR_TOLERANCE: f32 = 1e-2;
+// Distance of the control points giving a quarter circle with a cubic Bézier curve, relatively to
+// the radius
+const KAPPA: f32 = 0.552_284_8;
+
+// Side rank and rounded position of a connector
+type ConnectorKey = (usize, i32);
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Side {
+  Bottom,
+  Right,
+  Top,
+  Left,
+}
+
+impl Side {
+  const ALL: [Side; 4] = [Side::Bottom, Side::Right, Side::Top, Side::Left];
+
+  // Quarter turn counter-clockwise
+  fn rotated(self) -> Self {
+    match self {
+      Side::Bottom => Side::Right,
+      Side::Right => Side::Top,
+      Side::Top => Side::Left,
+      Side::Left => Side::Bottom,
+    }
+  }
+
+  // Mirror along the vertical axis
+  fn reflected(self) -> Self {
+    match self {
+      Side::Right => Side::Left,
+      Side::Left => Side::Right,
+      side => side,
+    }
+  }
+
+  // Direction pointing to the inside of the tile
+  fn inward(self) -> Coord<f32> {
+    match self {
+      Side::Bottom => (0.0, 1.0).into(),
+      Side::Right => (-1.0, 0.0).into(),
+      Side::Top => (0.0, -1.0).into(),
+      Side::Left => (1.0, 0.0).into(),
+    }
+  }
+}
+
+// Point of a tile side where paths cross over to the neighbouring tile. Positions go from 0.0 to
+// 1.0 counter-clockwise around the tile, so that rotations keep them.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Connector {
+  pub side: Side,
+  pub position: f32,
+}
+
+impl Connector {
+  pub fn new(side: Side, position: f32) -> Self {
+    Self {
+      side,
+      position: position.clamp(0.0, 1.0),
+    }
+  }
+
+  pub fn middle(side: Side) -> Self {
+    Self::new(side, 0.5)
+  }
+
+  // Coordinates in the unit square of the tile
+  pub fn coord(&self) -> Coord<f32> {
+    let p = self.position;
+    match self.side {
+      Side::Bottom => (p, 0.0).into(),
+      Side::Right => (1.0, p).into(),
+      Side::Top => (1.0 - p, 1.0).into(),
+      Side::Left => (0.0, 1.0 - p).into(),
+    }
+  }
+
+  fn rotated(self) -> Self {
+    Self::new(self.side.rotated(), self.position)
+  }
+
+  fn reflected(self) -> Self {
+    Self::new(self.side.reflected(), 1.0 - self.position)
+  }
+
+  // Rank counter-clockwise around the tile, used to compare connectors
+  fn key(&self) -> ConnectorKey {
+    let side = Side::ALL
+      .iter()
+      .position(|side| *side == self.side)
+      .unwrap();
+    (side, (self.position * 1e4).round() as i32)
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PathShape {
+  Straight,
+  // Leaves the sides at a right angle, a quarter circle between the middles of adjacent sides
+  Curved,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TruchetPath {
+  pub from: Connector,
+  pub to: Connector,
+  pub shape: PathShape,
+}
+
+// Tile design made of paths between connectors, drawn in the unit square
+#[derive(Clone, Debug)]
+pub struct TruchetTile {
+  paths: Vec<TruchetPath>,
+  weight: f32,
+}
+
+impl Default for TruchetTile {
+  fn default() -> Self {
+    Self {
+      paths: vec![],
+      weight: 1.0,
+    }
+  }
+}
+
+impl TruchetTile {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn line(self, from: Connector, to: Connector) -> Self {
+    self.path(from, to, PathShape::Straight)
+  }
+
+  pub fn curve(self, from: Connector, to: Connector) -> Self {
+    self.path(from, to, PathShape::Curved)
+  }
+
+  pub fn path(mut self, from: Connector, to: Connector, shape: PathShape) -> Self {
+    self.paths.push(TruchetPath { from, to, shape });
+    self
+  }
+
+  // Relative frequency of the tile, shared between its variants
+  pub fn weight(mut self, weight: f32) -> Self {
+    self.weight = weight.max(0.0);
+    self
+  }
+
+  pub fn paths(&self) -> &[TruchetPath] {
+    &self.paths
+  }
+
+  // Distinct connectors used by the paths, counter-clockwise from the bottom left corner
+  pub fn connectors(&self) -> Vec<Connector> {
+    let mut connectors = self
+      .paths
+      .iter()
+      .flat_map(|path| [path.from, path.to])
+      .collect::<Vec<_>>();
+    connectors.sort_by_key(|connector| connector.key());
+    connectors.dedup_by_key(|connector| connector.key());
+    connectors
+  }
+
+  fn transformed(&self, transform: impl Fn(Connector) -> Connector) -> Self {
+    Self {
+      paths: self
+        .paths
+        .iter()
+        .map(|path| TruchetPath {
+          from: transform(path.from),
+          to: transform(path.to),
+          shape: path.shape,
+        })
+        .collect(),
+      weight: self.weight,
+    }
+  }
+
+  // Paths regardless of their order and direction, to recognise identical variants
+  fn signature(&self) -> Vec<(ConnectorKey, ConnectorKey, PathShape)> {
+    let mut signature = self
+      .paths
+      .iter()
+      .map(|path| {
+        let (from, to) = (path.from.key(), path.to.key());
+        (from.min(to), from.max(to), path.shape)
+      })
+      .collect::<Vec<_>>();
+    signature.sort_by_key(|(from, to, shape)| (*from, *to, *shape as usize));
+    signature
+  }
+}
+
+#[derive(Clone, Copy)]
+pub enum TileSymmetry {
+  None,
+  Rotations,
+  RotationsAndReflections,
+}
+
+pub struct TileSet {
+  variants: Vec<TruchetTile>,
+}
+
+impl TileSet {
+  // Adds the distinct rotations and reflections of each tile allowed by the symmetry
+  pub fn new(tiles: Vec<TruchetTile>, symmetry: TileSymmetry) -> Self {
+    let variants = tiles
+      .iter()
+      .flat_map(|tile| {
+        let mut transformed = vec![tile.clone()];
+        if let TileSymmetry::RotationsAndReflections = symmetry {
+          transformed.push(tile.transformed(Connector::reflected));
+        }
+        if let TileSymmetry::Rotations | TileSymmetry::RotationsAndReflections = symmetry {
+          transformed = transformed
+            .into_iter()
+            .flat_map(|tile| {
+              (0..4).scan(tile, |tile, _| {
+                let current = tile.clone();
+                *tile = tile.transformed(Connector::rotated);
+                Some(current)
+              })
+            })
+            .collect();
+        }
+        let mut signatures = HashSet::new();
+        transformed.retain(|variant| signatures.insert(variant.signature()));
+        let n_variants = transformed.len() as f32;
+        transformed.into_iter().map(move |variant| TruchetTile {
+          weight: variant.weight / n_variants,
+          ..variant
+        })
+      })
+      .collect();
+    Self { variants }
+  }
+
+  pub fn variants(&self) -> &[TruchetTile] {
+    &self.variants
+  }
+
+  // Quarter circles joining the middles of adjacent sides
+  pub fn smith() -> Self {
+    let tile = TruchetTile::new()
+      .curve(
+        Connector::middle(Side::Bottom),
+        Connector::middle(Side::Left),
+      )
+      .curve(Connector::middle(Side::Top), Connector::middle(Side::Right));
+    Self::new(vec![tile], TileSymmetry::Rotations)
+  }
+
+  // Smith tiles and crossing straight pipes
+  pub fn pipes() -> Self {
+    let cross = TruchetTile::new()
+      .line(
+        Connector::middle(Side::Bottom),
+        Connector::middle(Side::Top),
+      )
+      .line(
+        Connector::middle(Side::Left),
+        Connector::middle(Side::Right),
+      );
+    let mut tile_set = Self::smith();
+    tile_set.variants.push(cross);
+    tile_set
+  }
+
+  // Every way of joining the connectors placed at the given positions of each side without
+  // crossing paths, connectors being left out in any even number. With positions at 1/3 and 2/3,
+  // the connectors of a tile line up with those of tiles half its size: the paths stay continuous
+  // across scales as long as neighbours are at most twice as large as each other.
+  pub fn complete(positions: &[f32], shape: PathShape) -> Self {
+    let connectors = Side::ALL
+      .iter()
+      .flat_map(|side| {
+        let mut positions = positions.to_vec();
+        positions.sort_by(|a, b| a.total_cmp(b));
+        positions.dedup();
+        positions
+          .into_iter()
+          .map(|position| Connector::new(*side, position))
+      })
+      .collect::<Vec<_>>();
+    let mut tiles = (0..1usize << connectors.len())
+      .filter(|subset| subset.count_ones() % 2 == 0)
+      .flat_map(|subset| {
+        let used = connectors
+          .iter()
+          .enumerate()
+          .filter(|(i, _)| subset & (1 << i) != 0)
+          .map(|(_, connector)| *connector)
+          .collect::<Vec<_>>();
+        non_crossing_pairings(&used)
+      })
+      .map(|pairs| {
+        pairs
+          .into_iter()
+          .fold(TruchetTile::new(), |tile, (from, to)| {
+            tile.path(from, to, shape)
+          })
+      })
+      .collect::<Vec<_>>();
+    // Positions closer than the precision of the connector keys lead to the same tiles
+    let mut signatures = HashSet::new();
+    tiles.retain(|tile| signatures.insert(tile.signature()));
+    Self::new(tiles, TileSymmetry::None)
+  }
+}
+
+pub struct TruchetOptions {
+  // Pick the variants whose connectors meet those of the already placed neighbours, whenever the
+  // tile set allows it
+  pub match_connectors: bool,
+  // Maximum distance between the curved paths and their sampling
+  pub tolerance: f32,
+}
+
+impl Default for TruchetOptions {
+  fn default() -> Self {
+    Self {
+      match_connectors: true,
+      tolerance: 0.5,
+    }
+  }
+}
+
+pub struct PlacedTile {
+  // Index of the leaf in the space
+  pub index: usize,
+  pub rect: Rect<f32>,
+  // Index in the tile set variants
+  pub variant: usize,
+  pub lines: MultiLineString<f32>,
+  connectors: Vec<Coord<f32>>,
+}
+
+pub struct Truchet {
+  pub tiles: Vec<PlacedTile>,
+}
+
+impl Truchet {
+  // Paths of all the tiles, joined at their connectors into continuous lines
+  pub fn lines(&self) -> MultiLineString<f32> {
+    join(
+      self
+        .tiles
+        .iter()
+        .flat_map(|tile| tile.lines.0.iter().cloned())
+        .collect(),
+    )
+  }
+
+  // Connectors not joined to the path of a neighbour, the space border excluded
+  pub fn n_mismatches(&self) -> usize {
+    let space_rect = self
+      .tiles
+      .iter()
+      .fold(None, |rect: Option<Rect<f32>>, tile| {
+        Some(match rect {
+          Some(rect) => Rect::new(
+            (
+              rect.min().x.min(tile.rect.min().x),
+              rect.min().y.min(tile.rect.min().y),
+            ),
+            (
+              rect.max().x.max(tile.rect.max().x),
+              rect.max().y.max(tile.rect.max().y),
+            ),
+          ),
+          None => tile.rect,
+        })
+      });
+    let space_rect = match space_rect {
+      Some(space_rect) => space_rect,
+      None => return 0,
+    };
+    let on_border = |coord: &Coord<f32>| {
+      (coord.x - space_rect.min().x).abs() <= CONNECTOR_TOLERANCE
+        || (coord.x - space_rect.max().x).abs() <= CONNECTOR_TOLERANCE
+        || (coord.y - space_rect.min().y).abs() <= CONNECTOR_TOLERANCE
+        || (coord.y - space_rect.max().y).abs() <= CONNECTOR_TOLERANCE
+    };
+    let mut counts = HashMap::new();
+    self
+      .tiles
+      .iter()
+      .flat_map(|tile| tile.connectors.iter())
+      .filter(|coord| !on_border(coord))
+      .for_each(|coord| *counts.entry(coord_key(*coord)).or_insert(0) += 1);
+    counts.values().filter(|count| **count < 2).count()
+  }
+}
+
+// Places a variant of the tile set on each leaf of the space. Leaves of different sizes give
+// multi-scale Truchet patterns, e.g. with a QuadSplit space.
+pub fn compute_truchet<T: SpaceTile>(
+  space: &Space<T>,
+  tile_set: &TileSet,
+  options: &TruchetOptions,
+  rng: &mut StdRng,
+) -> Truchet {
+  let mut cells = space
+    .leafs()
+    .into_iter()
+    .map(|index| {
+      let tile = space.get_node(index).unwrap().content();
+      (index, Rect::new(tile.min(), tile.max()))
+    })
+    .collect::<Vec<_>>();
+  // The top side of each tile is free when the tile is placed, which keeps complete tile sets
+  // from running out of matching variants
+  cells.sort_by(|(_, a), (_, b)| {
+    a.min()
+      .y
+      .total_cmp(&b.min().y)
+      .then(a.min().x.total_cmp(&b.min().x))
+  });
+  let adjacency = options.match_connectors.then(|| compute_adjacency(space));
+  let rects = cells.iter().copied().collect::<HashMap<_, _>>();
+  // Every connector a tile may have, to keep the placed tiles from facing connectors the
+  // neighbours can not meet
+  let mut possible = tile_set
+    .variants
+    .iter()
+    .flat_map(|variant| variant.connectors())
+    .collect::<Vec<_>>();
+  possible.sort_by_key(|connector| connector.key());
+  possible.dedup_by_key(|connector| connector.key());
+
+  let mut placed: HashMap<usize, usize> = HashMap::new();
+  let mut tiles: Vec<PlacedTile> = vec![];
+  cells.into_iter().for_each(|(index, rect)| {
+    let constraints = match &adjacency {
+      Some(adjacency) => adjacency
+        .neighbours(index)
+        .iter()
+        .flat_map(|neighbour| {
+          let connectors = match placed.get(neighbour) {
+            Some(tile) => tiles[*tile].connectors.clone(),
+            None => possible
+              .iter()
+              .map(|connector| to_world(&rects[neighbour], connector.coord()))
+              .collect(),
+          };
+          let placed = placed.contains_key(neighbour);
+          adjacency
+            .shared_edges(index, *neighbour)
+            .map(move |line| Constraint {
+              line: *line,
+              connectors: connectors
+                .iter()
+                .filter(|coord| is_on(line, coord))
+                .copied()
+                .collect(),
+              placed,
+            })
+            .collect::<Vec<_>>()
+        })
+        .collect(),
+      None => vec![],
+    };
+    let scored = tile_set
+      .variants
+      .iter()
+      .map(|variant| {
+        let connectors = variant
+          .connectors()
+          .iter()
+          .map(|connector| to_world(&rect, connector.coord()))
+          .collect::<Vec<_>>();
+        snap(connectors, &constraints)
+      })
+      .collect::<Vec<_>>();
+    let min_mismatches = scored.iter().map(|(_, mismatches)| *mismatches).min();
+    let candidates = (0..scored.len())
+      .filter(|i| Some(scored[*i].1) == min_mismatches)
+      .collect::<Vec<_>>();
+    if candidates.is_empty() {
+      return;
+    }
+    let variant = pick(&candidates, &tile_set.variants, rng);
+    let connectors = scored[variant].0.clone();
+    let lines = tile_lines(
+      &tile_set.variants[variant],
+      &rect,
+      &connectors,
+      options.tolerance,
+    );
+    placed.insert(index, tiles.len());
+    tiles.push(PlacedTile {
+      index,
+      rect,
+      variant,
+      lines,
+      connectors,
+    });
+  });
+  Truchet { tiles }
+}
+
+// Every way of pairing the connectors, given in order around the tile, without crossing paths
+fn non_crossing_pairings(connectors: &[Connector]) -> Vec<Vec<(Connector, Connector)>> {
+  let (first, rest) = match connectors.split_first() {
+    Some(split) => split,
+    None => return vec![vec![]],
+  };
+  // The first connector is joined to one leaving an even number of connectors on each side
+  (0..rest.len())
+    .step_by(2)
+    .flat_map(|i| {
+      let inside = non_crossing_pairings(&rest[..i]);
+      let outside = non_crossing_pairings(&rest[i + 1..]);
+      inside
+        .iter()
+        .flat_map(|inside| {
+          outside.iter().map(move |outside| {
+            std::iter::once((*first, rest[i]))
+              .chain(inside.iter().copied())
+              .chain(outside.iter().copied())
+              .collect::<Vec<_>>()
+          })
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect()
+}
+
+// Edge shared with a neighbour and the connectors it has along the edge, or may have when it is
+// not placed yet
+struct Constraint {
+  line: Line<f32>,
+  connectors: Vec<Coord<f32>>,
+  placed: bool,
+}
+
+// Moves the connectors onto the matching connectors of the neighbours, counting those left alone
+// on either side of the shared edges
+fn snap(mut connectors: Vec<Coord<f32>>, constraints: &[Constraint]) -> (Vec<Coord<f32>>, usize) {
+  let mut mismatches = 0;
+  constraints.iter().for_each(|constraint| {
+    let mut matched = vec![false; constraint.connectors.len()];
+    connectors
+      .iter_mut()
+      .filter(|coord| is_on(&constraint.line, coord))
+      .for_each(|coord| {
+        let found = constraint.connectors.iter().enumerate().find(|(i, other)| {
+          !matched[*i] && coord.euclidean_distance(*other) <= CONNECTOR_TOLERANCE
+        });
+        match found {
+          Some((i, other)) => {
+            matched[i] = true;
+            if constraint.placed {
+              *coord = *other;
+            }
+          }
+          None => mismatches += 1,
+        }
+      });
+    if constraint.placed {
+      mismatches += matched.iter().filter(|matched| !**matched).count();
+    }
+  });
+  (connectors, mismatches)
+}
+
+fn pick(candidates: &[usize], variants: &[TruchetTile], rng: &mut StdRng) -> usize {
+  let total = candidates.iter().map(|i| variants[*i].weight).sum::<f32>();
+  if total <= 0.0 {
+    return candidates[rng.gen_range(0..candidates.len())];
+  }
+  let mut target = rng.gen_range(0.0..total);
+  candidates
+    .iter()
+    .copied()
+    .find(|i| {
+      target -= variants[*i].weight;
+      target < 0.0
+    })
+    .unwrap_or(candidates[candidates.len() - 1])
+}
+
+fn tile_lines(
+  tile: &TruchetTile,
+  rect: &Rect<f32>,
+  connectors: &[Coord<f32>],
+  tolerance: f32,
+) -> MultiLineString<f32> {
+  let keys = tile
+    .connectors()
+    .iter()
+    .map(|connector| connector.key())
+    .collect::<Vec<_>>();
+  let world = |connector: &Connector| {
+    let position = keys.iter().position(|key| *key == connector.key()).unwrap();
+    connectors[position]
+  };
+  let lines = tile
+    .paths
+    .iter()
+    .map(|path| {
+      let (start, end) = (world(&path.from), world(&path.to));
+      match path.shape {
+        PathShape::Straight => LineString::new(vec![start, end]),
+        PathShape::Curved => {
+          let (from, to) = (path.from.coord(), path.to.coord());
+          let distance = KAPPA * from.euclidean_distance(&to) / SQRT_2;
+          let control_start = to_world(rect, from + path.from.side.inward() * distance);
+          let control_end = to_world(rect, to + path.to.side.inward() * distance);
+          let curve = CubicBezier::new(start, control_start, control_end, end);
+          let mut line = sample_adaptive(&curve, tolerance);
+          // Keeps the ends exactly on the connectors so that the lines can be joined
+          if let Some(first) = line.0.first_mut() {
+            *first = start;
+          }
+          if let Some(last) = line.0.last_mut() {
+            *last = end;
+          }
+          line
+        }
+      }
+    })
+    .collect();
+  MultiLineString::new(lines)
+}
+
+// Chains the lines sharing an end, open chains first then loops
+fn join(lines: Vec<LineString<f32>>) -> MultiLineString<f32> {
+  let mut ends: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+  lines.iter().enumerate().for_each(|(i, line)| {
+    if let (Some(first), Some(last)) = (line.0.first(), line.0.last()) {
+      ends.entry(coord_key(*first)).or_default().push(i);
+      ends.entry(coord_key(*last)).or_default().push(i);
+    }
+  });
+  let degree = |coord: Option<&Coord<f32>>| {
+    coord
+      .and_then(|coord| ends.get(&coord_key(*coord)))
+      .map(|lines| lines.len())
+      .unwrap_or(0)
+  };
+
+  let mut visited = vec![false; lines.len()];
+  let mut joined = vec![];
+  let open = (0..lines.len())
+    .filter(|i| degree(lines[*i].0.first()) != 2 || degree(lines[*i].0.last()) != 2);
+  let starts = open.chain(0..lines.len()).collect::<Vec<_>>();
+  starts.into_iter().for_each(|start| {
+    if visited[start] || lines[start].0.is_empty() {
+      return;
+    }
+    visited[start] = true;
+    let mut coords = lines[start].0.clone();
+    // Open chains start from their free end
+    if degree(coords.last()) != 2 && degree(coords.first()) == 2 {
+      coords.reverse();
+    }
+    loop {
+      let last = coord_key(*coords.last().unwrap());
+      let next = match ends[&last].iter().copied().find(|i| !visited[*i]) {
+        Some(next) => next,
+        None => break,
+      };
+      visited[next] = true;
+      let mut next_coords = lines[next].0.clone();
+      if coord_key(next_coords[0]) != last {
+        next_coords.reverse();
+      }
+      coords.extend(next_coords.into_iter().skip(1));
+    }
+    joined.push(LineString::new(coords));
+  });
+  MultiLineString::new(joined)
+}
+
+fn to_world(rect: &Rect<f32>, coord: Coord<f32>) -> Coord<f32> {
+  rect.min() + Coord::from((coord.x * rect.width(), coord.y * rect.height()))
+}
+
+fn is_on(line: &Line<f32>, coord: &Coord<f32>) -> bool {
+  line.euclidean_distance(coord) <= CONNECTOR_TOLERANCE
+}
+
+fn coord_key(coord: Coord<f32>) -> (u32, u32) {
+  (coord.x.to_bits(), coord.y.to_bits())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::algorithm::space::{compute_space, QuadSplit, SpaceOptions};
+  use rand::SeedableRng;
+
+  struct Tile {
+    min: Coord<f32>,
+    max: Coord<f32>,
+  }
+
+  impl SpaceTile for Tile {
+    fn new(min: Coord<f32>, max: Coord<f32>) -> Self {
+      Self { min, max }
+    }
+    fn width(&self) -> f32 {
+      self.max.x - self.min.x
+    }
+    fn height(&self) -> f32 {
+      self.max.y - self.min.y
+    }
+    fn min(&self) -> Coord<f32> {
+      self.min
+    }
+    fn max(&self) -> Coord<f32> {
+      self.max
+    }
+  }
+
+  const THIRDS: [f32; 2] = [1.0 / 3.0, 2.0 / 3.0];
+
+  #[test]
+  fn complete_sets_have_every_non_crossing_pairing() {
+    // Sum over the even subsets of 2k connectors of the k-th Catalan number
+    let tile_set = TileSet::complete(&[0.5], PathShape::Curved);
+    assert_eq!(tile_set.variants().len(), 1 + 6 + 2);
+    let tile_set = TileSet::complete(&THIRDS, PathShape::Curved);
+    assert_eq!(tile_set.variants().len(), 1 + 28 + 70 * 2 + 28 * 5 + 14);
+  }
+
+  #[test]
+  fn complete_sets_have_distinct_variants() {
+    let tile_set = TileSet::complete(&THIRDS, PathShape::Straight);
+    let signatures = tile_set
+      .variants()
+      .iter()
+      .map(|variant| variant.signature())
+      .collect::<HashSet<_>>();
+    assert_eq!(signatures.len(), tile_set.variants().len());
+    // Positions given twice do not add variants
+    let tile_set = TileSet::complete(&[0.5, 0.5], PathShape::Straight);
+    assert_eq!(tile_set.variants().len(), 9);
+  }
+
+  #[test]
+  fn complete_sets_have_no_crossing_paths() {
+    let tile_set = TileSet::complete(&THIRDS, PathShape::Curved);
+    tile_set.variants().iter().for_each(|variant| {
+      let signature = variant.signature();
+      signature.iter().for_each(|(a_from, a_to, _)| {
+        signature.iter().for_each(|(b_from, b_to, _)| {
+          let inside = |key| a_from < key && key < a_to;
+          assert_eq!(inside(b_from), inside(b_to));
+        });
+      });
+    });
+  }
+
+  #[test]
+  fn tiles_of_different_sizes_line_up() {
+    // The root and one of its quarters are split, neighbours being at most twice as large
+    let options = SpaceOptions {
+      max_leaves: 7,
+      ..Default::default()
+    };
+    let mut rng = StdRng::seed_from_u64(0);
+    let space = compute_space(
+      Tile::new(Coord::zero(), Coord::from((120.0, 120.0))),
+      &options,
+      &QuadSplit { jitter: 0.0 },
+      &mut rng,
+    );
+    let sizes = space
+      .leafs()
+      .into_iter()
+      .map(|index| space.get_node(index).unwrap().content().width() as u32)
+      .collect::<HashSet<_>>();
+    assert_eq!(sizes, HashSet::from([30, 60]));
+    let tile_set = TileSet::complete(&THIRDS, PathShape::Curved);
+    (0..10).for_each(|_| {
+      let truchet = compute_truchet(&space, &tile_set, &TruchetOptions::default(), &mut rng);
+      assert_eq!(truchet.tiles.len(), 7);
+      assert_eq!(truchet.n_mismatches(), 0);
+    });
+  }
+}
@@ -0,0 +1,415 @@
+use rand::{rngs::StdRng, Rng};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::geometry::grid::{CellCoord, Grid};
+
+// Tile described by the sockets of its edges, in the order of the grid edges. Sockets are read
+// counter-clockwise around the tile and two edges fit when one socket is the other one reversed:
+// symmetric sockets such as "0" or "aba" fit themselves, "ab" only fits "ba".
+#[derive(Clone)]
+pub struct WfcTile {
+  sockets: Vec<String>,
+  weight: f32,
+  rotations: bool,
+  reflections: bool,
+}
+
+impl WfcTile {
+  pub fn new(sockets: &[&str]) -> Self {
+    Self {
+      sockets: sockets.iter().map(|socket| socket.to_string()).collect(),
+      weight: 1.0,
+      rotations: false,
+      reflections: false,
+    }
+  }
+
+  // Relative frequency of the tile, shared between its variants
+  pub fn weight(mut self, weight: f32) -> Self {
+    self.weight = weight.max(0.0);
+    self
+  }
+
+  // Adds the rotations of the tile to the tile set
+  pub fn rotations(mut self, rotations: bool) -> Self {
+    self.rotations = rotations;
+    self
+  }
+
+  // Adds the mirrored tile, and its rotations when they are enabled
+  pub fn reflections(mut self, reflections: bool) -> Self {
+    self.reflections = reflections;
+    self
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct WfcVariant {
+  // Index of the tile in the tile set
+  pub tile: usize,
+  // Number of edges the tile is turned by, counter-clockwise
+  pub rotation: usize,
+  // The mirrored tile has its edges in reverse order, edge i taking the place of edge n - 1 - i
+  pub reflected: bool,
+  pub sockets: Vec<String>,
+  weight: f32,
+}
+
+pub struct WfcTileSet {
+  variants: Vec<WfcVariant>,
+}
+
+impl WfcTileSet {
+  // Variants of a tile giving the same sockets are merged
+  pub fn new(tiles: &[WfcTile]) -> Self {
+    let variants = tiles
+      .iter()
+      .enumerate()
+      .flat_map(|(index, tile)| {
+        let n_sides = tile.sockets.len();
+        let reflections: &[bool] = match tile.reflections {
+          true => &[false, true],
+          false => &[false],
+        };
+        let n_rotations = match tile.rotations {
+          true => n_sides.max(1),
+          false => 1,
+        };
+        let mut seen = HashSet::new();
+        let variants = reflections
+          .iter()
+          .flat_map(|reflected| (0..n_rotations).map(move |rotation| (rotation, *reflected)))
+          .map(|(rotation, reflected)| {
+            let sockets = (0..n_sides)
+              .map(|i| {
+                let source = (i + n_sides - rotation) % n_sides;
+                match reflected {
+                  true => tile.sockets[n_sides - 1 - source].chars().rev().collect(),
+                  false => tile.sockets[source].clone(),
+                }
+              })
+              .collect::<Vec<String>>();
+            WfcVariant {
+              tile: index,
+              rotation,
+              reflected,
+              sockets,
+              weight: tile.weight,
+            }
+          })
+          .filter(|variant| seen.insert(variant.sockets.clone()))
+          .collect::<Vec<_>>();
+        let n_variants = variants.len() as f32;
+        variants.into_iter().map(move |variant| WfcVariant {
+          weight: variant.weight / n_variants,
+          ..variant
+        })
+      })
+      .collect();
+    Self { variants }
+  }
+
+  pub fn variants(&self) -> &[WfcVariant] {
+    &self.variants
+  }
+
+  // Variant of the tile with the given rotation, e.g. to pre-place a tile
+  pub fn find(&self, tile: usize, rotation: usize, reflected: bool) -> Option<usize> {
+    self.variants.iter().position(|variant| {
+      variant.tile == tile && variant.rotation == rotation && variant.reflected == reflected
+    })
+  }
+}
+
+pub struct WfcOptions {
+  // Cells set before solving, with the index of their variant in the tile set
+  pub fixed: Vec<(CellCoord, usize)>,
+  // Socket the edges on the grid border must fit, any socket when None
+  pub border: Option<String>,
+  // Number of choices which may be undone after reaching a contradiction before giving up
+  pub max_backtracks: usize,
+}
+
+impl Default for WfcOptions {
+  fn default() -> Self {
+    Self {
+      fixed: vec![],
+      border: None,
+      max_backtracks: 1000,
+    }
+  }
+}
+
+// Fills the grid with variants of the tile set whose sockets fit those of their neighbours, by Wave
+// Function Collapse: the cell with the fewest possible variants is set first, and the choices
+// leading to a cell without any possible variant are undone. Returns None when the grid can not be
+// filled within options.max_backtracks.
+pub fn collapse(
+  grid: &Grid,
+  tile_set: &WfcTileSet,
+  options: &WfcOptions,
+  rng: &mut StdRng,
+) -> Option<HashMap<CellCoord, usize>> {
+  let solver = Solver::new(grid, tile_set);
+  let mut domains = solver.initial_domains(options.border.as_deref());
+
+  let mut queue = (0..solver.cells.len()).collect::<VecDeque<_>>();
+  for (cell, variant) in options.fixed.iter() {
+    let position = *solver.positions.get(cell)?;
+    if *variant >= solver.n_variants || !has(solver.domain(&domains, position), *variant) {
+      return None;
+    }
+    solver.set(&mut domains, position, *variant);
+  }
+  if !solver.propagate(&mut domains, &mut queue) {
+    return None;
+  }
+
+  // Saved domains before each choice, with the chosen cell and variant
+  let mut choices: Vec<(Vec<u64>, usize, usize)> = vec![];
+  let mut n_backtracks = 0;
+  while let Some(position) = solver.lowest_entropy(&domains, rng) {
+    let variant = solver.pick(solver.domain(&domains, position), rng);
+    choices.push((domains.clone(), position, variant));
+    solver.set(&mut domains, position, variant);
+    let mut consistent = solver.propagate(&mut domains, &mut VecDeque::from([position]));
+    while !consistent {
+      n_backtracks += 1;
+      if n_backtracks > options.max_backtracks {
+        return None;
+      }
+      let (saved, position, variant) = choices.pop()?;
+      domains = saved;
+      remove(solver.domain_mut(&mut domains, position), variant);
+      consistent = !is_empty(solver.domain(&domains, position))
+        && solver.propagate(&mut domains, &mut VecDeque::from([position]));
+    }
+  }
+
+  solver
+    .cells
+    .iter()
+    .enumerate()
+    .map(|(position, cell)| {
+      first(solver.domain(&domains, position)).map(|variant| (*cell, variant))
+    })
+    .collect()
+}
+
+// Domains of all the cells are stored one after the other, words bits sets each
+struct Solver<'a> {
+  tile_set: &'a WfcTileSet,
+  cells: Vec<CellCoord>,
+  positions: HashMap<CellCoord, usize>,
+  // Neighbour position and its edge shared with the cell, for each edge of each cell
+  neighbours: Vec<Vec<Option<(usize, usize)>>>,
+  n_sides: usize,
+  n_variants: usize,
+  words: usize,
+  // Variants fitting each variant, edge of the variant and edge of the neighbour
+  fits: Vec<Vec<u64>>,
+}
+
+impl<'a> Solver<'a> {
+  fn new(grid: &Grid, tile_set: &'a WfcTileSet) -> Self {
+    let cells = grid.cells().to_vec();
+    let positions = cells
+      .iter()
+      .enumerate()
+      .map(|(position, cell)| (*cell, position))
+      .collect::<HashMap<_, _>>();
+    let n_sides = grid.n_sides();
+    let neighbours = cells
+      .iter()
+      .map(|cell| {
+        (0..n_sides)
+          .map(|edge| {
+            grid
+              .neighbour(*cell, edge)
+              .map(|neighbour| (positions[&neighbour], grid.opposite_edge(*cell, edge)))
+          })
+          .collect()
+      })
+      .collect();
+
+    let n_variants = tile_set.variants.len();
+    let words = n_variants.div_ceil(64);
+    let socket = |variant: usize, edge: usize| {
+      tile_set.variants[variant]
+        .sockets
+        .get(edge)
+        .map(|socket| socket.as_str())
+        .unwrap_or_default()
+    };
+    let mut fits = vec![vec![0; words]; n_variants * n_sides * n_sides];
+    (0..n_variants).for_each(|variant| {
+      (0..n_sides).for_each(|edge| {
+        (0..n_sides).for_each(|other_edge| {
+          let domain = &mut fits[(variant * n_sides + edge) * n_sides + other_edge];
+          (0..n_variants)
+            .filter(|other| fit(socket(variant, edge), socket(*other, other_edge)))
+            .for_each(|other| insert(domain, other));
+        });
+      });
+    });
+
+    Self {
+      tile_set,
+      cells,
+      positions,
+      neighbours,
+      n_sides,
+      n_variants,
+      words,
+      fits,
+    }
+  }
+
+  fn domain<'d>(&self, domains: &'d [u64], position: usize) -> &'d [u64] {
+    &domains[position * self.words..(position + 1) * self.words]
+  }
+
+  fn domain_mut<'d>(&self, domains: &'d mut [u64], position: usize) -> &'d mut [u64] {
+    &mut domains[position * self.words..(position + 1) * self.words]
+  }
+
+  fn set(&self, domains: &mut [u64], position: usize, variant: usize) {
+    let domain = self.domain_mut(domains, position);
+    domain.iter_mut().for_each(|word| *word = 0);
+    insert(domain, variant);
+  }
+
+  fn initial_domains(&self, border: Option<&str>) -> Vec<u64> {
+    let mut full = vec![0; self.words];
+    (0..self.n_variants).for_each(|variant| insert(&mut full, variant));
+    self
+      .neighbours
+      .iter()
+      .flat_map(|edges| {
+        let mut domain = full.clone();
+        if let Some(border) = border {
+          edges
+            .iter()
+            .enumerate()
+            .filter(|(_, neighbour)| neighbour.is_none())
+            .for_each(|(edge, _)| {
+              (0..self.n_variants)
+                .filter(|variant| {
+                  let socket = self.tile_set.variants[*variant].sockets.get(edge);
+                  !fit(socket.map(|s| s.as_str()).unwrap_or_default(), border)
+                })
+                .for_each(|variant| remove(&mut domain, variant));
+            });
+        }
+        domain
+      })
+      .collect()
+  }
+
+  // Removes the variants of the neighbours which do not fit any variant left in the queued cells,
+  // until nothing changes. Returns false when a cell has no variant left.
+  fn propagate(&self, domains: &mut [u64], queue: &mut VecDeque<usize>) -> bool {
+    if (0..self.cells.len()).any(|position| is_empty(self.domain(domains, position))) {
+      return false;
+    }
+    let mut allowed = vec![0; self.words];
+    while let Some(position) = queue.pop_front() {
+      for (edge, neighbour) in self.neighbours[position].iter().enumerate() {
+        let (neighbour, other_edge) = match neighbour {
+          Some(neighbour) => *neighbour,
+          None => continue,
+        };
+        allowed.iter_mut().for_each(|word| *word = 0);
+        iter(self.domain(domains, position)).for_each(|variant| {
+          let fits = &self.fits[(variant * self.n_sides + edge) * self.n_sides + other_edge];
+          allowed.iter_mut().zip(fits).for_each(|(a, f)| *a |= f);
+        });
+        let domain = self.domain_mut(domains, neighbour);
+        let mut changed = false;
+        domain.iter_mut().zip(&allowed).for_each(|(d, a)| {
+          changed |= *d & !a != 0;
+          *d &= a;
+        });
+        if is_empty(domain) {
+          return false;
+        }
+        if changed {
+          queue.push_back(neighbour);
+        }
+      }
+    }
+    true
+  }
+
+  // Undecided cell with the lowest Shannon entropy of its variant weights, ties broken randomly
+  fn lowest_entropy(&self, domains: &[u64], rng: &mut StdRng) -> Option<usize> {
+    (0..self.cells.len())
+      .filter(|position| count(self.domain(domains, *position)) > 1)
+      .map(|position| {
+        let weights = || {
+          iter(self.domain(domains, position))
+            .map(|variant| self.tile_set.variants[variant].weight.max(f32::EPSILON))
+        };
+        let total = weights().sum::<f32>();
+        let entropy =
+          total.ln() - weights().map(|weight| weight * weight.ln()).sum::<f32>() / total;
+        (position, entropy + rng.gen_range(0.0..1e-3))
+      })
+      .min_by(|(_, a), (_, b)| a.total_cmp(b))
+      .map(|(position, _)| position)
+  }
+
+  fn pick(&self, domain: &[u64], rng: &mut StdRng) -> usize {
+    let variants = iter(domain).collect::<Vec<_>>();
+    let weight = |variant: usize| self.tile_set.variants[variant].weight;
+    let total = variants.iter().map(|variant| weight(*variant)).sum::<f32>();
+    if total <= 0.0 {
+      return variants[rng.gen_range(0..variants.len())];
+    }
+    let mut target = rng.gen_range(0.0..total);
+    variants
+      .iter()
+      .copied()
+      .find(|variant| {
+        target -= weight(*variant);
+        target < 0.0
+      })
+      .unwrap_or(variants[variants.len() - 1])
+  }
+}
+
+fn fit(socket: &str, other: &str) -> bool {
+  socket.chars().eq(other.chars().rev())
+}
+
+fn has(domain: &[u64], variant: usize) -> bool {
+  domain[variant / 64] & (1 << (variant % 64)) != 0
+}
+
+fn insert(domain: &mut [u64], variant: usize) {
+  domain[variant / 64] |= 1 << (variant % 64);
+}
+
+fn remove(domain: &mut [u64], variant: usize) {
+  domain[variant / 64] &= !(1 << (variant % 64));
+}
+
+fn count(domain: &[u64]) -> usize {
+  domain.iter().map(|word| word.count_ones() as usize).sum()
+}
+
+fn is_empty(domain: &[u64]) -> bool {
+  domain.iter().all(|word| *word == 0)
+}
+
+fn first(domain: &[u64]) -> Option<usize> {
+  iter(domain).next()
+}
+
+fn iter(domain: &[u64]) -> impl Iterator<Item = usize> + '_ {
+  domain.iter().enumerate().flat_map(|(i, word)| {
+    (0..64)
+      .filter(move |bit| word & (1 << bit) != 0)
+      .map(move |bit| i * 64 + bit)
+  })
+}
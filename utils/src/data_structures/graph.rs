@@ -1,12 +1,16 @@
-use std::collections::VecDeque;
+use std::{cell::OnceCell, collections::VecDeque};
 
 type NodeIndex = usize;
 type EdgeIndex = usize;
 
+// Tree-like graph rooted at the first node.
+// Removed nodes leave a hole so that the other indices stay valid.
 pub struct Graph<T> {
-  nodes: Vec<Node<T>>,
+  nodes: Vec<Option<Node<T>>>,
   edges: Vec<Edge>,
-  bfs_traversal: Option<Vec<NodeIndex>>,
+  // Number of nodes not removed, kept up to date to avoid scanning the holes
+  n_nodes: usize,
+  bfs_traversal: OnceCell<Vec<NodeIndex>>,
 }
 
 pub struct Node<T> {
@@ -21,11 +25,17 @@ impl<T> Node<T> {
   pub fn content(&self) -> &T {
     &self.content
   }
+
+  pub fn content_mut(&mut self) -> &mut T {
+    &mut self.content
+  }
 }
 
 #[derive(Default)]
 pub struct Edge {
   next_edges: Vec<EdgeIndex>,
+  parent: Option<NodeIndex>,
+  depth: usize,
 }
 
 impl<T> Default for Graph<T> {
@@ -33,71 +43,209 @@ impl<T> Default for Graph<T> {
     Self {
       nodes: Vec::new(),
       edges: Vec::new(),
-      bfs_traversal: None,
+      n_nodes: 0,
+      bfs_traversal: OnceCell::new(),
     }
   }
 }
 impl<T> Graph<T> {
+  // Number of nodes, removed ones excluded
   pub fn len(&self) -> usize {
-    self.nodes.len()
+    self.n_nodes
   }
 
   pub fn is_empty(&self) -> bool {
     self.len() == 0
   }
 
+  pub fn contains(&self, index: NodeIndex) -> bool {
+    matches!(self.nodes.get(index), Some(Some(_)))
+  }
+
   pub fn get_node(&self, index: NodeIndex) -> Option<&Node<T>> {
-    self.nodes.get(index)
+    self.nodes.get(index).and_then(|node| node.as_ref())
+  }
+
+  pub fn get_node_mut(&mut self, index: NodeIndex) -> Option<&mut Node<T>> {
+    self.nodes.get_mut(index).and_then(|node| node.as_mut())
   }
 
   pub fn add_node(&mut self, node: Node<T>) -> NodeIndex {
     let index = self.nodes.len();
-    self.nodes.push(node);
+    self.nodes.push(Some(node));
     self.edges.push(Edge::default());
+    self.n_nodes += 1;
+    self.bfs_traversal.take();
     index
   }
 
+  // Makes target a child of source. Edges creating a cycle are ignored.
   pub fn add_edge(&mut self, source: NodeIndex, target: NodeIndex) {
-    if source > self.nodes.len() || target > self.nodes.len() {
+    if !self.contains(source)
+      || !self.contains(target)
+      || source == target
+      || self.ancestors(source).any(|index| index == target)
+    {
       return;
     }
-    let edge = self.edges.get_mut(source).unwrap();
-    edge.next_edges.push(target);
+    // A node has a single parent
+    if let Some(parent) = self.edges[target].parent {
+      self.edges[parent]
+        .next_edges
+        .retain(|next_index| *next_index != target);
+    }
+    self.edges[source].next_edges.push(target);
+    self.edges[target].parent = Some(source);
+    // Update the depths of the whole subtree of target
+    let depth = self.edges[source].depth + 1;
+    let mut stack = vec![(target, depth)];
+    while let Some((index, depth)) = stack.pop() {
+      self.edges[index].depth = depth;
+      stack.extend(
+        self.edges[index]
+          .next_edges
+          .iter()
+          .map(|next_index| (*next_index, depth + 1)),
+      );
+    }
+    self.bfs_traversal.take();
+  }
+
+  // Removes the node and all its descendants, returning the removed indices
+  pub fn remove_subtree(&mut self, index: NodeIndex) -> Vec<NodeIndex> {
+    if !self.contains(index) {
+      return vec![];
+    }
+    if let Some(parent) = self.edges[index].parent {
+      self.edges[parent]
+        .next_edges
+        .retain(|next_index| *next_index != index);
+    }
+    let removed = std::iter::once(index)
+      .chain(self.descendants(index))
+      .collect::<Vec<_>>();
+    removed.iter().for_each(|removed_index| {
+      self.nodes[*removed_index] = None;
+      self.edges[*removed_index] = Edge::default();
+    });
+    self.n_nodes -= removed.len();
+    self.bfs_traversal.take();
+    removed
+  }
+
+  pub fn parent(&self, index: NodeIndex) -> Option<NodeIndex> {
+    self.edges.get(index).and_then(|edge| edge.parent)
   }
 
-  pub fn bfs(&mut self) -> &Vec<NodeIndex> {
-    if self.bfs_traversal.is_none() {
+  pub fn children(&self, index: NodeIndex) -> &[NodeIndex] {
+    self
+      .edges
+      .get(index)
+      .map(|edge| edge.next_edges.as_slice())
+      .unwrap_or_default()
+  }
+
+  // Number of edges between the node and the root
+  pub fn depth(&self, index: NodeIndex) -> Option<usize> {
+    self.contains(index).then(|| self.edges[index].depth)
+  }
+
+  // Parent, grand-parent, ... up to the root
+  pub fn ancestors(&self, index: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+    std::iter::successors(self.parent(index), move |index| self.parent(*index))
+  }
+
+  // Descendants of the node in depth first pre-order, the node excluded
+  pub fn descendants(&self, index: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+    self.pre_order(index).skip(1)
+  }
+
+  pub fn bfs(&self) -> &Vec<NodeIndex> {
+    self.bfs_traversal.get_or_init(|| {
       let mut traversal = Vec::new();
-      if self.nodes.is_empty() {
-        self.bfs_traversal = Some(traversal);
-      } else {
-        let mut queue = VecDeque::new();
-        queue.push_back(0);
-        while let Some(index) = queue.pop_front() {
-          traversal.push(index);
-          let edge = self.edges.get(index).unwrap();
-          for next_index in edge.next_edges.iter() {
-            queue.push_back(*next_index)
-          }
-        }
-        self.bfs_traversal = Some(traversal);
+      if !self.contains(0) {
+        return traversal;
       }
+      let mut queue = VecDeque::new();
+      queue.push_back(0);
+      while let Some(index) = queue.pop_front() {
+        traversal.push(index);
+        queue.extend(self.children(index));
+      }
+      traversal
+    })
+  }
+
+  // Depth first traversal from the root, parents before their children
+  pub fn dfs_pre_order(&self) -> impl Iterator<Item = NodeIndex> + '_ {
+    self.pre_order(0)
+  }
+
+  // Depth first traversal from the root, children before their parents
+  pub fn dfs_post_order(&self) -> impl Iterator<Item = NodeIndex> {
+    let mut traversal = Vec::new();
+    let mut stack = match self.contains(0) {
+      true => vec![0],
+      false => vec![],
+    };
+    while let Some(index) = stack.pop() {
+      traversal.push(index);
+      stack.extend(self.children(index));
     }
-    self.bfs_traversal.as_ref().unwrap()
+    // Reversing a pre-order visiting the last child first gives the post-order
+    traversal.into_iter().rev()
   }
 
-  pub fn leafs(&mut self) -> Vec<NodeIndex> {
+  pub fn leafs(&self) -> Vec<NodeIndex> {
     self
       .bfs()
-      .to_vec()
       .iter()
       .filter(|index| self.is_leaf(**index))
       .copied()
       .collect()
   }
 
-  fn is_leaf(&self, index: NodeIndex) -> bool {
-    let edges = self.edges.get(index).unwrap();
-    edges.next_edges.is_empty()
+  pub fn is_leaf(&self, index: NodeIndex) -> bool {
+    self.children(index).is_empty()
+  }
+
+  fn pre_order(&self, start: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+    let mut stack = match self.contains(start) {
+      true => vec![start],
+      false => vec![],
+    };
+    std::iter::from_fn(move || {
+      let index = stack.pop()?;
+      stack.extend(self.children(index).iter().rev());
+      Some(index)
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn len_counts_the_remaining_nodes() {
+    let mut graph = Graph::default();
+    assert!(graph.is_empty());
+    let indices = (0..6)
+      .map(|i| graph.add_node(Node::new(i)))
+      .collect::<Vec<_>>();
+    // 0 -> 1 -> 2 -> 3, 0 -> 4 -> 5
+    [(0, 1), (1, 2), (2, 3), (0, 4), (4, 5)]
+      .iter()
+      .for_each(|(source, target)| graph.add_edge(indices[*source], indices[*target]));
+    assert_eq!(graph.len(), 6);
+    assert_eq!(graph.remove_subtree(indices[2]).len(), 2);
+    assert_eq!(graph.len(), 4);
+    // Removing again changes nothing
+    assert!(graph.remove_subtree(indices[3]).is_empty());
+    assert_eq!(graph.len(), 4);
+    graph.add_node(Node::new(6));
+    assert_eq!(graph.len(), 5);
+    graph.remove_subtree(indices[0]);
+    assert_eq!(graph.len(), 1);
   }
 }
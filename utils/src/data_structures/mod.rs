@@ -1 +1,3 @@
 pub mod graph;
+pub mod scalar_grid;
+pub mod spatial_grid;
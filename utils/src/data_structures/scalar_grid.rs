@@ -0,0 +1,102 @@
+use geo::{Coord, Rect};
+
+// Values sampled on a regular grid stretched over rect, e.g. noise, simulations or image
+// luminance. Values are stored row by row, the first row being the top of the rect, and each
+// sample sits at the center of its cell like the pixels of an image.
+#[derive(Clone)]
+pub struct ScalarGrid {
+  values: Vec<f32>,
+  width: usize,
+  height: usize,
+  rect: Rect<f32>,
+}
+
+impl ScalarGrid {
+  // Missing values are set to zero
+  pub fn new(mut values: Vec<f32>, width: usize, height: usize, rect: Rect<f32>) -> Self {
+    values.resize(width * height, 0.0);
+    Self {
+      values,
+      width,
+      height,
+      rect,
+    }
+  }
+
+  // Samples the function every spacing over rect
+  pub fn from_fn<F: Fn(Coord<f32>) -> f32>(rect: Rect<f32>, spacing: f32, f: F) -> Self {
+    let spacing = spacing.max(f32::EPSILON);
+    let width = (rect.width() / spacing).round().max(1.0) as usize;
+    let height = (rect.height() / spacing).round().max(1.0) as usize;
+    let mut grid = Self::new(vec![], width, height, rect);
+    grid.values = (0..height)
+      .flat_map(|y| (0..width).map(move |x| (x, y)))
+      .map(|(x, y)| f(grid.coord(x as f32, y as f32)))
+      .collect();
+    grid
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn rect(&self) -> Rect<f32> {
+    self.rect
+  }
+
+  pub fn values(&self) -> &[f32] {
+    &self.values
+  }
+
+  pub fn values_mut(&mut self) -> &mut [f32] {
+    &mut self.values
+  }
+
+  pub fn value(&self, x: usize, y: usize) -> f32 {
+    self.values[y * self.width + x]
+  }
+
+  pub fn set_value(&mut self, x: usize, y: usize, value: f32) {
+    self.values[y * self.width + x] = value;
+  }
+
+  // Position of the sample, fractional indices falling between samples
+  pub fn coord(&self, x: f32, y: f32) -> Coord<f32> {
+    let cell_width = self.rect.width() / self.width.max(1) as f32;
+    let cell_height = self.rect.height() / self.height.max(1) as f32;
+    (
+      self.rect.min().x + (x + 0.5) * cell_width,
+      self.rect.max().y - (y + 0.5) * cell_height,
+    )
+      .into()
+  }
+
+  pub fn min_max(&self) -> (f32, f32) {
+    self
+      .values
+      .iter()
+      .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+        (min.min(*value), max.max(*value))
+      })
+  }
+
+  // Values rescaled between 0.0 and 1.0
+  pub fn normalized(&self) -> Self {
+    let (min, max) = self.min_max();
+    let range = (max - min).max(f32::EPSILON);
+    Self {
+      values: self
+        .values
+        .iter()
+        .map(|value| (value - min) / range)
+        .collect(),
+      width: self.width,
+      height: self.height,
+      rect: self.rect,
+    }
+  }
+}
@@ -0,0 +1,54 @@
+use geo::{Coord, EuclideanDistance};
+use std::collections::HashMap;
+
+type Cell = (i32, i32);
+
+// Buckets values by position on a regular grid to speed up neighbourhood queries
+pub struct SpatialGrid<T> {
+  cell_size: f32,
+  cells: HashMap<Cell, Vec<(Coord<f32>, T)>>,
+}
+
+impl<T> SpatialGrid<T> {
+  pub fn new(cell_size: f32) -> Self {
+    Self {
+      cell_size: cell_size.max(f32::EPSILON),
+      cells: HashMap::new(),
+    }
+  }
+
+  pub fn insert(&mut self, coord: Coord<f32>, value: T) {
+    let cell = self.cell(coord);
+    self.cells.entry(cell).or_default().push((coord, value));
+  }
+
+  pub fn clear(&mut self) {
+    self.cells.clear();
+  }
+
+  pub fn len(&self) -> usize {
+    self.cells.values().map(|values| values.len()).sum()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.cells.values().all(|values| values.is_empty())
+  }
+
+  // All the values located at most radius away from coord
+  pub fn query(&self, coord: Coord<f32>, radius: f32) -> impl Iterator<Item = &(Coord<f32>, T)> {
+    let (min_x, min_y) = self.cell(coord - Coord::from((radius, radius)));
+    let (max_x, max_y) = self.cell(coord + Coord::from((radius, radius)));
+    (min_x..=max_x)
+      .flat_map(move |x| (min_y..=max_y).map(move |y| (x, y)))
+      .filter_map(|cell| self.cells.get(&cell))
+      .flatten()
+      .filter(move |(position, _)| position.euclidean_distance(&coord) <= radius)
+  }
+
+  fn cell(&self, coord: Coord<f32>) -> Cell {
+    (
+      (coord.x / self.cell_size).floor() as i32,
+      (coord.y / self.cell_size).floor() as i32,
+    )
+  }
+}
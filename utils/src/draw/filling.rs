@@ -1,10 +1,10 @@
 use crate::{
   draw,
-  geometry::{self, hatch::hatch},
+  geometry::{self, hatch::hatch, polygon::deform},
 };
 use geo::Polygon;
 use nannou::{
-  prelude::{Hsl, Vec2},
+  prelude::{Hsl, Hsla, Vec2},
   Draw,
 };
 
@@ -93,3 +93,105 @@ pub fn marker(polygon: Polygon<f32>, draw: &Draw, degrees: f32, options: Filling
     )
   })
 }
+
+pub fn charcoal(polygon: Polygon<f32>, draw: &Draw, degrees: f32, options: FillingOptions) {
+  hatch(polygon, options.density, degrees).for_each(|(start, end)| {
+    draw::line::charcoal(
+      start,
+      end,
+      draw,
+      draw::line::LineOptions {
+        weight: options.weight,
+        density: options.density,
+        color: options.color,
+      },
+    )
+  })
+}
+
+pub fn calligraphy(
+  polygon: Polygon<f32>,
+  draw: &Draw,
+  degrees: f32,
+  nib_degrees: f32,
+  options: FillingOptions,
+) {
+  hatch(polygon, options.density, degrees).for_each(|(start, end)| {
+    draw::line::calligraphy(
+      start,
+      end,
+      draw,
+      nib_degrees,
+      draw::line::LineOptions {
+        weight: options.weight,
+        density: options.density,
+        color: options.color,
+      },
+    )
+  })
+}
+
+// Stacks many translucent layers, each one a random deformation of a common base shape
+pub fn watercolour(polygon: Polygon<f32>, draw: &Draw, options: FillingOptions) {
+  const N_LAYERS: usize = 30;
+  const BASE_DEPTH: usize = 2;
+  const LAYER_DEPTH: usize = 3;
+  const DEFORMATION_STD: f32 = 0.08;
+  let mut rng = rand::thread_rng();
+  let color = Hsla::new(
+    options.color.hue,
+    options.color.saturation,
+    options.color.lightness,
+    options.density.clamp(0.0, 1.0) / N_LAYERS as f32 * 2.0,
+  );
+  let base = deform(&polygon, BASE_DEPTH, DEFORMATION_STD, &mut rng);
+  (0..N_LAYERS).for_each(|_| {
+    let layer = deform(&base, LAYER_DEPTH, DEFORMATION_STD, &mut rng);
+    draw.polygon().color(color).points(
+      layer
+        .exterior()
+        .coords()
+        .map(|coord| Vec2::new(coord.x, coord.y)),
+    );
+  });
+}
+
+// Light wash where the pigment pools and darkens along the edges while drying
+pub fn ink_wash(polygon: Polygon<f32>, draw: &Draw, options: FillingOptions) {
+  const N_EDGES: usize = 3;
+  const DEFORMATION_STD: f32 = 0.02;
+  let mut rng = rand::thread_rng();
+  let density = options.density.clamp(0.0, 1.0);
+  let with_alpha = |alpha: f32| {
+    Hsla::new(
+      options.color.hue,
+      options.color.saturation,
+      options.color.lightness,
+      alpha,
+    )
+  };
+  let to_points = |polygon: &Polygon<f32>| {
+    polygon
+      .exterior()
+      .coords()
+      .map(|coord| Vec2::new(coord.x, coord.y))
+      .collect::<Vec<_>>()
+  };
+  draw
+    .polygon()
+    .color(with_alpha(0.3 * density))
+    .points(to_points(&polygon));
+  (0..N_EDGES).for_each(|_| {
+    let edge = to_points(&deform(&polygon, 1, DEFORMATION_STD, &mut rng));
+    draw
+      .polyline()
+      .weight(4.0 * options.weight)
+      .color(with_alpha(0.1 * density))
+      .points(edge.clone());
+    draw
+      .polyline()
+      .weight(options.weight)
+      .color(with_alpha(0.6 * density))
+      .points(edge);
+  });
+}
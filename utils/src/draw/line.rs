@@ -1,101 +1,249 @@
-use crate::geometry::line::sample_wooble;
-use geo::{ConvexHull, Coord, EuclideanDistance, Line, LineInterpolatePoint, LineString};
+use super::filling::{self, FillingOptions};
+use crate::geometry::line::{densify, normals, outline, proportions, Join};
+use geo::{Coord, EuclideanLength, LineString, Polygon};
 use nannou::{
-  prelude::{Hsl, Hsla, Vec2, PI},
+  image::{DynamicImage, GrayImage},
+  prelude::{Hsl, Hsla, Vec2},
   Draw,
 };
-use rand_distr::{Distribution, Uniform};
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
 
+// Distance between two samples when drawing along a path
+const PATH_SPACING: f32 = 5.0;
+// Standard deviation of the offset drift between two samples, relative to the line weight
+const DRIFT_STD: f32 = 0.05;
+// Size of the procedural paper grain cells used by default
+const GRAIN_SIZE: f32 = 4.0;
+
+#[derive(Clone, Copy)]
 pub struct LineOptions {
   pub weight: f32,
   pub density: f32,
   pub color: Hsl,
 }
 
+// Weight multiplier along the path, evaluated on the proportion of the length travelled
+pub enum Pressure {
+  Constant,
+  // Weight grows from zero over the start proportion of the length and fades over the end one
+  Taper { start: f32, end: f32 },
+  Custom(Box<dyn Fn(f32) -> f32>),
+}
+
+impl Pressure {
+  pub fn at(&self, t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    match self {
+      Pressure::Constant => 1.0,
+      Pressure::Taper { start, end } => {
+        let fade_in = if *start > 0.0 {
+          smoothstep(t / start)
+        } else {
+          1.0
+        };
+        let fade_out = if *end > 0.0 {
+          smoothstep((1.0 - t) / end)
+        } else {
+          1.0
+        };
+        fade_in.min(fade_out)
+      }
+      Pressure::Custom(pressure) => pressure(t).max(0.0),
+    }
+  }
+}
+
+// Paper tooth height in [0, 1] at a position, which dry media catch on. It only depends on the
+// position so that successive strokes catch on the same grain.
+pub enum Grain {
+  // Smooth noise over a lattice with cells of the given size
+  Procedural { size: f32 },
+  // Lightness of a paper texture tiled over the plane, one pixel covering scale units
+  Texture { image: GrayImage, scale: f32 },
+  Custom(Box<dyn Fn(Coord<f32>) -> f32>),
+}
+
+impl Grain {
+  // Grain of the paper drawn in the background, e.g. assets/images/paper.jpg
+  pub fn texture(image: &DynamicImage, scale: f32) -> Self {
+    Grain::Texture {
+      image: image.to_luma8(),
+      scale,
+    }
+  }
+
+  pub fn at(&self, coord: Coord<f32>) -> f32 {
+    match self {
+      Grain::Procedural { size } => lattice_noise(coord / *size),
+      Grain::Texture { image, scale } => {
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+          return 0.0;
+        }
+        // Image rows go down while y goes up
+        let x = (coord.x / scale).floor().rem_euclid(width as f32) as u32;
+        let y = (-coord.y / scale).floor().rem_euclid(height as f32) as u32;
+        image.get_pixel(x.min(width - 1), y.min(height - 1)).0[0] as f32 / u8::MAX as f32
+      }
+      Grain::Custom(grain) => grain(coord).clamp(0.0, 1.0),
+    }
+  }
+}
+
+pub struct PathOptions {
+  pub pressure: Pressure,
+  pub join: Join,
+  // Paper grain the dry media catch on
+  pub grain: Grain,
+}
+
+impl Default for PathOptions {
+  fn default() -> Self {
+    Self {
+      pressure: Pressure::Constant,
+      join: Join::Round,
+      grain: Grain::Procedural { size: GRAIN_SIZE },
+    }
+  }
+}
+
 pub fn stroke(start: Coord<f32>, end: Coord<f32>, draw: &Draw, options: LineOptions) {
-  let start = Vec2::from(start.x_y());
-  let end = Vec2::from(end.x_y());
-  let color = Hsla::new(
-    options.color.hue,
-    options.color.saturation,
-    options.color.lightness,
-    options.density.clamp(0.0, 1.0),
-  );
-  draw
-    .line()
-    .start(start)
-    .end(end)
-    .stroke_weight(options.weight)
-    .color(color);
+  stroke_path(
+    &LineString::new(vec![start, end]),
+    draw,
+    options,
+    &PathOptions::default(),
+  )
 }
 
 pub fn brush(start: Coord<f32>, end: Coord<f32>, draw: &Draw, options: LineOptions) {
+  brush_path(
+    &LineString::new(vec![start, end]),
+    draw,
+    options,
+    &PathOptions::default(),
+  )
+}
+
+pub fn pencil(start: Coord<f32>, end: Coord<f32>, draw: &Draw, options: LineOptions) {
+  pencil_path(
+    &LineString::new(vec![start, end]),
+    draw,
+    options,
+    &PathOptions::default(),
+  )
+}
+
+pub fn marker(start: Coord<f32>, end: Coord<f32>, draw: &Draw, options: LineOptions) {
+  marker_path(
+    &LineString::new(vec![start, end]),
+    draw,
+    options,
+    &PathOptions::default(),
+  )
+}
+
+pub fn stroke_path(
+  line_string: &LineString<f32>,
+  draw: &Draw,
+  options: LineOptions,
+  path_options: &PathOptions,
+) {
+  let color = with_alpha(options.color, options.density);
+  let polygon = path_outline(line_string, options.weight, path_options);
+  draw.polygon().color(color).points(
+    polygon
+      .exterior()
+      .coords()
+      .map(|coord| Vec2::new(coord.x, coord.y)),
+  );
+}
+
+pub fn brush_path(
+  line_string: &LineString<f32>,
+  draw: &Draw,
+  options: LineOptions,
+  path_options: &PathOptions,
+) {
   const N_LINES: u32 = 50;
   let stroke_weight = 10. * options.weight / N_LINES as f32;
-  let color = Hsla::new(
-    options.color.hue,
-    options.color.saturation,
-    options.color.lightness,
-    options.density.clamp(0.0, 1.0),
-  );
-  // Generate points in the circle centered on start and end with radius equal to weight
-  // Then draw a line between those points
+  let color = with_alpha(options.color, options.density);
+  let samples = PathSamples::new(line_string, options.weight, &path_options.pressure);
+  let mut rng = rand::thread_rng();
+  // Each bristle keeps its own offset from the path, drifting continuously along it
   (0..N_LINES)
     .map(|_| {
-      let start = sample_within_circle(&start, options.weight);
-      let end = sample_within_circle(&end, options.weight);
-      (Vec2::from(start.x_y()), Vec2::from(end.x_y()))
+      let offsets = drift(samples.len(), &mut rng);
+      samples
+        .offset_points(&offsets)
+        .map(|coord| Vec2::new(coord.x, coord.y))
+        .collect::<Vec<_>>()
     })
-    .for_each(|(start, end)| {
+    .for_each(|points| {
       draw
-        .line()
-        .start(start)
-        .end(end)
+        .polyline()
         .stroke_weight(stroke_weight)
-        .color(color);
+        .color(color)
+        .points(points);
     })
 }
 
-pub fn pencil(start: Coord<f32>, end: Coord<f32>, draw: &Draw, options: LineOptions) {
+pub fn pencil_path(
+  line_string: &LineString<f32>,
+  draw: &Draw,
+  options: LineOptions,
+  path_options: &PathOptions,
+) {
   const STROKE_WEIGHT: f32 = 5.0;
   let n_lines = 10.0 * options.density * options.weight;
-  // Generate points in the circle centered on start and end with radius equal to weight
-  // Then draw a line between those points
-  (0..n_lines as u32)
-    .map(|_| {
-      let start = sample_within_circle(&start, options.weight);
-      let end = sample_within_circle(&end, options.weight);
-      Line::new(start, end)
-    })
-    .for_each(|line| {
-      let n_points = (options.density * start.euclidean_distance(&end)) as usize / 50;
-      Uniform::new(0.0, 1.0)
-        .sample_iter(&mut rand::thread_rng())
-        .map(|t| line.line_interpolate_point(t))
-        .filter(|point| point.is_some())
-        .map(|point| Vec2::from(point.unwrap().x_y()))
-        .take(n_points)
-        .for_each(|point| {
-          draw
-            .ellipse()
-            .xy(point)
-            .w_h(STROKE_WEIGHT, STROKE_WEIGHT)
-            .color(options.color);
-        })
-    })
+  let n_points = (options.density * line_string.euclidean_length()) as usize / 50;
+  let samples = PathSamples::new(line_string, options.weight, &path_options.pressure);
+  let mut rng = rand::thread_rng();
+  // Each graphite trace follows its own continuous offset from the path
+  // Then we scatter points along it
+  (0..n_lines as u32).for_each(|_| {
+    let offsets = drift(samples.len(), &mut rng);
+    (0..n_points)
+      .map(|_| samples.offset_point_at(rng.gen(), &offsets))
+      .map(|coord| Vec2::new(coord.x, coord.y))
+      .for_each(|point| {
+        draw
+          .ellipse()
+          .xy(point)
+          .w_h(STROKE_WEIGHT, STROKE_WEIGHT)
+          .color(options.color);
+      })
+  })
 }
 
-pub fn marker(start: Coord<f32>, end: Coord<f32>, draw: &Draw, options: LineOptions) {
-  let color = Hsla::new(
-    options.color.hue,
-    options.color.saturation,
-    options.color.lightness,
-    options.density.clamp(0.0, 1.0),
-  );
-  let n_samples = (options.weight * start.euclidean_distance(&end)) as usize / 50;
-  let std_dev = 0.5 * options.weight;
-  let line_string = sample_wooble(start, end, n_samples, std_dev).collect::<LineString<f32>>();
-  let polygon = line_string.convex_hull();
+pub fn marker_path(
+  line_string: &LineString<f32>,
+  draw: &Draw,
+  options: LineOptions,
+  path_options: &PathOptions,
+) {
+  let color = with_alpha(options.color, options.density);
+  let samples = PathSamples::new(line_string, options.weight, &path_options.pressure);
+  let mut rng = rand::thread_rng();
+  // The ink blob wobbles around the path and its width varies smoothly
+  let offsets = drift(samples.len(), &mut rng);
+  let widths = drift(samples.len(), &mut rng);
+  let path = samples
+    .offset_points(
+      &offsets
+        .iter()
+        .map(|offset| 0.25 * offset)
+        .collect::<Vec<_>>(),
+    )
+    .collect::<LineString<f32>>();
+  let half_widths = samples
+    .weights
+    .iter()
+    .zip(widths.iter())
+    .map(|(weight, width)| 0.5 * weight * (1.0 + 0.5 * width))
+    .collect::<Vec<_>>();
+  let polygon = outline(&path, &half_widths, path_options.join);
   let points = polygon
     .exterior()
     .coords()
@@ -103,8 +251,305 @@ pub fn marker(start: Coord<f32>, end: Coord<f32>, draw: &Draw, options: LineOpti
   draw.polygon().points_colored(points);
 }
 
-fn sample_within_circle(center: &Coord<f32>, radius: f32) -> Coord<f32> {
-  let r = radius * rand::random::<f32>().sqrt();
-  let theta = 2. * PI * rand::random::<f32>();
-  *center + (r * theta.cos(), r * theta.sin()).into()
+pub fn charcoal(start: Coord<f32>, end: Coord<f32>, draw: &Draw, options: LineOptions) {
+  charcoal_path(
+    &LineString::new(vec![start, end]),
+    draw,
+    options,
+    &PathOptions::default(),
+  )
+}
+
+pub fn watercolour(start: Coord<f32>, end: Coord<f32>, draw: &Draw, options: LineOptions) {
+  watercolour_path(
+    &LineString::new(vec![start, end]),
+    draw,
+    options,
+    &PathOptions::default(),
+  )
+}
+
+pub fn ink_wash(start: Coord<f32>, end: Coord<f32>, draw: &Draw, options: LineOptions) {
+  ink_wash_path(
+    &LineString::new(vec![start, end]),
+    draw,
+    options,
+    &PathOptions::default(),
+  )
+}
+
+pub fn calligraphy(
+  start: Coord<f32>,
+  end: Coord<f32>,
+  draw: &Draw,
+  nib_degrees: f32,
+  options: LineOptions,
+) {
+  calligraphy_path(
+    &LineString::new(vec![start, end]),
+    draw,
+    nib_degrees,
+    options,
+    &PathOptions::default(),
+  )
+}
+
+pub fn charcoal_path(
+  line_string: &LineString<f32>,
+  draw: &Draw,
+  options: LineOptions,
+  path_options: &PathOptions,
+) {
+  const STROKE_WEIGHT: f32 = 2.0;
+  const COVERAGE: f32 = 0.5;
+  let n_points = (COVERAGE * 2.0 * options.weight * line_string.euclidean_length()
+    / STROKE_WEIGHT.powi(2)) as usize;
+  let samples = PathSamples::new(line_string, options.weight, &path_options.pressure);
+  let mut rng = rand::thread_rng();
+  // Pigment only sticks to the paper tooth: the harder the pressure,
+  // the deeper it reaches into the grain valleys
+  (0..n_points)
+    .filter_map(|_| {
+      let (coord, normal, weight) = samples.at(rng.gen());
+      let coord = coord + normal * (weight * rng.gen_range(-1.0..1.0));
+      let pressure = if options.weight > 0.0 {
+        weight / options.weight
+      } else {
+        0.0
+      };
+      (path_options.grain.at(coord) > 1.0 - options.density * pressure)
+        .then(|| Vec2::new(coord.x, coord.y))
+    })
+    .for_each(|point| {
+      draw
+        .ellipse()
+        .xy(point)
+        .w_h(STROKE_WEIGHT, STROKE_WEIGHT)
+        .color(options.color);
+    })
+}
+
+pub fn watercolour_path(
+  line_string: &LineString<f32>,
+  draw: &Draw,
+  options: LineOptions,
+  path_options: &PathOptions,
+) {
+  filling::watercolour(
+    path_outline(line_string, options.weight, path_options),
+    draw,
+    FillingOptions {
+      weight: options.weight,
+      density: options.density,
+      color: options.color,
+    },
+  )
+}
+
+pub fn ink_wash_path(
+  line_string: &LineString<f32>,
+  draw: &Draw,
+  options: LineOptions,
+  path_options: &PathOptions,
+) {
+  filling::ink_wash(
+    path_outline(line_string, options.weight, path_options),
+    draw,
+    FillingOptions {
+      weight: 0.1 * options.weight,
+      density: options.density,
+      color: options.color,
+    },
+  )
+}
+
+// Broad nib held at a fixed angle: the line is thin when drawn along the nib
+// and as wide as the nib when drawn across it
+pub fn calligraphy_path(
+  line_string: &LineString<f32>,
+  draw: &Draw,
+  nib_degrees: f32,
+  options: LineOptions,
+  path_options: &PathOptions,
+) {
+  let color = with_alpha(options.color, options.density);
+  let samples = PathSamples::new(line_string, options.weight, &path_options.pressure);
+  let nib = Coord::from((
+    nib_degrees.to_radians().cos(),
+    nib_degrees.to_radians().sin(),
+  ));
+  let left = (0..samples.len()).map(|i| samples.coords[i] + nib * (0.5 * samples.weights[i]));
+  let right = (0..samples.len()).map(|i| samples.coords[i] - nib * (0.5 * samples.weights[i]));
+  let points = left
+    .chain(right.rev())
+    .map(|coord| Vec2::new(coord.x, coord.y))
+    .collect::<Vec<_>>();
+  draw.polygon().color(color).points(points);
+}
+
+// Outline of the path used by the media painting a polygon
+fn path_outline(
+  line_string: &LineString<f32>,
+  weight: f32,
+  path_options: &PathOptions,
+) -> Polygon<f32> {
+  let path = densify(line_string, PATH_SPACING);
+  let half_widths = proportions(&path)
+    .iter()
+    .map(|t| 0.5 * weight * path_options.pressure.at(*t))
+    .collect::<Vec<_>>();
+  outline(&path, &half_widths, path_options.join)
+}
+
+// Path densified at a regular spacing, with the normal and the weight at each sample
+struct PathSamples {
+  coords: Vec<Coord<f32>>,
+  normals: Vec<Coord<f32>>,
+  proportions: Vec<f32>,
+  weights: Vec<f32>,
+}
+
+impl PathSamples {
+  fn new(line_string: &LineString<f32>, weight: f32, pressure: &Pressure) -> Self {
+    let path = densify(line_string, PATH_SPACING);
+    let normals = normals(&path);
+    let proportions = proportions(&path);
+    let weights = proportions
+      .iter()
+      .map(|t| weight * pressure.at(*t))
+      .collect();
+    Self {
+      coords: path.0,
+      normals,
+      proportions,
+      weights,
+    }
+  }
+
+  fn len(&self) -> usize {
+    self.coords.len()
+  }
+
+  // Moves each sample along its normal by offset times the local weight
+  fn offset_points<'a>(&'a self, offsets: &'a [f32]) -> impl Iterator<Item = Coord<f32>> + 'a {
+    (0..self.len().min(offsets.len()))
+      .map(|i| self.coords[i] + self.normals[i] * (offsets[i] * self.weights[i]))
+  }
+
+  // Same as offset_points at a proportion t of the length, interpolating between samples
+  fn offset_point_at(&self, t: f32, offsets: &[f32]) -> Coord<f32> {
+    let (coord, normal, weight) = self.at(t);
+    let offset = match self.locate(t) {
+      Some((index, local)) => offsets[index - 1] + (offsets[index] - offsets[index - 1]) * local,
+      None => 0.0,
+    };
+    coord + normal * (offset * weight)
+  }
+
+  // Position, normal and weight at a proportion t of the length, interpolating between samples
+  fn at(&self, t: f32) -> (Coord<f32>, Coord<f32>, f32) {
+    match self.locate(t) {
+      Some((index, local)) => (
+        self.coords[index - 1] + (self.coords[index] - self.coords[index - 1]) * local,
+        self.normals[index - 1] + (self.normals[index] - self.normals[index - 1]) * local,
+        self.weights[index - 1] + (self.weights[index] - self.weights[index - 1]) * local,
+      ),
+      None => (
+        self.coords.first().copied().unwrap_or_else(Coord::zero),
+        Coord::zero(),
+        0.0,
+      ),
+    }
+  }
+
+  // Index of the first sample after t and relative position of t from the previous sample
+  fn locate(&self, t: f32) -> Option<(usize, f32)> {
+    if self.len() < 2 {
+      return None;
+    }
+    let index = self
+      .proportions
+      .partition_point(|proportion| *proportion < t)
+      .clamp(1, self.len() - 1);
+    let (before, after) = (self.proportions[index - 1], self.proportions[index]);
+    let local = if after > before {
+      ((t - before) / (after - before)).clamp(0.0, 1.0)
+    } else {
+      0.0
+    };
+    Some((index, local))
+  }
+}
+
+// Random walk in [-1, 1] with one value per sample
+fn drift<R: Rng>(n_samples: usize, rng: &mut R) -> Vec<f32> {
+  let normal = Normal::new(0.0, DRIFT_STD).unwrap();
+  let mut offset = rng.gen_range(-1.0..1.0);
+  (0..n_samples)
+    .map(|_| {
+      let current = offset;
+      offset = (offset + normal.sample(rng)).clamp(-1.0, 1.0);
+      current
+    })
+    .collect()
+}
+
+fn with_alpha(color: Hsl, alpha: f32) -> Hsla {
+  Hsla::new(
+    color.hue,
+    color.saturation,
+    color.lightness,
+    alpha.clamp(0.0, 1.0),
+  )
+}
+
+fn smoothstep(x: f32) -> f32 {
+  let x = x.clamp(0.0, 1.0);
+  x * x * (3.0 - 2.0 * x)
+}
+
+// Value in [0, 1] smoothly interpolated between random values at the integer coordinates
+fn lattice_noise(coord: Coord<f32>) -> f32 {
+  let (x, y) = (coord.x, coord.y);
+  let (x0, y0) = (x.floor(), y.floor());
+  let (tx, ty) = (smoothstep(x - x0), smoothstep(y - y0));
+  let (i, j) = (x0 as i32, y0 as i32);
+  let bottom = lattice_value(i, j) + (lattice_value(i + 1, j) - lattice_value(i, j)) * tx;
+  let top = lattice_value(i, j + 1) + (lattice_value(i + 1, j + 1) - lattice_value(i, j + 1)) * tx;
+  bottom + (top - bottom) * ty
+}
+
+fn lattice_value(i: i32, j: i32) -> f32 {
+  let mut hash = (i as u32).wrapping_mul(374_761_393) ^ (j as u32).wrapping_mul(668_265_263);
+  hash = (hash ^ (hash >> 13)).wrapping_mul(1_274_126_177);
+  (hash ^ (hash >> 16)) as f32 / u32::MAX as f32
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn texture_grain_is_tiled() {
+    // Two by two texture, black on the top row
+    let image = GrayImage::from_raw(2, 2, vec![0, 0, 255, 255]).unwrap();
+    let grain = Grain::Texture { image, scale: 10.0 };
+    assert_eq!(grain.at(Coord::from((5.0, -5.0))), 0.0);
+    assert_eq!(grain.at(Coord::from((5.0, -15.0))), 1.0);
+    assert_eq!(grain.at(Coord::from((5.0, 5.0))), 1.0);
+    assert_eq!(grain.at(Coord::from((-15.0, -25.0))), 0.0);
+  }
+
+  #[test]
+  fn grain_is_in_unit_range() {
+    let procedural = Grain::Procedural { size: GRAIN_SIZE };
+    let custom = Grain::Custom(Box::new(|coord| coord.x));
+    (-50..50).for_each(|i| {
+      let coord = Coord::from((i as f32 * 0.7, i as f32 * 1.3));
+      [&procedural, &custom].iter().for_each(|grain| {
+        let value = grain.at(coord);
+        assert!((0.0..=1.0).contains(&value));
+      });
+    });
+  }
 }
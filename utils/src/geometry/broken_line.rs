@@ -0,0 +1,172 @@
+use super::curve::{sample_adaptive, QuadraticBezier};
+use geo::{Coord, LineString};
+use rand::Rng;
+use rand_distr::{Distribution, Normal, StandardNormal};
+
+// Maximum distance between a curved piece and its sampling
+const CURVE_TOLERANCE: f32 = 0.5;
+
+#[derive(Clone, Copy)]
+pub enum BreakpointDistribution {
+  // Breakpoints anywhere along the line
+  Uniform,
+  // Evenly spaced breakpoints, each moved by up to jitter times the spacing
+  Regular { jitter: f32 },
+  // Breakpoints gathered around a proportion of the line
+  Normal { mean: f32, std_dev: f32 },
+}
+
+pub struct BrokenLine {
+  // Drawn pieces of the line, two points long unless they are curved
+  pub segments: Vec<LineString<f32>>,
+}
+
+// Splits a segment into pieces separated by gaps, e.g. to give hatches a hand drawn look
+pub struct BrokenLineBuilder {
+  start: Coord<f32>,
+  end: Coord<f32>,
+  max_breakpoints: usize,
+  break_proportion: f32,
+  distribution: BreakpointDistribution,
+  jitter: f32,
+  curvature: f32,
+}
+
+impl BrokenLineBuilder {
+  pub fn new(start: Coord<f32>, end: Coord<f32>) -> Self {
+    Self {
+      start,
+      end,
+      max_breakpoints: 0,
+      break_proportion: 1.0,
+      distribution: BreakpointDistribution::Uniform,
+      jitter: 0.0,
+      curvature: 0.0,
+    }
+  }
+
+  // The number of breakpoints is drawn between 0 and max_breakpoints
+  pub fn max_breakpoints(mut self, max_breakpoints: usize) -> Self {
+    self.max_breakpoints = max_breakpoints;
+    self
+  }
+
+  // Proportion of the line length which is drawn, the rest being shared between the gaps
+  pub fn break_proportion(mut self, break_proportion: f32) -> Self {
+    self.break_proportion = break_proportion.clamp(0.0, 1.0);
+    self
+  }
+
+  pub fn distribution(mut self, distribution: BreakpointDistribution) -> Self {
+    self.distribution = distribution;
+    self
+  }
+
+  // Standard deviation of the offset of the pieces ends across the line
+  pub fn jitter(mut self, jitter: f32) -> Self {
+    self.jitter = jitter.max(0.0);
+    self
+  }
+
+  // Standard deviation of the pieces bend, relatively to their length. 0.0 gives straight pieces.
+  pub fn curvature(mut self, curvature: f32) -> Self {
+    self.curvature = curvature.max(0.0);
+    self
+  }
+
+  pub fn build<R: Rng>(&self, rng: &mut R) -> BrokenLine {
+    let delta = self.end - self.start;
+    let length = delta.x.hypot(delta.y);
+    if length <= 0.0 {
+      return BrokenLine { segments: vec![] };
+    }
+    let direction = delta / length;
+    let normal = Coord::from((-direction.y, direction.x));
+
+    let n_breakpoints = rng.gen_range(0..=self.max_breakpoints);
+    let breakpoints = self.breakpoints(n_breakpoints, rng);
+
+    // Gap widths are random shares of the total gap length
+    let weights = breakpoints
+      .iter()
+      .map(|_| rng.gen_range(0.5..1.5))
+      .collect::<Vec<f32>>();
+    let total_weight = weights.iter().sum::<f32>();
+    let total_gap = (1.0 - self.break_proportion) * length;
+    let gaps = breakpoints
+      .iter()
+      .zip(weights)
+      .map(|(breakpoint, weight)| {
+        let half_width = total_gap * weight / total_weight / 2.0;
+        (
+          breakpoint * length - half_width,
+          breakpoint * length + half_width,
+        )
+      })
+      .collect::<Vec<_>>();
+
+    let offset = |rng: &mut R| match self.jitter > 0.0 {
+      true => normal * (rng.sample::<f32, _>(StandardNormal) * self.jitter),
+      false => Coord::zero(),
+    };
+    let starts = std::iter::once(0.0).chain(gaps.iter().map(|(_, gap_end)| *gap_end));
+    let ends = gaps
+      .iter()
+      .map(|(gap_start, _)| *gap_start)
+      .chain(std::iter::once(length));
+    let segments = starts
+      .zip(ends)
+      .map(|(piece_start, piece_end)| (piece_start.max(0.0), piece_end.min(length)))
+      .filter(|(piece_start, piece_end)| piece_end > piece_start)
+      .map(|(piece_start, piece_end)| {
+        let start = self.start + direction * piece_start + offset(rng);
+        let end = self.start + direction * piece_end + offset(rng);
+        self.piece(start, end, normal, rng)
+      })
+      .collect();
+    BrokenLine { segments }
+  }
+
+  // Sorted breakpoints, as proportions of the line length
+  fn breakpoints<R: Rng>(&self, n_breakpoints: usize, rng: &mut R) -> Vec<f32> {
+    let mut breakpoints = match self.distribution {
+      BreakpointDistribution::Uniform => (0..n_breakpoints)
+        .map(|_| rng.gen_range(0.0..1.0))
+        .collect::<Vec<_>>(),
+      BreakpointDistribution::Regular { jitter } => {
+        let spacing = 1.0 / (n_breakpoints + 1) as f32;
+        let jitter = jitter.clamp(0.0, 1.0) * spacing;
+        (1..=n_breakpoints)
+          .map(|i| match jitter > 0.0 {
+            true => i as f32 * spacing + rng.gen_range(-jitter..jitter),
+            false => i as f32 * spacing,
+          })
+          .collect()
+      }
+      BreakpointDistribution::Normal { mean, std_dev } => {
+        let normal = Normal::new(mean, std_dev.abs()).unwrap();
+        (0..n_breakpoints)
+          .map(|_| normal.sample(rng).clamp(0.0, 1.0))
+          .collect()
+      }
+    };
+    breakpoints.sort_by(|a, b| a.total_cmp(b));
+    breakpoints
+  }
+
+  fn piece<R: Rng>(
+    &self,
+    start: Coord<f32>,
+    end: Coord<f32>,
+    normal: Coord<f32>,
+    rng: &mut R,
+  ) -> LineString<f32> {
+    if self.curvature <= 0.0 {
+      return LineString::new(vec![start, end]);
+    }
+    let delta = end - start;
+    let bend = rng.sample::<f32, _>(StandardNormal) * self.curvature * delta.x.hypot(delta.y);
+    let control = (start + end) / 2.0 + normal * bend;
+    sample_adaptive(&QuadraticBezier::new(start, control, end), CURVE_TOLERANCE)
+  }
+}
@@ -0,0 +1,358 @@
+use geo::{Coord, EuclideanDistance, LineString};
+
+// Number of samples used to approximate the curve length when reparameterising by arc length
+const ARC_LENGTH_TABLE_SIZE: usize = 256;
+// Minimum number of segments before adaptive subdivision kicks in.
+// Prevents symmetric curves (e.g. an S shape) from being mistaken for a straight line.
+const ADAPTIVE_MIN_SEGMENTS: usize = 4;
+const ADAPTIVE_MAX_DEPTH: usize = 12;
+
+pub trait Curve {
+  // Evaluates the curve at t in [0, 1]
+  fn point_at(&self, t: f32) -> Coord<f32>;
+}
+
+pub struct QuadraticBezier {
+  pub start: Coord<f32>,
+  pub control: Coord<f32>,
+  pub end: Coord<f32>,
+}
+
+impl QuadraticBezier {
+  pub fn new(start: Coord<f32>, control: Coord<f32>, end: Coord<f32>) -> Self {
+    Self {
+      start,
+      control,
+      end,
+    }
+  }
+}
+
+impl Curve for QuadraticBezier {
+  fn point_at(&self, t: f32) -> Coord<f32> {
+    let u = 1.0 - t;
+    self.start * (u * u) + self.control * (2.0 * u * t) + self.end * (t * t)
+  }
+}
+
+pub struct CubicBezier {
+  pub start: Coord<f32>,
+  pub control_1: Coord<f32>,
+  pub control_2: Coord<f32>,
+  pub end: Coord<f32>,
+}
+
+impl CubicBezier {
+  pub fn new(
+    start: Coord<f32>,
+    control_1: Coord<f32>,
+    control_2: Coord<f32>,
+    end: Coord<f32>,
+  ) -> Self {
+    Self {
+      start,
+      control_1,
+      control_2,
+      end,
+    }
+  }
+}
+
+impl Curve for CubicBezier {
+  fn point_at(&self, t: f32) -> Coord<f32> {
+    let u = 1.0 - t;
+    self.start * (u * u * u)
+      + self.control_1 * (3.0 * u * u * t)
+      + self.control_2 * (3.0 * u * t * t)
+      + self.end * (t * t * t)
+  }
+}
+
+// Catmull-Rom spline going through every control point.
+// alpha = 0.0 gives the uniform variant, 0.5 the centripetal one (no cusps nor self-intersections)
+// and 1.0 the chordal one.
+pub struct CatmullRom {
+  points: Vec<Coord<f32>>,
+  alpha: f32,
+}
+
+impl CatmullRom {
+  pub fn new(points: Vec<Coord<f32>>, alpha: f32) -> Self {
+    // Add mirrored phantom points so that the spline reaches the first and last control points
+    let mut padded = Vec::with_capacity(points.len() + 2);
+    if let [first, second, ..] = points[..] {
+      padded.push(first * 2.0 - second);
+    }
+    padded.extend(points.iter().copied());
+    if let [.., before_last, last] = points[..] {
+      padded.push(last * 2.0 - before_last);
+    }
+    Self {
+      points: padded,
+      alpha,
+    }
+  }
+
+  pub fn centripetal(points: Vec<Coord<f32>>) -> Self {
+    Self::new(points, 0.5)
+  }
+
+  fn n_segments(&self) -> usize {
+    self.points.len().saturating_sub(3)
+  }
+
+  fn knot_interval(&self, a: Coord<f32>, b: Coord<f32>) -> f32 {
+    // Avoid null intervals when two consecutive points are equal
+    a.euclidean_distance(&b).powf(self.alpha).max(f32::EPSILON)
+  }
+
+  // Barry and Goldman's pyramidal formulation
+  fn segment_point_at(&self, segment: usize, t: f32) -> Coord<f32> {
+    let [p0, p1, p2, p3] = [
+      self.points[segment],
+      self.points[segment + 1],
+      self.points[segment + 2],
+      self.points[segment + 3],
+    ];
+    let t0 = 0.0;
+    let t1 = t0 + self.knot_interval(p0, p1);
+    let t2 = t1 + self.knot_interval(p1, p2);
+    let t3 = t2 + self.knot_interval(p2, p3);
+    let t = t1 + t * (t2 - t1);
+
+    let lerp = |a: Coord<f32>, b: Coord<f32>, ta: f32, tb: f32| {
+      if tb - ta <= 0.0 {
+        return a;
+      }
+      a * ((tb - t) / (tb - ta)) + b * ((t - ta) / (tb - ta))
+    };
+    let a1 = lerp(p0, p1, t0, t1);
+    let a2 = lerp(p1, p2, t1, t2);
+    let a3 = lerp(p2, p3, t2, t3);
+    let b1 = lerp(a1, a2, t0, t2);
+    let b2 = lerp(a2, a3, t1, t3);
+    lerp(b1, b2, t1, t2)
+  }
+}
+
+impl Curve for CatmullRom {
+  fn point_at(&self, t: f32) -> Coord<f32> {
+    match self.n_segments() {
+      0 => self.points.first().copied().unwrap_or_else(Coord::zero),
+      n_segments => {
+        let (segment, t) = locate_segment(t, n_segments);
+        self.segment_point_at(segment, t)
+      }
+    }
+  }
+}
+
+// Uniform cubic B-spline approximating the control points.
+// The curve does not go through the control points, use CatmullRom for interpolation.
+pub struct BSpline {
+  points: Vec<Coord<f32>>,
+}
+
+impl BSpline {
+  pub fn new(points: Vec<Coord<f32>>) -> Self {
+    Self { points }
+  }
+
+  // Repeats the end points so that the curve starts and ends on them
+  pub fn clamped(points: Vec<Coord<f32>>) -> Self {
+    let mut padded = Vec::with_capacity(points.len() + 4);
+    if let Some(first) = points.first() {
+      padded.extend([*first, *first]);
+    }
+    padded.extend(points.iter().copied());
+    if let Some(last) = points.last() {
+      padded.extend([*last, *last]);
+    }
+    Self { points: padded }
+  }
+
+  fn n_segments(&self) -> usize {
+    self.points.len().saturating_sub(3)
+  }
+}
+
+impl Curve for BSpline {
+  fn point_at(&self, t: f32) -> Coord<f32> {
+    match self.n_segments() {
+      0 => self.points.first().copied().unwrap_or_else(Coord::zero),
+      n_segments => {
+        let (segment, t) = locate_segment(t, n_segments);
+        let [p0, p1, p2, p3] = [
+          self.points[segment],
+          self.points[segment + 1],
+          self.points[segment + 2],
+          self.points[segment + 3],
+        ];
+        let t2 = t * t;
+        let t3 = t2 * t;
+        let b0 = (1.0 - t).powi(3) / 6.0;
+        let b1 = (3.0 * t3 - 6.0 * t2 + 4.0) / 6.0;
+        let b2 = (-3.0 * t3 + 3.0 * t2 + 3.0 * t + 1.0) / 6.0;
+        let b3 = t3 / 6.0;
+        p0 * b0 + p1 * b1 + p2 * b2 + p3 * b3
+      }
+    }
+  }
+}
+
+// Elliptical arc going from theta_start to theta_end (radians), rotated by rotation (radians).
+// Angles can decrease to draw the arc clockwise.
+pub struct Arc {
+  pub center: Coord<f32>,
+  pub radius_x: f32,
+  pub radius_y: f32,
+  pub rotation: f32,
+  pub theta_start: f32,
+  pub theta_end: f32,
+}
+
+impl Arc {
+  pub fn circular(center: Coord<f32>, radius: f32, theta_start: f32, theta_end: f32) -> Self {
+    Self::elliptical(center, radius, radius, 0.0, theta_start, theta_end)
+  }
+
+  pub fn elliptical(
+    center: Coord<f32>,
+    radius_x: f32,
+    radius_y: f32,
+    rotation: f32,
+    theta_start: f32,
+    theta_end: f32,
+  ) -> Self {
+    Self {
+      center,
+      radius_x,
+      radius_y,
+      rotation,
+      theta_start,
+      theta_end,
+    }
+  }
+}
+
+impl Curve for Arc {
+  fn point_at(&self, t: f32) -> Coord<f32> {
+    let theta = self.theta_start + t * (self.theta_end - self.theta_start);
+    let x = self.radius_x * theta.cos();
+    let y = self.radius_y * theta.sin();
+    let (sin, cos) = self.rotation.sin_cos();
+    self.center + (x * cos - y * sin, x * sin + y * cos).into()
+  }
+}
+
+// Samples n_samples + 1 points evenly spaced in the curve parameter
+pub fn sample_uniform<C: Curve>(curve: &C, n_samples: usize) -> LineString<f32> {
+  let n_samples = n_samples.max(1);
+  (0..=n_samples)
+    .map(|i| curve.point_at(i as f32 / n_samples as f32))
+    .collect()
+}
+
+// Samples n_samples + 1 points evenly spaced along the curve length
+pub fn sample_arc_length<C: Curve>(curve: &C, n_samples: usize) -> LineString<f32> {
+  let n_samples = n_samples.max(1);
+  let table = ArcLengthTable::new(curve);
+  (0..=n_samples)
+    .map(|i| curve.point_at(table.parameter_at(i as f32 / n_samples as f32)))
+    .collect()
+}
+
+// Samples points spaced by approximately spacing along the curve length
+pub fn sample_spacing<C: Curve>(curve: &C, spacing: f32) -> LineString<f32> {
+  let n_samples = (length(curve) / spacing.max(f32::EPSILON)).ceil() as usize;
+  sample_arc_length(curve, n_samples)
+}
+
+// Recursively subdivides the curve until each segment deviates from the curve by less than tolerance.
+// High curvature areas get more points than flat ones.
+pub fn sample_adaptive<C: Curve>(curve: &C, tolerance: f32) -> LineString<f32> {
+  let mut coords = vec![curve.point_at(0.0)];
+  (0..ADAPTIVE_MIN_SEGMENTS).for_each(|i| {
+    let t_start = i as f32 / ADAPTIVE_MIN_SEGMENTS as f32;
+    let t_end = (i + 1) as f32 / ADAPTIVE_MIN_SEGMENTS as f32;
+    subdivide(curve, t_start, t_end, tolerance, 0, &mut coords);
+  });
+  LineString::new(coords)
+}
+
+fn subdivide<C: Curve>(
+  curve: &C,
+  t_start: f32,
+  t_end: f32,
+  tolerance: f32,
+  depth: usize,
+  coords: &mut Vec<Coord<f32>>,
+) {
+  let start = curve.point_at(t_start);
+  let end = curve.point_at(t_end);
+  let t_mid = (t_start + t_end) / 2.0;
+  let mid = curve.point_at(t_mid);
+  let chord_mid = (start + end) / 2.0;
+  if depth < ADAPTIVE_MAX_DEPTH && mid.euclidean_distance(&chord_mid) > tolerance {
+    subdivide(curve, t_start, t_mid, tolerance, depth + 1, coords);
+    subdivide(curve, t_mid, t_end, tolerance, depth + 1, coords);
+  } else {
+    coords.push(end);
+  }
+}
+
+pub fn length<C: Curve>(curve: &C) -> f32 {
+  ArcLengthTable::new(curve).length()
+}
+
+// Cumulative lengths of a dense uniform sampling of the curve,
+// used to map a length proportion back to a curve parameter.
+struct ArcLengthTable {
+  lengths: Vec<f32>,
+}
+
+impl ArcLengthTable {
+  fn new<C: Curve>(curve: &C) -> Self {
+    let mut lengths = Vec::with_capacity(ARC_LENGTH_TABLE_SIZE + 1);
+    let mut previous = curve.point_at(0.0);
+    let mut total = 0.0;
+    lengths.push(total);
+    (1..=ARC_LENGTH_TABLE_SIZE).for_each(|i| {
+      let current = curve.point_at(i as f32 / ARC_LENGTH_TABLE_SIZE as f32);
+      total += previous.euclidean_distance(&current);
+      lengths.push(total);
+      previous = current;
+    });
+    Self { lengths }
+  }
+
+  fn length(&self) -> f32 {
+    *self.lengths.last().unwrap()
+  }
+
+  fn parameter_at(&self, proportion: f32) -> f32 {
+    let length = self.length();
+    if length <= 0.0 {
+      return proportion.clamp(0.0, 1.0);
+    }
+    let target = proportion.clamp(0.0, 1.0) * length;
+    let index = self
+      .lengths
+      .partition_point(|l| *l < target)
+      .clamp(1, ARC_LENGTH_TABLE_SIZE);
+    let (before, after) = (self.lengths[index - 1], self.lengths[index]);
+    let local = if after > before {
+      (target - before) / (after - before)
+    } else {
+      0.0
+    };
+    (index as f32 - 1.0 + local) / ARC_LENGTH_TABLE_SIZE as f32
+  }
+}
+
+// Maps a global parameter to a segment index and a local parameter in [0, 1]
+fn locate_segment(t: f32, n_segments: usize) -> (usize, f32) {
+  let t = t.clamp(0.0, 1.0) * n_segments as f32;
+  let segment = (t.floor() as usize).min(n_segments - 1);
+  (segment, t - segment as f32)
+}
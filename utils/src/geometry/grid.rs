@@ -0,0 +1,247 @@
+use geo::{Contains, Coord, LineString, Polygon, Rect};
+use std::collections::HashSet;
+use std::f32::consts::PI;
+
+const SQRT_3: f32 = 1.732_050_8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HexOrientation {
+  // Two horizontal sides
+  Flat,
+  // Two vertical sides
+  Pointy,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GridKind {
+  Square,
+  Hexagonal(HexOrientation),
+  Triangular,
+}
+
+// Column and row for square and triangular grids, axial coordinates for hexagonal grids.
+// Triangles point up when q + r is even.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct CellCoord {
+  pub q: i32,
+  pub r: i32,
+}
+
+impl CellCoord {
+  pub fn new(q: i32, r: i32) -> Self {
+    Self { q, r }
+  }
+}
+
+// Regular tiling of the plane by cells with sides of the given size. Vertices and edges of a cell
+// go counter-clockwise, edge i joining vertex i to vertex i + 1.
+pub struct Grid {
+  kind: GridKind,
+  size: f32,
+  origin: Coord<f32>,
+  cells: Vec<CellCoord>,
+  index: HashSet<CellCoord>,
+}
+
+impl Grid {
+  // Cells whose center lies in the rectangle, the lattice being centered on it
+  pub fn new(kind: GridKind, rect: Rect<f32>, size: f32) -> Self {
+    let size = size.max(f32::EPSILON);
+    let (width, height) = (rect.width(), rect.height());
+    let origin = match kind {
+      GridKind::Square => {
+        let columns = (width / size).floor().max(1.0);
+        let rows = (height / size).floor().max(1.0);
+        rect.center() - Coord::from(((columns - 1.0) * size / 2.0, (rows - 1.0) * size / 2.0))
+      }
+      GridKind::Hexagonal(_) | GridKind::Triangular => rect.center(),
+    };
+    let mut grid = Self {
+      kind,
+      size,
+      origin,
+      cells: vec![],
+      index: HashSet::new(),
+    };
+
+    let extent = (width.max(height) / size).ceil() as i32 + 2;
+    let (q_range, r_range) = match kind {
+      GridKind::Square => (0..=extent, 0..=extent),
+      GridKind::Hexagonal(_) => (-extent..=extent, -extent..=extent),
+      GridKind::Triangular => (-2 * extent..=2 * extent, -extent..=extent),
+    };
+    let inside = |coord: Coord<f32>| {
+      let tolerance = size * 1e-3;
+      coord.x >= rect.min().x - tolerance
+        && coord.x <= rect.max().x + tolerance
+        && coord.y >= rect.min().y - tolerance
+        && coord.y <= rect.max().y + tolerance
+    };
+    let cells = r_range
+      .flat_map(|r| q_range.clone().map(move |q| CellCoord::new(q, r)))
+      .filter(|cell| inside(grid.center(*cell)))
+      .collect::<Vec<_>>();
+    grid.index = cells.iter().copied().collect();
+    grid.cells = cells;
+    grid
+  }
+
+  pub fn kind(&self) -> GridKind {
+    self.kind
+  }
+
+  pub fn size(&self) -> f32 {
+    self.size
+  }
+
+  pub fn cells(&self) -> &[CellCoord] {
+    &self.cells
+  }
+
+  pub fn contains(&self, cell: CellCoord) -> bool {
+    self.index.contains(&cell)
+  }
+
+  pub fn n_sides(&self) -> usize {
+    match self.kind {
+      GridKind::Square => 4,
+      GridKind::Hexagonal(_) => 6,
+      GridKind::Triangular => 3,
+    }
+  }
+
+  // Centroid of the cell
+  pub fn center(&self, cell: CellCoord) -> Coord<f32> {
+    let s = self.size;
+    let (q, r) = (cell.q as f32, cell.r as f32);
+    let offset: Coord<f32> = match self.kind {
+      GridKind::Square => (q * s, r * s).into(),
+      GridKind::Hexagonal(HexOrientation::Flat) => (1.5 * q * s, SQRT_3 * (r + q / 2.0) * s).into(),
+      GridKind::Hexagonal(HexOrientation::Pointy) => {
+        (SQRT_3 * (q + r / 2.0) * s, 1.5 * r * s).into()
+      }
+      GridKind::Triangular => {
+        let height = SQRT_3 / 2.0 * s;
+        let y = match is_up(cell) {
+          true => r * height + height / 3.0,
+          false => r * height + height * 2.0 / 3.0,
+        };
+        (q * s / 2.0, y).into()
+      }
+    };
+    self.origin + offset
+  }
+
+  pub fn vertices(&self, cell: CellCoord) -> Vec<Coord<f32>> {
+    let center = self.center(cell);
+    let s = self.size;
+    let around = |n_sides: usize, start: f32, radius: f32| {
+      (0..n_sides)
+        .map(|i| {
+          let angle = start + 2.0 * PI * i as f32 / n_sides as f32;
+          center + Coord::from((angle.cos(), angle.sin())) * radius
+        })
+        .collect()
+    };
+    match self.kind {
+      GridKind::Square => around(4, -3.0 * PI / 4.0, s / 2.0 * 2f32.sqrt()),
+      GridKind::Hexagonal(HexOrientation::Flat) => around(6, 0.0, s),
+      GridKind::Hexagonal(HexOrientation::Pointy) => around(6, -PI / 6.0, s),
+      GridKind::Triangular => match is_up(cell) {
+        true => around(3, -5.0 * PI / 6.0, s / SQRT_3),
+        false => around(3, -PI / 2.0, s / SQRT_3),
+      },
+    }
+  }
+
+  pub fn polygon(&self, cell: CellCoord) -> Polygon<f32> {
+    Polygon::new(LineString::new(self.vertices(cell)), vec![])
+  }
+
+  pub fn edge_midpoints(&self, cell: CellCoord) -> Vec<Coord<f32>> {
+    let vertices = self.vertices(cell);
+    (0..vertices.len())
+      .map(|i| (vertices[i] + vertices[(i + 1) % vertices.len()]) / 2.0)
+      .collect()
+  }
+
+  // Cell sharing the given edge, whether it belongs to the grid or not
+  pub fn across(&self, cell: CellCoord, edge: usize) -> CellCoord {
+    let (dq, dr) = match self.kind {
+      GridKind::Square => [(0, -1), (1, 0), (0, 1), (-1, 0)][edge % 4],
+      GridKind::Hexagonal(_) => [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)][edge % 6],
+      GridKind::Triangular => match is_up(cell) {
+        true => [(0, -1), (1, 0), (-1, 0)][edge % 3],
+        false => [(1, 0), (0, 1), (-1, 0)][edge % 3],
+      },
+    };
+    CellCoord::new(cell.q + dq, cell.r + dr)
+  }
+
+  // Cell of the grid sharing the given edge
+  pub fn neighbour(&self, cell: CellCoord, edge: usize) -> Option<CellCoord> {
+    Some(self.across(cell, edge)).filter(|neighbour| self.contains(*neighbour))
+  }
+
+  // Cells of the grid sharing an edge with the cell, in the order of the edges
+  pub fn neighbours(&self, cell: CellCoord) -> Vec<CellCoord> {
+    (0..self.n_sides())
+      .filter_map(|edge| self.neighbour(cell, edge))
+      .collect()
+  }
+
+  // Edge of the neighbour shared with the cell
+  pub fn opposite_edge(&self, cell: CellCoord, edge: usize) -> usize {
+    let neighbour = self.across(cell, edge);
+    (0..self.n_sides())
+      .find(|other| self.across(neighbour, *other) == cell)
+      .unwrap_or(0)
+  }
+
+  // Cell of the grid containing the point
+  pub fn cell_at(&self, coord: Coord<f32>) -> Option<CellCoord> {
+    let s = self.size;
+    let local = coord - self.origin;
+    let estimate = match self.kind {
+      GridKind::Square => {
+        CellCoord::new((local.x / s).round() as i32, (local.y / s).round() as i32)
+      }
+      GridKind::Hexagonal(HexOrientation::Flat) => {
+        let q = local.x * 2.0 / 3.0 / s;
+        let r = (-local.x / 3.0 + SQRT_3 / 3.0 * local.y) / s;
+        round_axial(q, r)
+      }
+      GridKind::Hexagonal(HexOrientation::Pointy) => {
+        let q = (SQRT_3 / 3.0 * local.x - local.y / 3.0) / s;
+        let r = local.y * 2.0 / 3.0 / s;
+        round_axial(q, r)
+      }
+      GridKind::Triangular => CellCoord::new(
+        (local.x * 2.0 / s).round() as i32,
+        (local.y / (SQRT_3 / 2.0 * s)).floor() as i32,
+      ),
+    };
+    // Points close to the edges may fall in a neighbour of the estimate
+    std::iter::once(estimate)
+      .chain((0..self.n_sides()).map(|edge| self.across(estimate, edge)))
+      .find(|cell| self.polygon(*cell).contains(&coord))
+      .or(Some(estimate))
+      .filter(|cell| self.contains(*cell))
+  }
+}
+
+fn is_up(cell: CellCoord) -> bool {
+  (cell.q + cell.r).rem_euclid(2) == 0
+}
+
+fn round_axial(q: f32, r: f32) -> CellCoord {
+  let s = -q - r;
+  let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+  let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+  if dq > dr && dq > ds {
+    rq = -rr - rs;
+  } else if dr > ds {
+    rr = -rq - rs;
+  }
+  CellCoord::new(rq as i32, rr as i32)
+}
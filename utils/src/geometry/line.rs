@@ -1,6 +1,10 @@
-use geo::{Coord, EuclideanLength, Line, LineInterpolatePoint, Rotate};
+use geo::{
+  Coord, EuclideanDistance, EuclideanLength, Line, LineInterpolatePoint, LineString, Polygon,
+  Rotate,
+};
 
 use super::coord::slant;
+use crate::noise::Noise2;
 
 pub fn sample_straight(
   start: Coord<f32>,
@@ -26,3 +30,215 @@ pub fn sample_wooble(
   let std_dev_y = std_dev * std_vec.dy() / line.euclidean_length();
   sample_straight(start, end, n_samples).map(move |coord| slant(coord, std_dev_x, std_dev_y))
 }
+
+// Same as sample_wooble with a smooth drift instead of independent jitter.
+// Offsets come from the noise sampled at each point position,
+// so that neighbouring lines drift alike as if drawn by the same hand.
+pub fn sample_smooth_wooble<'a, N: Noise2>(
+  start: Coord<f32>,
+  end: Coord<f32>,
+  n_samples: usize,
+  amplitude: f32,
+  frequency: f32,
+  noise: &'a N,
+) -> impl Iterator<Item = Coord<f32>> + 'a {
+  let normal = segment_normal(start, end);
+  sample_straight(start, end, n_samples).map(move |coord| {
+    coord + normal * (amplitude * noise.noise2(coord.x * frequency, coord.y * frequency))
+  })
+}
+
+// Moves every point of the line along its normal by the noise sampled at its position
+pub fn smooth_wooble<N: Noise2>(
+  line_string: &LineString<f32>,
+  amplitude: f32,
+  frequency: f32,
+  noise: &N,
+) -> LineString<f32> {
+  line_string
+    .coords()
+    .zip(normals(line_string))
+    .map(|(coord, normal)| {
+      *coord + normal * (amplitude * noise.noise2(coord.x * frequency, coord.y * frequency))
+    })
+    .collect()
+}
+
+#[derive(Clone, Copy)]
+pub enum Join {
+  // Sharp corners, replaced by a bevel when the miter is longer than limit * width
+  Miter(f32),
+  Bevel,
+  Round,
+}
+
+// Subdivides each segment so that consecutive points are at most spacing apart.
+// Original vertices are kept so corners stay sharp.
+pub fn densify(line_string: &LineString<f32>, spacing: f32) -> LineString<f32> {
+  let spacing = spacing.max(f32::EPSILON);
+  let mut coords: Vec<Coord<f32>> = line_string.coords().copied().collect();
+  coords.dedup();
+  if coords.len() < 2 {
+    return LineString::new(coords);
+  }
+  let last = coords.pop().unwrap();
+  let mut densified = vec![];
+  let mut start = coords[0];
+  coords
+    .iter()
+    .skip(1)
+    .chain(std::iter::once(&last))
+    .for_each(|end| {
+      let n_samples = (start.euclidean_distance(end) / spacing).ceil().max(1.0) as usize;
+      densified.extend(sample_straight(start, *end, n_samples).take(n_samples));
+      start = *end;
+    });
+  densified.push(last);
+  LineString::new(densified)
+}
+
+// Proportion of the total length travelled at each vertex, from 0.0 to 1.0
+pub fn proportions(line_string: &LineString<f32>) -> Vec<f32> {
+  let mut travelled = 0.0;
+  let mut lengths = vec![];
+  line_string
+    .coords()
+    .fold(None, |previous: Option<&Coord<f32>>, coord| {
+      if let Some(previous) = previous {
+        travelled += previous.euclidean_distance(coord);
+      }
+      lengths.push(travelled);
+      Some(coord)
+    });
+  lengths
+    .iter()
+    .map(|length| {
+      if travelled > 0.0 {
+        length / travelled
+      } else {
+        0.0
+      }
+    })
+    .collect()
+}
+
+// Unit normals at each vertex, pointing to the left of the direction of travel.
+// Interior vertices use the bisector of their two adjacent segments.
+pub fn normals(line_string: &LineString<f32>) -> Vec<Coord<f32>> {
+  let coords = &line_string.0;
+  (0..coords.len())
+    .map(|i| {
+      let incoming = i
+        .checked_sub(1)
+        .map(|j| segment_normal(coords[j], coords[i]));
+      let outgoing = coords
+        .get(i + 1)
+        .map(|next| segment_normal(coords[i], *next));
+      match (incoming, outgoing) {
+        (Some(incoming), Some(outgoing)) => {
+          let bisector = incoming + outgoing;
+          let norm = bisector.x.hypot(bisector.y);
+          if norm > f32::EPSILON {
+            bisector / norm
+          } else {
+            outgoing
+          }
+        }
+        (Some(normal), None) | (None, Some(normal)) => normal,
+        (None, None) => Coord::zero(),
+      }
+    })
+    .collect()
+}
+
+// Builds the outline of a variable width line, half_widths giving the half width at each vertex.
+// Caps are flat, use a width tapering to zero for pointy ends.
+// Consecutive duplicated points must be removed beforehand, see densify.
+pub fn outline(line_string: &LineString<f32>, half_widths: &[f32], join: Join) -> Polygon<f32> {
+  let coords = &line_string.0;
+  let n_coords = coords.len().min(half_widths.len());
+  if n_coords < 2 {
+    return Polygon::new(LineString::new(vec![]), vec![]);
+  }
+  let mut left = vec![];
+  let mut right = vec![];
+  (0..n_coords).for_each(|i| {
+    let coord = coords[i];
+    let width = half_widths[i];
+    let incoming = i.checked_sub(1).map(|j| segment_normal(coords[j], coord));
+    let outgoing = (i + 1 < n_coords).then(|| segment_normal(coord, coords[i + 1]));
+    match (incoming, outgoing) {
+      (Some(incoming), Some(outgoing)) => {
+        let bisector = incoming + outgoing;
+        let norm = bisector.x.hypot(bisector.y);
+        // Cosine of the half angle between the two segments
+        let cos = norm / 2.0;
+        let turns_left = incoming.x * outgoing.y - incoming.y * outgoing.x > 0.0;
+        let miter_ratio = if cos > f32::EPSILON {
+          1.0 / cos
+        } else {
+          f32::INFINITY
+        };
+        let inner_ratio = miter_ratio.min(INNER_MITER_LIMIT);
+        let inner = if norm > f32::EPSILON {
+          bisector / norm * (width * inner_ratio)
+        } else {
+          outgoing * width
+        };
+        let outer_side = match join {
+          Join::Miter(limit) if miter_ratio <= limit => {
+            vec![bisector / norm * (width * miter_ratio)]
+          }
+          Join::Miter(_) | Join::Bevel => vec![incoming * width, outgoing * width],
+          Join::Round => round_join(incoming, outgoing, width),
+        };
+        // The outer side of the corner is on the right when turning left
+        if turns_left {
+          left.push(coord + inner);
+          right.extend(outer_side.iter().map(|offset| coord - *offset));
+        } else {
+          left.extend(outer_side.iter().map(|offset| coord + *offset));
+          right.push(coord - inner);
+        }
+      }
+      (Some(normal), None) | (None, Some(normal)) => {
+        left.push(coord + normal * width);
+        right.push(coord - normal * width);
+      }
+      (None, None) => {}
+    }
+  });
+  right.reverse();
+  left.extend(right);
+  Polygon::new(LineString::new(left), vec![])
+}
+
+const INNER_MITER_LIMIT: f32 = 4.0;
+const ROUND_JOIN_STEP: f32 = 0.3;
+
+fn round_join(from: Coord<f32>, to: Coord<f32>, width: f32) -> Vec<Coord<f32>> {
+  let start = from.y.atan2(from.x);
+  let mut delta = to.y.atan2(to.x) - start;
+  if delta > std::f32::consts::PI {
+    delta -= 2.0 * std::f32::consts::PI;
+  } else if delta < -std::f32::consts::PI {
+    delta += 2.0 * std::f32::consts::PI;
+  }
+  let n_steps = (delta.abs() / ROUND_JOIN_STEP).ceil().max(1.0) as usize;
+  (0..=n_steps)
+    .map(|i| {
+      let theta = start + delta * i as f32 / n_steps as f32;
+      Coord::from((theta.cos(), theta.sin())) * width
+    })
+    .collect()
+}
+
+fn segment_normal(start: Coord<f32>, end: Coord<f32>) -> Coord<f32> {
+  let direction = end - start;
+  let norm = direction.x.hypot(direction.y);
+  if norm > f32::EPSILON {
+    Coord::from((-direction.y, direction.x)) / norm
+  } else {
+    Coord::zero()
+  }
+}
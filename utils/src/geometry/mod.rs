@@ -1,4 +1,9 @@
+pub mod broken_line;
 pub mod coord;
+pub mod curve;
 pub mod filling;
+pub mod grid;
 pub mod hatch;
 pub mod line;
+pub mod polygon;
+pub mod sdf;
@@ -0,0 +1,37 @@
+use geo::{Coord, EuclideanDistance, LineString, Polygon};
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+// Recursively inserts a displaced point in the middle of every edge of the polygon exterior.
+// Displacements are drawn from a normal law with standard deviation std_dev times the edge length,
+// which gives the irregular outlines of watercolour blobs.
+pub fn deform<R: Rng>(
+  polygon: &Polygon<f32>,
+  depth: usize,
+  std_dev: f32,
+  rng: &mut R,
+) -> Polygon<f32> {
+  let mut coords: Vec<Coord<f32>> = polygon.exterior().coords().copied().collect();
+  // Closed rings repeat their first point at the end
+  if coords.len() > 1 && coords.first() == coords.last() {
+    coords.pop();
+  }
+  let normal = Normal::new(0.0, std_dev.abs()).unwrap();
+  let position = Normal::new(0.5f32, 0.1).unwrap();
+  (0..depth).for_each(|_| {
+    coords = (0..coords.len())
+      .flat_map(|i| {
+        let start = coords[i];
+        let end = coords[(i + 1) % coords.len()];
+        let length = start.euclidean_distance(&end);
+        let t = position.sample(rng).clamp(0.0, 1.0);
+        let theta = rng.gen_range(0.0..std::f32::consts::TAU);
+        let displacement = length * normal.sample(rng);
+        let middle =
+          start + (end - start) * t + Coord::from((theta.cos(), theta.sin())) * displacement;
+        [start, middle]
+      })
+      .collect();
+  });
+  Polygon::new(LineString::new(coords), vec![])
+}
@@ -0,0 +1,508 @@
+use geo::{Coord, Line, MultiLineString, MultiPolygon, Polygon, Rect};
+
+use super::hatch::hatch;
+use crate::{
+  algorithm::marching_squares::{band, contours, isolines},
+  data_structures::scalar_grid::ScalarGrid,
+  noise::Noise2,
+};
+
+// Offset between the two noise samples displacing the x and y coordinates
+const WARP_OFFSET: f32 = 31.7;
+
+// Signed distance to a shape: negative inside, positive outside. Combinations of shapes may only
+// give a bound of the distance, which still describes the shape through its sign.
+pub trait Sdf {
+  fn distance(&self, coord: Coord<f32>) -> f32;
+
+  fn union<S: Sdf>(self, other: S) -> Union<Self, S>
+  where
+    Self: Sized,
+  {
+    Union(self, other)
+  }
+
+  fn intersection<S: Sdf>(self, other: S) -> Intersection<Self, S>
+  where
+    Self: Sized,
+  {
+    Intersection(self, other)
+  }
+
+  fn difference<S: Sdf>(self, other: S) -> Difference<Self, S>
+  where
+    Self: Sized,
+  {
+    Difference(self, other)
+  }
+
+  // Union blending the shapes over a distance of about k
+  fn smooth_union<S: Sdf>(self, other: S, k: f32) -> SmoothUnion<Self, S>
+  where
+    Self: Sized,
+  {
+    SmoothUnion(self, other, k)
+  }
+
+  fn smooth_intersection<S: Sdf>(self, other: S, k: f32) -> SmoothIntersection<Self, S>
+  where
+    Self: Sized,
+  {
+    SmoothIntersection(self, other, k)
+  }
+
+  fn smooth_difference<S: Sdf>(self, other: S, k: f32) -> SmoothDifference<Self, S>
+  where
+    Self: Sized,
+  {
+    SmoothDifference(self, other, k)
+  }
+
+  fn translate(self, offset: Coord<f32>) -> Translate<Self>
+  where
+    Self: Sized,
+  {
+    Translate(self, offset)
+  }
+
+  // Rotation around the origin
+  fn rotate(self, degrees: f32) -> Rotate<Self>
+  where
+    Self: Sized,
+  {
+    Rotate(self, degrees.to_radians())
+  }
+
+  // Scaling from the origin
+  fn scale(self, factor: f32) -> Scale<Self>
+  where
+    Self: Sized,
+  {
+    Scale(self, factor)
+  }
+
+  // Infinite copies of the shape every period, a zero period leaving the axis untouched.
+  // The shape should fit in a period centered on the origin.
+  fn repeat(self, period: Coord<f32>) -> Repeat<Self>
+  where
+    Self: Sized,
+  {
+    Repeat(self, period)
+  }
+
+  // Displaces the plane by the noise before evaluating the shape
+  fn warp<N: Noise2>(self, noise: N, frequency: f32, strength: f32) -> Warp<Self, N>
+  where
+    Self: Sized,
+  {
+    Warp {
+      sdf: self,
+      noise,
+      frequency,
+      strength,
+    }
+  }
+
+  // Shape grown by radius, rounding its corners
+  fn round(self, radius: f32) -> Round<Self>
+  where
+    Self: Sized,
+  {
+    Round(self, radius)
+  }
+
+  // Ring of the given thickness along the border of the shape
+  fn onion(self, thickness: f32) -> Onion<Self>
+  where
+    Self: Sized,
+  {
+    Onion(self, thickness)
+  }
+}
+
+impl<S: Sdf + ?Sized> Sdf for Box<S> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    self.as_ref().distance(coord)
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Circle {
+  pub center: Coord<f32>,
+  pub radius: f32,
+}
+
+impl Circle {
+  pub fn new(center: Coord<f32>, radius: f32) -> Self {
+    Self { center, radius }
+  }
+}
+
+impl Sdf for Circle {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    length(coord - self.center) - self.radius
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Rectangle {
+  pub rect: Rect<f32>,
+}
+
+impl Rectangle {
+  pub fn new(rect: Rect<f32>) -> Self {
+    Self { rect }
+  }
+}
+
+impl Sdf for Rectangle {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    box_distance(coord - self.rect.center(), half_size(&self.rect))
+  }
+}
+
+// Rectangle whose corners are rounded with the given radius, staying inside rect
+#[derive(Clone, Copy)]
+pub struct RoundedRectangle {
+  pub rect: Rect<f32>,
+  pub radius: f32,
+}
+
+impl RoundedRectangle {
+  pub fn new(rect: Rect<f32>, radius: f32) -> Self {
+    Self { rect, radius }
+  }
+}
+
+impl Sdf for RoundedRectangle {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    let half_size = half_size(&self.rect);
+    let radius = self.radius.clamp(0.0, half_size.x.min(half_size.y));
+    let inner = half_size - Coord::from((radius, radius));
+    box_distance(coord - self.rect.center(), inner) - radius
+  }
+}
+
+// Segment drawn with the given width and round caps
+#[derive(Clone, Copy)]
+pub struct Segment {
+  pub start: Coord<f32>,
+  pub end: Coord<f32>,
+  pub width: f32,
+}
+
+impl Segment {
+  pub fn new(start: Coord<f32>, end: Coord<f32>, width: f32) -> Self {
+    Self { start, end, width }
+  }
+}
+
+impl Sdf for Segment {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    segment_distance(coord, self.start, self.end) - self.width / 2.0
+  }
+}
+
+// Polygon with its holes, inside points being found with the even-odd rule
+#[derive(Clone)]
+pub struct PolygonShape {
+  pub polygon: Polygon<f32>,
+}
+
+impl PolygonShape {
+  pub fn new(polygon: Polygon<f32>) -> Self {
+    Self { polygon }
+  }
+}
+
+impl Sdf for PolygonShape {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    let mut inside = false;
+    let distance = std::iter::once(self.polygon.exterior())
+      .chain(self.polygon.interiors())
+      .flat_map(|ring| ring.lines())
+      .fold(f32::INFINITY, |distance, line| {
+        let (start, end) = (line.start, line.end);
+        // Crossings of the horizontal ray going right from coord
+        if (start.y > coord.y) != (end.y > coord.y)
+          && coord.x < start.x + (coord.y - start.y) / (end.y - start.y) * (end.x - start.x)
+        {
+          inside = !inside;
+        }
+        distance.min(segment_distance(coord, start, end))
+      });
+    if inside {
+      -distance
+    } else {
+      distance
+    }
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Union<A, B>(A, B);
+
+impl<A: Sdf, B: Sdf> Sdf for Union<A, B> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    self.0.distance(coord).min(self.1.distance(coord))
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Intersection<A, B>(A, B);
+
+impl<A: Sdf, B: Sdf> Sdf for Intersection<A, B> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    self.0.distance(coord).max(self.1.distance(coord))
+  }
+}
+
+// First shape with the second one cut out
+#[derive(Clone, Copy)]
+pub struct Difference<A, B>(A, B);
+
+impl<A: Sdf, B: Sdf> Sdf for Difference<A, B> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    self.0.distance(coord).max(-self.1.distance(coord))
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct SmoothUnion<A, B>(A, B, f32);
+
+impl<A: Sdf, B: Sdf> Sdf for SmoothUnion<A, B> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    smooth_min(self.0.distance(coord), self.1.distance(coord), self.2)
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct SmoothIntersection<A, B>(A, B, f32);
+
+impl<A: Sdf, B: Sdf> Sdf for SmoothIntersection<A, B> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    -smooth_min(-self.0.distance(coord), -self.1.distance(coord), self.2)
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct SmoothDifference<A, B>(A, B, f32);
+
+impl<A: Sdf, B: Sdf> Sdf for SmoothDifference<A, B> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    -smooth_min(-self.0.distance(coord), self.1.distance(coord), self.2)
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Translate<S>(S, Coord<f32>);
+
+impl<S: Sdf> Sdf for Translate<S> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    self.0.distance(coord - self.1)
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Rotate<S>(S, f32);
+
+impl<S: Sdf> Sdf for Rotate<S> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    let (sin, cos) = (-self.1).sin_cos();
+    self.0.distance(Coord::from((
+      coord.x * cos - coord.y * sin,
+      coord.x * sin + coord.y * cos,
+    )))
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Scale<S>(S, f32);
+
+impl<S: Sdf> Sdf for Scale<S> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    let factor = match self.1.abs() > f32::EPSILON {
+      true => self.1,
+      false => f32::EPSILON,
+    };
+    self.0.distance(coord / factor) * factor
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Repeat<S>(S, Coord<f32>);
+
+impl<S: Sdf> Sdf for Repeat<S> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    let wrap = |value: f32, period: f32| match period > 0.0 {
+      true => value - period * (value / period).round(),
+      false => value,
+    };
+    self.0.distance(Coord::from((
+      wrap(coord.x, self.1.x),
+      wrap(coord.y, self.1.y),
+    )))
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Warp<S, N> {
+  sdf: S,
+  noise: N,
+  frequency: f32,
+  strength: f32,
+}
+
+impl<S: Sdf, N: Noise2> Sdf for Warp<S, N> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    let (x, y) = (coord.x * self.frequency, coord.y * self.frequency);
+    let offset = Coord::from((
+      self.noise.noise2(x, y),
+      self.noise.noise2(x + WARP_OFFSET, y + WARP_OFFSET),
+    ));
+    self.sdf.distance(coord + offset * self.strength)
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Round<S>(S, f32);
+
+impl<S: Sdf> Sdf for Round<S> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    self.0.distance(coord) - self.1
+  }
+}
+
+#[derive(Clone, Copy)]
+pub struct Onion<S>(S, f32);
+
+impl<S: Sdf> Sdf for Onion<S> {
+  fn distance(&self, coord: Coord<f32>) -> f32 {
+    self.0.distance(coord).abs() - self.1 / 2.0
+  }
+}
+
+// Distances sampled every spacing over rect
+pub fn sample<S: Sdf>(sdf: &S, rect: Rect<f32>, spacing: f32) -> ScalarGrid {
+  ScalarGrid::from_fn(rect, spacing, |coord| sdf.distance(coord))
+}
+
+// Border of the shape inside rect, precise up to the sampling spacing
+pub fn outline<S: Sdf>(sdf: &S, rect: Rect<f32>, spacing: f32) -> MultiLineString<f32> {
+  isolines(&sample(sdf, rect, spacing), 0.0)
+}
+
+// Inside of the shape as polygons, e.g. for the fillings of utils::draw::filling
+pub fn polygons<S: Sdf>(sdf: &S, rect: Rect<f32>, spacing: f32) -> MultiPolygon<f32> {
+  band(&sample(sdf, rect, spacing), f32::NEG_INFINITY, 0.0)
+}
+
+// Outline then lines shrunk every gap towards the inside of the shape until it vanishes
+pub fn offsets<S: Sdf>(
+  sdf: &S,
+  rect: Rect<f32>,
+  spacing: f32,
+  gap: f32,
+) -> Vec<MultiLineString<f32>> {
+  let grid = sample(sdf, rect, spacing);
+  let (min, _) = grid.min_max();
+  let gap = gap.max(spacing);
+  let levels = (0..)
+    .map(|i| -(i as f32) * gap)
+    .take_while(|level| *level > min)
+    .collect::<Vec<_>>();
+  contours(&grid, &levels)
+}
+
+// Parts of the segment inside the shape, borders being found up to tolerance
+pub fn clip<S: Sdf>(sdf: &S, start: Coord<f32>, end: Coord<f32>, tolerance: f32) -> Vec<Line<f32>> {
+  let direction = end - start;
+  let length = length(direction);
+  let n_steps = (length / tolerance.max(f32::EPSILON)).ceil().max(1.0) as usize;
+  let at = |t: f32| start + direction * t;
+  let inside = |t: f32| sdf.distance(at(t)) < 0.0;
+  // Bisection of the interval where the inside test changes
+  let border = |mut a: f32, mut b: f32| {
+    let inside_a = inside(a);
+    while (b - a) * length > tolerance / 4.0 {
+      let middle = (a + b) / 2.0;
+      if inside(middle) == inside_a {
+        a = middle;
+      } else {
+        b = middle;
+      }
+    }
+    (a + b) / 2.0
+  };
+
+  let mut lines = vec![];
+  let mut entry = inside(0.0).then_some(0.0);
+  (1..=n_steps).for_each(|step| {
+    let (previous, t) = (
+      (step - 1) as f32 / n_steps as f32,
+      step as f32 / n_steps as f32,
+    );
+    match (entry, inside(t)) {
+      (None, true) => entry = Some(border(previous, t)),
+      (Some(t_entry), false) => {
+        lines.push(Line::new(at(t_entry), at(border(previous, t))));
+        entry = None;
+      }
+      _ => {}
+    }
+  });
+  if let Some(t_entry) = entry {
+    lines.push(Line::new(at(t_entry), end));
+  }
+  lines
+}
+
+// Hatches of rect masked by the inside of the shape, density and degrees as in hatch
+pub fn hatch_mask<S: Sdf>(
+  sdf: &S,
+  rect: Rect<f32>,
+  density: f32,
+  degrees: f32,
+  tolerance: f32,
+) -> Vec<Line<f32>> {
+  hatch(rect.to_polygon(), density, degrees)
+    .flat_map(|(start, end)| clip(sdf, start, end, tolerance))
+    .collect()
+}
+
+fn length(coord: Coord<f32>) -> f32 {
+  coord.x.hypot(coord.y)
+}
+
+fn half_size(rect: &Rect<f32>) -> Coord<f32> {
+  Coord::from((rect.width() / 2.0, rect.height() / 2.0))
+}
+
+// Distance to an axis aligned box centered on the origin
+fn box_distance(coord: Coord<f32>, half_size: Coord<f32>) -> f32 {
+  let q = Coord::from((coord.x.abs() - half_size.x, coord.y.abs() - half_size.y));
+  let outside = length(Coord::from((q.x.max(0.0), q.y.max(0.0))));
+  outside + q.x.max(q.y).min(0.0)
+}
+
+fn segment_distance(coord: Coord<f32>, start: Coord<f32>, end: Coord<f32>) -> f32 {
+  let direction = end - start;
+  let squared_length = direction.x * direction.x + direction.y * direction.y;
+  let t = match squared_length > f32::EPSILON {
+    true => {
+      let along = (coord - start).x * direction.x + (coord - start).y * direction.y;
+      (along / squared_length).clamp(0.0, 1.0)
+    }
+    false => 0.0,
+  };
+  length(coord - (start + direction * t))
+}
+
+// Polynomial smooth minimum, equal to min(a, b) when they are more than k apart
+fn smooth_min(a: f32, b: f32, k: f32) -> f32 {
+  if k <= 0.0 {
+    return a.min(b);
+  }
+  let h = (k - (a - b).abs()).max(0.0) / k;
+  a.min(b) - h * h * k / 4.0
+}
@@ -3,3 +3,4 @@ pub mod app;
 pub mod data_structures;
 pub mod draw;
 pub mod geometry;
+pub mod noise;
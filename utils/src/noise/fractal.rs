@@ -0,0 +1,217 @@
+use super::{Noise2, Noise3, Noise4};
+
+// Shift applied to each octave so that they do not all share the same lattice origin
+const OCTAVE_OFFSET: f32 = 17.31;
+
+// Fractal Brownian motion: sum of octaves of the source noise
+// with increasing frequency (lacunarity) and decreasing amplitude (gain)
+pub struct Fbm<N> {
+  source: N,
+  octaves: usize,
+  lacunarity: f32,
+  gain: f32,
+}
+
+impl<N> Fbm<N> {
+  pub fn new(source: N) -> Self {
+    Self {
+      source,
+      octaves: 4,
+      lacunarity: 2.0,
+      gain: 0.5,
+    }
+  }
+
+  pub fn octaves(mut self, octaves: usize) -> Self {
+    self.octaves = octaves.max(1);
+    self
+  }
+
+  pub fn lacunarity(mut self, lacunarity: f32) -> Self {
+    self.lacunarity = lacunarity;
+    self
+  }
+
+  pub fn gain(mut self, gain: f32) -> Self {
+    self.gain = gain;
+    self
+  }
+
+  // Sums the octaves, sample being called with the octave frequency and offset
+  fn fractal<F: Fn(f32, f32) -> f32>(&self, sample: F) -> f32 {
+    let (mut frequency, mut amplitude) = (1.0, 1.0);
+    let (mut sum, mut total_amplitude) = (0.0, 0.0);
+    (0..self.octaves).for_each(|octave| {
+      sum += amplitude * sample(frequency, octave as f32 * OCTAVE_OFFSET);
+      total_amplitude += amplitude;
+      frequency *= self.lacunarity;
+      amplitude *= self.gain;
+    });
+    sum / total_amplitude
+  }
+}
+
+impl<N: Noise2> Noise2 for Fbm<N> {
+  fn noise2(&self, x: f32, y: f32) -> f32 {
+    self.fractal(|frequency, offset| {
+      self
+        .source
+        .noise2(x * frequency + offset, y * frequency + offset)
+    })
+  }
+}
+
+impl<N: Noise3> Noise3 for Fbm<N> {
+  fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+    self.fractal(|frequency, offset| {
+      self.source.noise3(
+        x * frequency + offset,
+        y * frequency + offset,
+        z * frequency + offset,
+      )
+    })
+  }
+}
+
+impl<N: Noise4> Noise4 for Fbm<N> {
+  fn noise4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+    self.fractal(|frequency, offset| {
+      self.source.noise4(
+        x * frequency + offset,
+        y * frequency + offset,
+        z * frequency + offset,
+        w * frequency + offset,
+      )
+    })
+  }
+}
+
+// Ridged multifractal: octaves of 1 - |noise| give sharp crests, like mountain ridges or veins
+pub struct Ridged<N> {
+  fbm: Fbm<N>,
+}
+
+impl<N> Ridged<N> {
+  pub fn new(source: N) -> Self {
+    Self {
+      fbm: Fbm::new(source),
+    }
+  }
+
+  pub fn octaves(mut self, octaves: usize) -> Self {
+    self.fbm = self.fbm.octaves(octaves);
+    self
+  }
+
+  pub fn lacunarity(mut self, lacunarity: f32) -> Self {
+    self.fbm = self.fbm.lacunarity(lacunarity);
+    self
+  }
+
+  pub fn gain(mut self, gain: f32) -> Self {
+    self.fbm = self.fbm.gain(gain);
+    self
+  }
+}
+
+fn ridge(value: f32) -> f32 {
+  let ridge = 1.0 - value.abs();
+  // Map back from [0, 1] to [-1, 1]
+  2.0 * ridge * ridge - 1.0
+}
+
+impl<N: Noise2> Noise2 for Ridged<N> {
+  fn noise2(&self, x: f32, y: f32) -> f32 {
+    self.fbm.fractal(|frequency, offset| {
+      ridge(
+        self
+          .fbm
+          .source
+          .noise2(x * frequency + offset, y * frequency + offset),
+      )
+    })
+  }
+}
+
+impl<N: Noise3> Noise3 for Ridged<N> {
+  fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+    self.fbm.fractal(|frequency, offset| {
+      ridge(self.fbm.source.noise3(
+        x * frequency + offset,
+        y * frequency + offset,
+        z * frequency + offset,
+      ))
+    })
+  }
+}
+
+impl<N: Noise4> Noise4 for Ridged<N> {
+  fn noise4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+    self.fbm.fractal(|frequency, offset| {
+      ridge(self.fbm.source.noise4(
+        x * frequency + offset,
+        y * frequency + offset,
+        z * frequency + offset,
+        w * frequency + offset,
+      ))
+    })
+  }
+}
+
+// Domain warping: the source is sampled at coordinates displaced by the warp noise
+pub struct DomainWarp<N, W> {
+  source: N,
+  warp: W,
+  strength: f32,
+}
+
+// Offsets decorrelating the warp along each axis
+const WARP_OFFSETS: [f32; 4] = [0.0, 5.2, 1.3, 8.7];
+
+impl<N, W> DomainWarp<N, W> {
+  pub fn new(source: N, warp: W, strength: f32) -> Self {
+    Self {
+      source,
+      warp,
+      strength,
+    }
+  }
+}
+
+impl<N: Noise2, W: Noise2> Noise2 for DomainWarp<N, W> {
+  fn noise2(&self, x: f32, y: f32) -> f32 {
+    let [dx, dy] =
+      [WARP_OFFSETS[0], WARP_OFFSETS[1]].map(|offset| self.warp.noise2(x + offset, y + offset));
+    self
+      .source
+      .noise2(x + self.strength * dx, y + self.strength * dy)
+  }
+}
+
+impl<N: Noise3, W: Noise3> Noise3 for DomainWarp<N, W> {
+  fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+    let [dx, dy, dz] = [WARP_OFFSETS[0], WARP_OFFSETS[1], WARP_OFFSETS[2]]
+      .map(|offset| self.warp.noise3(x + offset, y + offset, z + offset));
+    self.source.noise3(
+      x + self.strength * dx,
+      y + self.strength * dy,
+      z + self.strength * dz,
+    )
+  }
+}
+
+impl<N: Noise4, W: Noise4> Noise4 for DomainWarp<N, W> {
+  fn noise4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+    let [dx, dy, dz, dw] = WARP_OFFSETS.map(|offset| {
+      self
+        .warp
+        .noise4(x + offset, y + offset, z + offset, w + offset)
+    });
+    self.source.noise4(
+      x + self.strength * dx,
+      y + self.strength * dy,
+      z + self.strength * dz,
+      w + self.strength * dw,
+    )
+  }
+}
@@ -0,0 +1,82 @@
+mod fractal;
+mod perlin;
+mod simplex;
+mod worley;
+
+pub use fractal::{DomainWarp, Fbm, Ridged};
+pub use perlin::Perlin;
+pub use simplex::Simplex;
+pub use worley::{Worley, WorleyFeature};
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use std::f32::consts::TAU;
+
+// Noise functions return values roughly in [-1, 1], except Worley which returns distances
+pub trait Noise2 {
+  fn noise2(&self, x: f32, y: f32) -> f32;
+}
+
+pub trait Noise3 {
+  fn noise3(&self, x: f32, y: f32, z: f32) -> f32;
+}
+
+pub trait Noise4 {
+  fn noise4(&self, x: f32, y: f32, z: f32, w: f32) -> f32;
+}
+
+// Samples the noise on a circle so that t = 0.0 and t = 1.0 give the same value.
+// Useful to animate a single value over a looping animation.
+pub fn loop1<N: Noise2>(noise: &N, t: f32, radius: f32) -> f32 {
+  let (sin, cos) = (TAU * t).sin_cos();
+  noise.noise2(radius * cos, radius * sin)
+}
+
+// Samples a 2D field moving along a circle in the two extra dimensions.
+// The field at t = 0.0 and t = 1.0 is the same, which makes seamless looping animations.
+pub fn loop2<N: Noise4>(noise: &N, x: f32, y: f32, t: f32, radius: f32) -> f32 {
+  let (sin, cos) = (TAU * t).sin_cos();
+  noise.noise4(x, y, radius * cos, radius * sin)
+}
+
+// Seeded permutation of [0, 256) repeated twice to avoid index wrapping
+struct Permutation {
+  values: Vec<usize>,
+}
+
+impl Permutation {
+  fn new(seed: u64) -> Self {
+    let mut values: Vec<usize> = (0..256).collect();
+    values.shuffle(&mut StdRng::seed_from_u64(seed));
+    values.extend_from_within(..);
+    Self { values }
+  }
+
+  // Hash of lattice coordinates in [0, 256)
+  fn hash(&self, coords: &[i32]) -> usize {
+    coords
+      .iter()
+      .fold(0, |hash, coord| self.values[hash + (*coord & 255) as usize])
+  }
+}
+
+fn fade(t: f32) -> f32 {
+  t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f32, a: f32, b: f32) -> f32 {
+  a + t * (b - a)
+}
+
+// Gradients pointing to the middle of the hypercube edges
+fn grad4(hash: usize, x: f32, y: f32, z: f32, w: f32) -> f32 {
+  let h = hash & 31;
+  let (a, b, c) = match h >> 3 {
+    0 => (y, z, w),
+    1 => (x, z, w),
+    2 => (x, y, w),
+    _ => (x, y, z),
+  };
+  (if h & 4 == 0 { a } else { -a })
+    + (if h & 2 == 0 { b } else { -b })
+    + (if h & 1 == 0 { c } else { -c })
+}
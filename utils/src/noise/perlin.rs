@@ -0,0 +1,110 @@
+use super::{fade, grad4, lerp, Noise2, Noise3, Noise4, Permutation};
+
+// Improved Perlin gradient noise
+pub struct Perlin {
+  permutation: Permutation,
+}
+
+impl Perlin {
+  pub fn new(seed: u64) -> Self {
+    Self {
+      permutation: Permutation::new(seed),
+    }
+  }
+}
+
+impl Noise2 for Perlin {
+  fn noise2(&self, x: f32, y: f32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (xf, yf) = (x - x0, y - y0);
+    let (i, j) = (x0 as i32, y0 as i32);
+    let gradient = |di: i32, dj: i32| {
+      let hash = self.permutation.hash(&[i + di, j + dj]);
+      grad2(hash, xf - di as f32, yf - dj as f32)
+    };
+    let (u, v) = (fade(xf), fade(yf));
+    lerp(
+      v,
+      lerp(u, gradient(0, 0), gradient(1, 0)),
+      lerp(u, gradient(0, 1), gradient(1, 1)),
+    )
+  }
+}
+
+impl Noise3 for Perlin {
+  fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+    let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+    let (xf, yf, zf) = (x - x0, y - y0, z - z0);
+    let (i, j, k) = (x0 as i32, y0 as i32, z0 as i32);
+    let gradient = |di: i32, dj: i32, dk: i32| {
+      let hash = self.permutation.hash(&[i + di, j + dj, k + dk]);
+      grad3(hash, xf - di as f32, yf - dj as f32, zf - dk as f32)
+    };
+    let (u, v, w) = (fade(xf), fade(yf), fade(zf));
+    let layer = |dk: i32| {
+      lerp(
+        v,
+        lerp(u, gradient(0, 0, dk), gradient(1, 0, dk)),
+        lerp(u, gradient(0, 1, dk), gradient(1, 1, dk)),
+      )
+    };
+    lerp(w, layer(0), layer(1))
+  }
+}
+
+impl Noise4 for Perlin {
+  fn noise4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+    let (x0, y0, z0, w0) = (x.floor(), y.floor(), z.floor(), w.floor());
+    let (xf, yf, zf, wf) = (x - x0, y - y0, z - z0, w - w0);
+    let (i, j, k, l) = (x0 as i32, y0 as i32, z0 as i32, w0 as i32);
+    let gradient = |di: i32, dj: i32, dk: i32, dl: i32| {
+      let hash = self.permutation.hash(&[i + di, j + dj, k + dk, l + dl]);
+      grad4(
+        hash,
+        xf - di as f32,
+        yf - dj as f32,
+        zf - dk as f32,
+        wf - dl as f32,
+      )
+    };
+    let (s, t, u, v) = (fade(xf), fade(yf), fade(zf), fade(wf));
+    let layer = |dk: i32, dl: i32| {
+      lerp(
+        t,
+        lerp(s, gradient(0, 0, dk, dl), gradient(1, 0, dk, dl)),
+        lerp(s, gradient(0, 1, dk, dl), gradient(1, 1, dk, dl)),
+      )
+    };
+    lerp(
+      v,
+      lerp(u, layer(0, 0), layer(1, 0)),
+      lerp(u, layer(0, 1), layer(1, 1)),
+    )
+  }
+}
+
+// Gradients are the unit directions and diagonals, scaled so that the output stays within [-1, 1]
+fn grad2(hash: usize, x: f32, y: f32) -> f32 {
+  match hash & 7 {
+    0 => x + y,
+    1 => -x + y,
+    2 => x - y,
+    3 => -x - y,
+    4 => x,
+    5 => -x,
+    6 => y,
+    _ => -y,
+  }
+}
+
+// Gradients pointing to the middle of the cube edges
+fn grad3(hash: usize, x: f32, y: f32, z: f32) -> f32 {
+  let h = hash & 15;
+  let u = if h < 8 { x } else { y };
+  let v = match h {
+    0..=3 => y,
+    12 | 14 => x,
+    _ => z,
+  };
+  (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
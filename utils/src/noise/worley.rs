@@ -0,0 +1,82 @@
+use super::{Noise2, Noise3, Permutation};
+
+#[derive(Clone, Copy)]
+pub enum WorleyFeature {
+  // Distance to the closest feature point, gives round cells
+  F1,
+  // Distance to the second closest feature point
+  F2,
+  // Difference between both, gives the cell borders
+  F2MinusF1,
+}
+
+// Worley (cellular) noise: distance to random feature points, one per lattice cell
+pub struct Worley {
+  permutation: Permutation,
+  feature: WorleyFeature,
+}
+
+impl Worley {
+  pub fn new(seed: u64) -> Self {
+    Self {
+      permutation: Permutation::new(seed),
+      feature: WorleyFeature::F1,
+    }
+  }
+
+  pub fn feature(mut self, feature: WorleyFeature) -> Self {
+    self.feature = feature;
+    self
+  }
+
+  // Position of the feature point inside a cell, along the given axis
+  fn jitter(&self, cell: &[i32], axis: i32) -> f32 {
+    let mut coords = cell.to_vec();
+    coords.push(axis);
+    let high = self.permutation.hash(&coords);
+    coords.push(axis + 1);
+    let low = self.permutation.hash(&coords);
+    (high * 256 + low) as f32 / 65536.0
+  }
+
+  fn select(&self, closest: f32, second_closest: f32) -> f32 {
+    match self.feature {
+      WorleyFeature::F1 => closest,
+      WorleyFeature::F2 => second_closest,
+      WorleyFeature::F2MinusF1 => second_closest - closest,
+    }
+  }
+}
+
+impl Noise2 for Worley {
+  fn noise2(&self, x: f32, y: f32) -> f32 {
+    let (i, j) = (x.floor() as i32, y.floor() as i32);
+    let mut distances = (-1..=1)
+      .flat_map(|di| (-1..=1).map(move |dj| (i + di, j + dj)))
+      .map(|(ci, cj)| {
+        let dx = ci as f32 + self.jitter(&[ci, cj], 0) - x;
+        let dy = cj as f32 + self.jitter(&[ci, cj], 2) - y;
+        (dx * dx + dy * dy).sqrt()
+      })
+      .collect::<Vec<_>>();
+    distances.sort_by(f32::total_cmp);
+    self.select(distances[0], distances[1])
+  }
+}
+
+impl Noise3 for Worley {
+  fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+    let (i, j, k) = (x.floor() as i32, y.floor() as i32, z.floor() as i32);
+    let mut distances = (-1..=1)
+      .flat_map(|di| (-1..=1).flat_map(move |dj| (-1..=1).map(move |dk| (i + di, j + dj, k + dk))))
+      .map(|(ci, cj, ck)| {
+        let dx = ci as f32 + self.jitter(&[ci, cj, ck], 0) - x;
+        let dy = cj as f32 + self.jitter(&[ci, cj, ck], 2) - y;
+        let dz = ck as f32 + self.jitter(&[ci, cj, ck], 4) - z;
+        (dx * dx + dy * dy + dz * dz).sqrt()
+      })
+      .collect::<Vec<_>>();
+    distances.sort_by(f32::total_cmp);
+    self.select(distances[0], distances[1])
+  }
+}